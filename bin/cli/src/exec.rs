@@ -155,6 +155,12 @@ pub async fn run(execution: Execution) -> anyhow::Result<()> {
     Execution::PruneVolumes(data) => {
       println!("{}: {data:?}", "Data".dimmed())
     }
+    Execution::BackupVolume(data) => {
+      println!("{}: {data:?}", "Data".dimmed())
+    }
+    Execution::RestoreVolume(data) => {
+      println!("{}: {data:?}", "Data".dimmed())
+    }
     Execution::PruneDockerBuilders(data) => {
       println!("{}: {data:?}", "Data".dimmed())
     }
@@ -389,6 +395,14 @@ pub async fn run(execution: Execution) -> anyhow::Result<()> {
       .execute(request)
       .await
       .map(ExecutionResult::Single),
+    Execution::BackupVolume(request) => komodo_client()
+      .execute(request)
+      .await
+      .map(ExecutionResult::Single),
+    Execution::RestoreVolume(request) => komodo_client()
+      .execute(request)
+      .await
+      .map(ExecutionResult::Single),
     Execution::PruneDockerBuilders(request) => komodo_client()
       .execute(request)
       .await
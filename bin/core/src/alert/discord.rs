@@ -69,6 +69,20 @@ pub async fn send_alert(
         "{level} | **{name}**{region} memory usage at **{percentage:.1}%** 💾\n\nUsing **{used_gb:.1} GiB** / **{total_gb:.1} GiB**\n{link}"
       )
     }
+    AlertData::ServerSwap {
+      id,
+      name,
+      region,
+      used_gb,
+      total_gb,
+    } => {
+      let region = fmt_region(region);
+      let link = resource_link(ResourceTargetVariant::Server, id);
+      let percentage = 100.0 * used_gb / total_gb;
+      format!(
+        "{level} | **{name}**{region} swap usage at **{percentage:.1}%** 💾\n\nUsing **{used_gb:.1} GiB** / **{total_gb:.1} GiB**\n{link}"
+      )
+    }
     AlertData::ServerDisk {
       id,
       name,
@@ -84,6 +98,60 @@ pub async fn send_alert(
         "{level} | **{name}**{region} disk usage at **{percentage:.1}%** 💿\nmount point: `{path:?}`\nusing **{used_gb:.1} GiB** / **{total_gb:.1} GiB**\n{link}"
       )
     }
+    AlertData::ServerDiskInodes {
+      id,
+      name,
+      region,
+      path,
+      used,
+      total,
+    } => {
+      let region = fmt_region(region);
+      let link = resource_link(ResourceTargetVariant::Server, id);
+      let percentage = 100.0 * *used as f64 / *total as f64;
+      format!(
+        "{level} | **{name}**{region} inode usage at **{percentage:.1}%** 💿\nmount point: `{path:?}`\nusing **{used}** / **{total}** inodes\n{link}"
+      )
+    }
+    AlertData::ServerDiskForecast {
+      id,
+      name,
+      region,
+      path,
+      used_gb,
+      total_gb,
+      growth_gb_per_day,
+      days_until_full,
+    } => {
+      let region = fmt_region(region);
+      let link = resource_link(ResourceTargetVariant::Server, id);
+      match alert.level {
+        SeverityLevel::Ok => format!(
+          "{level} | **{name}**{region} disk no longer forecast to be full soon 💿\nmount point: `{path:?}`\nusing **{used_gb:.1} GiB** / **{total_gb:.1} GiB**\n{link}"
+        ),
+        _ => format!(
+          "{level} | **{name}**{region} disk will be full in **{days_until_full:.1} days** 💿\nmount point: `{path:?}`\nusing **{used_gb:.1} GiB** / **{total_gb:.1} GiB**, growing **{growth_gb_per_day:.2} GiB** / day\n{link}"
+        ),
+      }
+    }
+    AlertData::PeripheryVersionMismatch {
+      id,
+      name,
+      region,
+      periphery_version,
+      core_version,
+    } => {
+      let region = fmt_region(region);
+      let link = resource_link(ResourceTargetVariant::Server, id);
+      match alert.level {
+        SeverityLevel::Ok => format!(
+          "{level} | **{name}**{region} periphery version now matches core\nperiphery: **v{periphery_version}**\ncore: **v{core_version}**\n{link}"
+        ),
+        _ => format!(
+          "{level} | **{name}**{region} periphery version **mismatch** 🔀\nperiphery: **v{periphery_version}**\ncore: **v{core_version}**\n{link}"
+        ),
+      }
+    }
     AlertData::ContainerStateChange {
       id,
       name,
@@ -98,6 +166,38 @@ pub async fn send_alert(
         "📦 Deployment **{name}** is now **{to}**\nserver: **{server_name}**\nprevious: **{from}**\n{link}"
       )
     }
+    AlertData::ContainerRestartLoop {
+      id,
+      name,
+      server_id: _server_id,
+      server_name,
+      service,
+      container,
+      restarts,
+      window_minutes,
+    } => {
+      let (resource_type, label, service) =
+        fmt_container_owner(service);
+      let link = resource_link(resource_type, id);
+      format!(
+        "{level} | 🔄 Container **{container}** is in a **restart loop**\n{label}: **{name}**{service}\nserver: **{server_name}**\nrestarts: **{restarts}** in the last **{window_minutes} min**\n{link}"
+      )
+    }
+    AlertData::ContainerOomKilled {
+      id,
+      name,
+      server_id: _server_id,
+      server_name,
+      service,
+      container,
+    } => {
+      let (resource_type, label, service) =
+        fmt_container_owner(service);
+      let link = resource_link(resource_type, id);
+      format!(
+        "{level} | 💥 Container **{container}** was **OOM killed**\n{label}: **{name}**{service}\nserver: **{server_name}**\n{link}"
+      )
+    }
     AlertData::DeploymentImageUpdateAvailable {
       id,
       name,
@@ -189,6 +289,41 @@ pub async fn send_alert(
       let link = resource_link(ResourceTargetVariant::Repo, id);
       format!("{level} | Repo build for **{name}** failed\n{link}")
     }
+    AlertData::ProcedureFailed { id, name } => {
+      let link = resource_link(ResourceTargetVariant::Procedure, id);
+      format!("{level} | Procedure **{name}** failed\n{link}")
+    }
+    AlertData::ActionFailed { id, name } => {
+      let link = resource_link(ResourceTargetVariant::Action, id);
+      format!("{level} | Action **{name}** failed\n{link}")
+    }
+    AlertData::ResourceSyncFailed { id, name } => {
+      let link =
+        resource_link(ResourceTargetVariant::ResourceSync, id);
+      format!("{level} | Resource sync **{name}** failed\n{link}")
+    }
+    AlertData::DeployFailed {
+      id,
+      name,
+      server_id: _server_id,
+      server_name,
+    } => {
+      let link = resource_link(ResourceTargetVariant::Deployment, id);
+      format!(
+        "{level} | Deployment **{name}** failed to deploy\nserver: **{server_name}**\n{link}"
+      )
+    }
+    AlertData::StackDeployFailed {
+      id,
+      name,
+      server_id: _server_id,
+      server_name,
+    } => {
+      let link = resource_link(ResourceTargetVariant::Stack, id);
+      format!(
+        "{level} | Stack **{name}** failed to deploy\nserver: **{server_name}**\n{link}"
+      )
+    }
     AlertData::None {} => Default::default(),
   };
   if !content.is_empty() {
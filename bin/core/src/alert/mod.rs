@@ -205,6 +205,25 @@ fn fmt_stack_state(state: &StackState) -> String {
   }
 }
 
+/// Container alerts can come from a Deployment or a Stack service.
+/// Returns the owning resource type, its label, and the formatted service.
+fn fmt_container_owner(
+  service: &Option<String>,
+) -> (ResourceTargetVariant, &'static str, String) {
+  match service {
+    Some(service) => (
+      ResourceTargetVariant::Stack,
+      "stack",
+      format!(" | service: {service}"),
+    ),
+    None => (
+      ResourceTargetVariant::Deployment,
+      "deployment",
+      String::new(),
+    ),
+  }
+}
+
 fn fmt_level(level: SeverityLevel) -> &'static str {
   match level {
     SeverityLevel::Critical => "CRITICAL 🚨",
@@ -156,6 +156,33 @@ pub async fn send_alert(
         }
       }
     }
+    AlertData::ServerSwap {
+      id,
+      name,
+      region,
+      used_gb,
+      total_gb,
+    } => {
+      let region = fmt_region(region);
+      let percentage = 100.0 * used_gb / total_gb;
+      let text = format!(
+        "{level} | *{name}*{region} swap usage at *{percentage:.1}%* 💾"
+      );
+      let blocks = vec![
+        Block::header(level),
+        Block::section(format!(
+          "*{name}*{region} swap usage at *{percentage:.1}%* 💾"
+        )),
+        Block::section(format!(
+          "using *{used_gb:.1} GiB* / *{total_gb:.1} GiB*"
+        )),
+        Block::section(resource_link(
+          ResourceTargetVariant::Server,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
     AlertData::ServerDisk {
       id,
       name,
@@ -207,6 +234,115 @@ pub async fn send_alert(
         }
       }
     }
+    AlertData::ServerDiskInodes {
+      id,
+      name,
+      region,
+      path,
+      used,
+      total,
+    } => {
+      let region = fmt_region(region);
+      let percentage = 100.0 * *used as f64 / *total as f64;
+      let text = format!(
+        "{level} | *{name}*{region} inode usage at *{percentage:.1}%* | mount point: *{path:?}* 💿"
+      );
+      let blocks = vec![
+        Block::header(level),
+        Block::section(format!(
+          "*{name}*{region} inode usage at *{percentage:.1}%* 💿"
+        )),
+        Block::section(format!(
+          "mount point: {path:?} | using *{used}* / *{total}* inodes"
+        )),
+        Block::section(resource_link(
+          ResourceTargetVariant::Server,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
+    AlertData::ServerDiskForecast {
+      id,
+      name,
+      region,
+      path,
+      used_gb,
+      total_gb,
+      growth_gb_per_day,
+      days_until_full,
+    } => {
+      let region = fmt_region(region);
+      match alert.level {
+        SeverityLevel::Ok => {
+          let text = format!(
+            "{level} | *{name}*{region} disk no longer forecast to be full soon | mount point: *{path:?}* 💿"
+          );
+          let blocks = vec![
+            Block::header(level),
+            Block::section(format!(
+              "*{name}*{region} disk no longer forecast to be full soon 💿"
+            )),
+            Block::section(format!(
+              "mount point: {path:?} | using *{used_gb:.1} GiB* / *{total_gb:.1} GiB*"
+            )),
+            Block::section(resource_link(
+              ResourceTargetVariant::Server,
+              id,
+            )),
+          ];
+          (text, blocks.into())
+        }
+        _ => {
+          let text = format!(
+            "{level} | *{name}*{region} disk will be full in *{days_until_full:.1} days* | mount point: *{path:?}* 💿"
+          );
+          let blocks = vec![
+            Block::header(level),
+            Block::section(format!(
+              "*{name}*{region} disk will be full in *{days_until_full:.1} days* 💿"
+            )),
+            Block::section(format!(
+              "mount point: {path:?} | using *{used_gb:.1} GiB* / *{total_gb:.1} GiB* | growing *{growth_gb_per_day:.2} GiB* / day"
+            )),
+            Block::section(resource_link(
+              ResourceTargetVariant::Server,
+              id,
+            )),
+          ];
+          (text, blocks.into())
+        }
+      }
+    }
+    AlertData::PeripheryVersionMismatch {
+      id,
+      name,
+      region,
+      periphery_version,
+      core_version,
+    } => {
+      let region = fmt_region(region);
+      let text = match alert.level {
+        SeverityLevel::Ok => format!(
+          "{level} | *{name}*{region} periphery version now matches core"
+        ),
+        _ => format!(
+          "{level} | *{name}*{region} periphery version *mismatch* 🔀"
+        ),
+      };
+      let blocks = vec![
+        Block::header(level),
+        Block::section(text.clone()),
+        Block::section(format!(
+          "periphery: *v{periphery_version}*\ncore: *v{core_version}*"
+        )),
+        Block::section(resource_link(
+          ResourceTargetVariant::Server,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
     AlertData::ContainerStateChange {
       name,
       server_name,
@@ -229,6 +365,52 @@ pub async fn send_alert(
       ];
       (text, blocks.into())
     }
+    AlertData::ContainerRestartLoop {
+      id,
+      name,
+      server_name,
+      server_id: _server_id,
+      service,
+      container,
+      restarts,
+      window_minutes,
+    } => {
+      let text = format!(
+        "{level} | 🔄 Container *{container}* is in a *restart loop*"
+      );
+      let (resource_type, label, service) =
+        fmt_container_owner(service);
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!(
+          "{label}: *{name}*{service}\nserver: *{server_name}*\nrestarts: *{restarts}* in the last *{window_minutes} min*",
+        )),
+        Block::section(resource_link(resource_type, id)),
+      ];
+      (text, blocks.into())
+    }
+    AlertData::ContainerOomKilled {
+      id,
+      name,
+      server_name,
+      server_id: _server_id,
+      service,
+      container,
+    } => {
+      let text = format!(
+        "{level} | 💥 Container *{container}* was *OOM killed*"
+      );
+      let (resource_type, label, service) =
+        fmt_container_owner(service);
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!(
+          "{label}: *{name}*{service}\nserver: *{server_name}*",
+        )),
+        Block::section(resource_link(resource_type, id)),
+      ];
+      (text, blocks.into())
+    }
     AlertData::DeploymentImageUpdateAvailable {
       id,
       name,
@@ -396,6 +578,84 @@ pub async fn send_alert(
       ];
       (text, blocks.into())
     }
+    AlertData::ProcedureFailed { id, name } => {
+      let text = format!("{level} | Procedure *{name}* has *failed*");
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!("procedure name: *{name}*",)),
+        Block::section(resource_link(
+          ResourceTargetVariant::Procedure,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
+    AlertData::ActionFailed { id, name } => {
+      let text = format!("{level} | Action *{name}* has *failed*");
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!("action name: *{name}*",)),
+        Block::section(resource_link(
+          ResourceTargetVariant::Action,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
+    AlertData::ResourceSyncFailed { id, name } => {
+      let text =
+        format!("{level} | Resource sync *{name}* has *failed*");
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!("sync name: *{name}*",)),
+        Block::section(resource_link(
+          ResourceTargetVariant::ResourceSync,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
+    AlertData::DeployFailed {
+      id,
+      name,
+      server_id: _server_id,
+      server_name,
+    } => {
+      let text = format!(
+        "{level} | Deployment *{name}* has *failed* to deploy"
+      );
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!(
+          "deployment name: *{name}*\nserver: *{server_name}*",
+        )),
+        Block::section(resource_link(
+          ResourceTargetVariant::Deployment,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
+    AlertData::StackDeployFailed {
+      id,
+      name,
+      server_id: _server_id,
+      server_name,
+    } => {
+      let text =
+        format!("{level} | Stack *{name}* has *failed* to deploy");
+      let blocks = vec![
+        Block::header(text.clone()),
+        Block::section(format!(
+          "stack name: *{name}*\nserver: *{server_name}*",
+        )),
+        Block::section(resource_link(
+          ResourceTargetVariant::Stack,
+          id,
+        )),
+      ];
+      (text, blocks.into())
+    }
     AlertData::None {} => Default::default(),
   };
   if !text.is_empty() {
@@ -0,0 +1,291 @@
+use anyhow::{Context, anyhow};
+use axum::{
+  Extension, Router,
+  body::{Body, Bytes},
+  extract::{DefaultBodyLimit, Query},
+  http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+  middleware,
+  response::Response,
+  routing::get,
+};
+use formatting::format_serror;
+use futures::stream;
+use komodo_client::{
+  api::copy::{DeploymentCopyQuery, StackCopyQuery},
+  entities::{
+    Operation, ResourceTarget, deployment::Deployment,
+    permission::PermissionLevel, server::Server, server::ServerState,
+    user::User,
+  },
+};
+use periphery_client::api::container::ContainerCopyQuery;
+
+use crate::{
+  auth::auth_request,
+  config::core_config,
+  helpers::{
+    periphery_client,
+    query::get_server_with_state,
+    update::{add_update, make_update},
+  },
+  resource,
+  stack::get_stack_and_server,
+  state::stack_status_cache,
+};
+
+pub fn router() -> Router {
+  Router::new()
+    .route(
+      "/deployment",
+      get(download_from_deployment).post(upload_to_deployment),
+    )
+    .route("/stack", get(download_from_stack).post(upload_to_stack))
+    // Archives are buffered to be signed for periphery,
+    // and are usually larger than the default limit.
+    .layer(DefaultBodyLimit::max(
+      core_config().max_upload_size_mb as usize * 1024 * 1024,
+    ))
+    .layer(middleware::from_fn(auth_request))
+}
+
+/// The container to copy to / from,
+/// and where to record the copy.
+struct CopyTarget {
+  target: ResourceTarget,
+  server: Server,
+  docker_endpoint: String,
+  container: String,
+}
+
+async fn download_from_deployment(
+  Extension(user): Extension<User>,
+  Query(DeploymentCopyQuery { deployment, path }): Query<
+    DeploymentCopyQuery,
+  >,
+) -> serror::Result<Response> {
+  let target = deployment_copy_target(&deployment, &user).await?;
+  download(target, Operation::DownloadFromDeployment, path, &user)
+    .await
+}
+
+async fn upload_to_deployment(
+  Extension(user): Extension<User>,
+  Query(DeploymentCopyQuery { deployment, path }): Query<
+    DeploymentCopyQuery,
+  >,
+  tar: Bytes,
+) -> serror::Result<()> {
+  let target = deployment_copy_target(&deployment, &user).await?;
+  upload(target, Operation::UploadToDeployment, path, tar, &user)
+    .await
+}
+
+async fn download_from_stack(
+  Extension(user): Extension<User>,
+  Query(StackCopyQuery {
+    stack,
+    service,
+    path,
+  }): Query<StackCopyQuery>,
+) -> serror::Result<Response> {
+  let target = stack_copy_target(&stack, &service, &user).await?;
+  download(target, Operation::DownloadFromStackService, path, &user)
+    .await
+}
+
+async fn upload_to_stack(
+  Extension(user): Extension<User>,
+  Query(StackCopyQuery {
+    stack,
+    service,
+    path,
+  }): Query<StackCopyQuery>,
+  tar: Bytes,
+) -> serror::Result<()> {
+  let target = stack_copy_target(&stack, &service, &user).await?;
+  upload(target, Operation::UploadToStackService, path, tar, &user)
+    .await
+}
+
+async fn deployment_copy_target(
+  deployment: &str,
+  user: &User,
+) -> anyhow::Result<CopyTarget> {
+  let deployment = resource::get_check_permissions::<Deployment>(
+    deployment,
+    user,
+    PermissionLevel::Write,
+  )
+  .await?;
+  if deployment.config.server_id.is_empty() {
+    return Err(anyhow!("Deployment has no server configured"));
+  }
+  let (server, state) =
+    get_server_with_state(&deployment.config.server_id).await?;
+  if state != ServerState::Ok {
+    return Err(anyhow!(
+      "cannot copy files when server is unreachable or disabled"
+    ));
+  }
+  check_inbound(&server)?;
+  Ok(CopyTarget {
+    target: ResourceTarget::Deployment(deployment.id),
+    server,
+    docker_endpoint: deployment.config.docker_endpoint,
+    container: deployment.name,
+  })
+}
+
+async fn stack_copy_target(
+  stack: &str,
+  service: &str,
+  user: &User,
+) -> anyhow::Result<CopyTarget> {
+  let (stack, server) =
+    get_stack_and_server(stack, user, PermissionLevel::Write, true)
+      .await?;
+  check_inbound(&server)?;
+  let container = stack_status_cache()
+    .get(&stack.id)
+    .await
+    .and_then(|status| {
+      status
+        .curr
+        .services
+        .iter()
+        .find(|s| s.service == service)?
+        .container
+        .as_ref()
+        .map(|container| container.name.clone())
+    })
+    .with_context(|| {
+      format!("no container found for service {service}")
+    })?;
+  Ok(CopyTarget {
+    target: ResourceTarget::Stack(stack.id),
+    server,
+    docker_endpoint: stack.config.docker_endpoint,
+    container,
+  })
+}
+
+/// Archives are streamed over http,
+/// which the outbound connection doesn't carry.
+fn check_inbound(server: &Server) -> anyhow::Result<()> {
+  if server.config.outbound {
+    return Err(anyhow!(
+      "Copying files is not available for Servers using an outbound connection"
+    ));
+  }
+  Ok(())
+}
+
+async fn download(
+  CopyTarget {
+    target,
+    server,
+    docker_endpoint,
+    container,
+  }: CopyTarget,
+  operation: Operation,
+  path: String,
+  user: &User,
+) -> serror::Result<Response> {
+  let mut update = make_update(target, operation, user);
+  let res = async {
+    periphery_client(&server)?
+      .with_docker_endpoint(docker_endpoint)
+      .download_from_container(&ContainerCopyQuery {
+        container: container.clone(),
+        path: path.clone(),
+      })
+      .await
+  }
+  .await;
+  let res =
+    res.with_context(|| format!("Failed to download '{path}'"));
+  match &res {
+    Ok(_) => update.push_simple_log(
+      "Download",
+      format!("Downloading '{path}' from container '{container}'"),
+    ),
+    Err(e) => {
+      update.push_error_log("Download", format_serror(&e.into()))
+    }
+  }
+  update.finalize();
+  // Don't allow unrecorded downloads
+  add_update(update).await?;
+
+  let res = res?;
+  let filename = path
+    .trim_end_matches('/')
+    .rsplit('/')
+    .next()
+    .filter(|name| !name.is_empty())
+    .unwrap_or("root")
+    .replace('"', "");
+  // Pass through the archive as periphery streams it
+  let body =
+    Body::from_stream(stream::unfold(Some(res), |res| async move {
+      let mut res = res?;
+      match res.chunk().await {
+        Ok(Some(chunk)) => Some((Ok(chunk), Some(res))),
+        Ok(None) => None,
+        Err(e) => Some((Err(e), None)),
+      }
+    }));
+  let res = Response::builder()
+    .header(CONTENT_TYPE, "application/x-tar")
+    .header(
+      CONTENT_DISPOSITION,
+      format!("attachment; filename=\"{filename}.tar\""),
+    )
+    .body(body)
+    .context("failed to build response")?;
+  Ok(res)
+}
+
+async fn upload(
+  CopyTarget {
+    target,
+    server,
+    docker_endpoint,
+    container,
+  }: CopyTarget,
+  operation: Operation,
+  path: String,
+  tar: Bytes,
+  user: &User,
+) -> serror::Result<()> {
+  let mut update = make_update(target, operation, user);
+  let size = tar.len();
+  let res = async {
+    periphery_client(&server)?
+      .with_docker_endpoint(docker_endpoint)
+      .upload_to_container(
+        &ContainerCopyQuery {
+          container: container.clone(),
+          path: path.clone(),
+        },
+        tar,
+      )
+      .await
+  }
+  .await;
+  let res =
+    res.with_context(|| format!("Failed to upload to '{path}'"));
+  match &res {
+    Ok(_) => update.push_simple_log(
+      "Upload",
+      format!(
+        "Extracted {size} byte archive to '{path}' in container '{container}'"
+      ),
+    ),
+    Err(e) => update.push_error_log("Upload", format_serror(&e.into())),
+  }
+  update.finalize();
+  add_update(update).await?;
+  res?;
+  Ok(())
+}
@@ -31,7 +31,7 @@ use crate::{
     periphery_client,
     query::get_variables_and_secrets,
     registry_token,
-    update::update_update,
+    update::{update_update, with_pull_progress},
   },
   monitor::update_cache_for_server,
   resource,
@@ -233,16 +233,17 @@ impl Resolve<ExecuteArgs> for Deploy {
     update.version = version;
     update_update(update.clone()).await?;
 
-    match periphery_client(&server)?
-      .request(api::container::Deploy {
-        deployment,
-        stop_signal: self.stop_signal,
-        stop_time: self.stop_time,
-        registry_token,
-        replacers: secret_replacers.into_iter().collect(),
-      })
-      .await
-    {
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint);
+    let req = periphery.request(api::container::Deploy {
+      deployment,
+      stop_signal: self.stop_signal,
+      stop_time: self.stop_time,
+      registry_token,
+      replacers: secret_replacers.into_iter().collect(),
+      progress_id: Some(update.id.clone()),
+    });
+    match with_pull_progress(&periphery, &update, req).await {
       Ok(log) => update.logs.push(log),
       Err(e) => {
         update.push_error_log(
@@ -265,17 +266,22 @@ impl Resolve<ExecuteArgs> for Deploy {
 const PULL_TIMEOUT: i64 = 5_000;
 type ServerId = String;
 type Image = String;
-type PullCache = TimeoutCache<(ServerId, Image), Log>;
+type DockerEndpoint = String;
+type PullCache = TimeoutCache<(ServerId, DockerEndpoint, Image), Log>;
 
 fn pull_cache() -> &'static PullCache {
   static PULL_CACHE: OnceLock<PullCache> = OnceLock::new();
   PULL_CACHE.get_or_init(Default::default)
 }
 
+/// If `update` is provided, the pull progress
+/// is polled into it while the image pulls.
 pub async fn pull_deployment_inner(
   deployment: Deployment,
   server: &Server,
+  update: Option<&Update>,
 ) -> anyhow::Result<Log> {
+  let docker_endpoint = deployment.config.docker_endpoint;
   let (image, account, token) = match deployment.config.image {
     DeploymentImage::Build { build_id, version } => {
       let build = resource::get::<Build>(&build_id).await?;
@@ -339,7 +345,11 @@ pub async fn pull_deployment_inner(
 
   // Acquire the pull lock for this image on the server
   let lock = pull_cache()
-    .get_lock((server.id.clone(), image.clone()))
+    .get_lock((
+      server.id.clone(),
+      docker_endpoint.clone(),
+      image.clone(),
+    ))
     .await;
 
   // Lock the path lock, prevents simultaneous pulls by
@@ -353,14 +363,21 @@ pub async fn pull_deployment_inner(
   }
 
   let res = async {
-    let log = match periphery_client(server)?
-      .request(api::image::PullImage {
-        name: image,
-        account,
-        token,
-      })
-      .await
-    {
+    let periphery =
+      periphery_client(server)?.with_docker_endpoint(docker_endpoint);
+    let req = periphery.request(api::image::PullImage {
+      name: image,
+      account,
+      token,
+      progress_id: update.map(|update| update.id.clone()),
+    });
+    let res = match update {
+      Some(update) => {
+        with_pull_progress(&periphery, update, req).await
+      }
+      None => req.await,
+    };
+    let log = match res {
       Ok(log) => log,
       Err(e) => Log::error("Pull image", format_serror(&e.into())),
     };
@@ -401,7 +418,9 @@ impl Resolve<ExecuteArgs> for PullDeployment {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let log = pull_deployment_inner(deployment, &server).await?;
+    let log =
+      pull_deployment_inner(deployment, &server, Some(&update))
+        .await?;
 
     update.logs.push(log);
     update.finalize();
@@ -437,6 +456,7 @@ impl Resolve<ExecuteArgs> for StartDeployment {
     update_update(update.clone()).await?;
 
     let log = match periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint)
       .request(api::container::StartContainer {
         name: deployment.name,
       })
@@ -484,6 +504,7 @@ impl Resolve<ExecuteArgs> for RestartDeployment {
     update_update(update.clone()).await?;
 
     let log = match periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint)
       .request(api::container::RestartContainer {
         name: deployment.name,
       })
@@ -533,6 +554,7 @@ impl Resolve<ExecuteArgs> for PauseDeployment {
     update_update(update.clone()).await?;
 
     let log = match periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint)
       .request(api::container::PauseContainer {
         name: deployment.name,
       })
@@ -580,6 +602,7 @@ impl Resolve<ExecuteArgs> for UnpauseDeployment {
     update_update(update.clone()).await?;
 
     let log = match periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint)
       .request(api::container::UnpauseContainer {
         name: deployment.name,
       })
@@ -629,6 +652,7 @@ impl Resolve<ExecuteArgs> for StopDeployment {
     update_update(update.clone()).await?;
 
     let log = match periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint)
       .request(api::container::StopContainer {
         name: deployment.name,
         signal: self
@@ -711,6 +735,7 @@ impl Resolve<ExecuteArgs> for DestroyDeployment {
     update_update(update.clone()).await?;
 
     let log = match periphery_client(&server)?
+      .with_docker_endpoint(&deployment.config.docker_endpoint)
       .request(api::container::RemoveContainer {
         name: deployment.name,
         signal: self
@@ -78,6 +78,8 @@ pub enum ExecuteRequest {
   PruneImages(PruneImages),
   DeleteVolume(DeleteVolume),
   PruneVolumes(PruneVolumes),
+  BackupVolume(BackupVolume),
+  RestoreVolume(RestoreVolume),
   PruneDockerBuilders(PruneDockerBuilders),
   PruneBuildx(PruneBuildx),
   PruneSystem(PruneSystem),
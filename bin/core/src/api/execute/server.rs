@@ -50,7 +50,8 @@ impl Resolve<ExecuteArgs> for StartContainer {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::StartContainer {
@@ -104,7 +105,8 @@ impl Resolve<ExecuteArgs> for RestartContainer {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::RestartContainer {
@@ -160,7 +162,8 @@ impl Resolve<ExecuteArgs> for PauseContainer {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::PauseContainer {
@@ -214,7 +217,8 @@ impl Resolve<ExecuteArgs> for UnpauseContainer {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::UnpauseContainer {
@@ -270,7 +274,8 @@ impl Resolve<ExecuteArgs> for StopContainer {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::StopContainer {
@@ -308,6 +313,7 @@ impl Resolve<ExecuteArgs> for DestroyContainer {
       container,
       signal,
       time,
+      docker_endpoint,
     } = self;
     let server = resource::get_check_permissions::<Server>(
       &server,
@@ -332,7 +338,8 @@ impl Resolve<ExecuteArgs> for DestroyContainer {
     // Send update after setting action state, this way frontend gets correct state.
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::RemoveContainer {
@@ -388,6 +395,7 @@ impl Resolve<ExecuteArgs> for StartAllContainers {
     update_update(update.clone()).await?;
 
     let logs = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default())
       .request(api::container::StartAllContainers {})
       .await
       .context("failed to start all containers on host")?;
@@ -438,6 +446,7 @@ impl Resolve<ExecuteArgs> for RestartAllContainers {
     update_update(update.clone()).await?;
 
     let logs = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default())
       .request(api::container::RestartAllContainers {})
       .await
       .context("failed to restart all containers on host")?;
@@ -490,6 +499,7 @@ impl Resolve<ExecuteArgs> for PauseAllContainers {
     update_update(update.clone()).await?;
 
     let logs = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default())
       .request(api::container::PauseAllContainers {})
       .await
       .context("failed to pause all containers on host")?;
@@ -540,6 +550,7 @@ impl Resolve<ExecuteArgs> for UnpauseAllContainers {
     update_update(update.clone()).await?;
 
     let logs = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default())
       .request(api::container::UnpauseAllContainers {})
       .await
       .context("failed to unpause all containers on host")?;
@@ -592,6 +603,7 @@ impl Resolve<ExecuteArgs> for StopAllContainers {
     update_update(update.clone()).await?;
 
     let logs = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default())
       .request(api::container::StopAllContainers {})
       .await
       .context("failed to stop all containers on host")?;
@@ -641,7 +653,8 @@ impl Resolve<ExecuteArgs> for PruneContainers {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::container::PruneContainers {})
@@ -686,7 +699,8 @@ impl Resolve<ExecuteArgs> for DeleteNetwork {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::network::DeleteNetwork {
@@ -748,7 +762,8 @@ impl Resolve<ExecuteArgs> for PruneNetworks {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::network::PruneNetworks {})
@@ -791,7 +806,8 @@ impl Resolve<ExecuteArgs> for DeleteImage {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::image::DeleteImage {
@@ -850,7 +866,8 @@ impl Resolve<ExecuteArgs> for PruneImages {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log =
       match periphery.request(api::image::PruneImages {}).await {
@@ -891,7 +908,8 @@ impl Resolve<ExecuteArgs> for DeleteVolume {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery
       .request(api::volume::DeleteVolume {
@@ -953,7 +971,8 @@ impl Resolve<ExecuteArgs> for PruneVolumes {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log =
       match periphery.request(api::volume::PruneVolumes {}).await {
@@ -977,6 +996,103 @@ impl Resolve<ExecuteArgs> for PruneVolumes {
   }
 }
 
+impl Resolve<ExecuteArgs> for BackupVolume {
+  #[instrument(name = "BackupVolume", skip(user, update), fields(user_id = user.id, update_id = update.id))]
+  async fn resolve(
+    self,
+    ExecuteArgs { user, update }: &ExecuteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Execute,
+    )
+    .await?;
+
+    let mut update = update.clone();
+
+    update_update(update.clone()).await?;
+
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
+
+    match periphery
+      .request(api::volume::BackupVolume {
+        name: self.volume.clone(),
+        keep: self.keep,
+      })
+      .await
+    {
+      Ok(logs) => update.logs.extend(logs),
+      Err(e) => update.push_error_log(
+        "backup volume",
+        format_serror(
+          &e.context(format!(
+            "failed to back up volume {} on server {}",
+            self.volume, server.name
+          ))
+          .into(),
+        ),
+      ),
+    }
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+impl Resolve<ExecuteArgs> for RestoreVolume {
+  #[instrument(name = "RestoreVolume", skip(user, update), fields(user_id = user.id, update_id = update.id))]
+  async fn resolve(
+    self,
+    ExecuteArgs { user, update }: &ExecuteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Execute,
+    )
+    .await?;
+
+    let mut update = update.clone();
+
+    update_update(update.clone()).await?;
+
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
+
+    let log = match periphery
+      .request(api::volume::RestoreVolume {
+        name: self.volume.clone(),
+        backup: self.backup,
+      })
+      .await
+    {
+      Ok(log) => log,
+      Err(e) => Log::error(
+        "restore volume",
+        format_serror(
+          &e.context(format!(
+            "failed to restore volume {} on server {}",
+            self.volume, server.name
+          ))
+          .into(),
+        ),
+      ),
+    };
+
+    update.logs.push(log);
+    update_cache_for_server(&server).await;
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
 impl Resolve<ExecuteArgs> for PruneDockerBuilders {
   #[instrument(name = "PruneDockerBuilders", skip(user, update), fields(user_id = user.id, update_id = update.id))]
   async fn resolve(
@@ -1005,7 +1121,8 @@ impl Resolve<ExecuteArgs> for PruneDockerBuilders {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log =
       match periphery.request(api::build::PruneBuilders {}).await {
@@ -1057,7 +1174,8 @@ impl Resolve<ExecuteArgs> for PruneBuildx {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log =
       match periphery.request(api::build::PruneBuildx {}).await {
@@ -1109,7 +1227,8 @@ impl Resolve<ExecuteArgs> for PruneSystem {
 
     update_update(update.clone()).await?;
 
-    let periphery = periphery_client(&server)?;
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default());
 
     let log = match periphery.request(api::PruneSystem {}).await {
       Ok(log) => log,
@@ -26,7 +26,9 @@ use crate::{
     },
     periphery_client,
     query::get_variables_and_secrets,
-    update::{add_update_without_send, update_update},
+    update::{
+      add_update_without_send, update_update, with_pull_progress,
+    },
   },
   monitor::update_cache_for_server,
   resource,
@@ -173,6 +175,16 @@ impl Resolve<ExecuteArgs> for DeployStack {
       Default::default()
     };
 
+    let periphery = periphery_client(&server)?
+      .with_docker_endpoint(&stack.config.docker_endpoint);
+    let req = periphery.request(ComposeUp {
+      stack: stack.clone(),
+      services: self.services,
+      git_token,
+      registry_token,
+      replacers: secret_replacers.into_iter().collect(),
+      progress_id: Some(update.id.clone()),
+    });
     let ComposeUpResponse {
       logs,
       deployed,
@@ -183,15 +195,7 @@ impl Resolve<ExecuteArgs> for DeployStack {
       compose_config,
       commit_hash,
       commit_message,
-    } = periphery_client(&server)?
-      .request(ComposeUp {
-        stack: stack.clone(),
-        services: self.services,
-        git_token,
-        registry_token,
-        replacers: secret_replacers.into_iter().collect(),
-      })
-      .await?;
+    } = with_pull_progress(&periphery, &update, req).await?;
 
     update.logs.extend(logs);
 
@@ -448,14 +452,19 @@ pub async fn pull_stack_inner(
     }
   };
 
-  let res = periphery_client(server)?
-    .request(ComposePull {
-      stack,
-      services,
-      git_token,
-      registry_token,
-    })
-    .await?;
+  let periphery = periphery_client(server)?
+    .with_docker_endpoint(&stack.config.docker_endpoint);
+  let req = periphery.request(ComposePull {
+    stack,
+    services,
+    git_token,
+    registry_token,
+    progress_id: update.as_ref().map(|update| update.id.clone()),
+  });
+  let res = match update {
+    Some(update) => with_pull_progress(&periphery, update, req).await,
+    None => req.await,
+  }?;
 
   // Ensure cached stack state up to date by updating server cache
   update_cache_for_server(server).await;
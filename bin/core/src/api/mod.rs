@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod copy;
 pub mod execute;
 pub mod read;
 pub mod user;
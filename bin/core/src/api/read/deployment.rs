@@ -124,7 +124,12 @@ impl Resolve<ReadArgs> for GetDeploymentLog {
     } = self;
     let Deployment {
       name,
-      config: DeploymentConfig { server_id, .. },
+      config:
+        DeploymentConfig {
+          server_id,
+          docker_endpoint,
+          ..
+        },
       ..
     } = resource::get_check_permissions::<Deployment>(
       &deployment,
@@ -137,6 +142,7 @@ impl Resolve<ReadArgs> for GetDeploymentLog {
     }
     let server = resource::get::<Server>(&server_id).await?;
     let res = periphery_client(&server)?
+      .with_docker_endpoint(docker_endpoint)
       .request(api::container::GetContainerLog {
         name,
         tail: cmp::min(tail, MAX_LOG_LENGTH),
@@ -162,7 +168,12 @@ impl Resolve<ReadArgs> for SearchDeploymentLog {
     } = self;
     let Deployment {
       name,
-      config: DeploymentConfig { server_id, .. },
+      config:
+        DeploymentConfig {
+          server_id,
+          docker_endpoint,
+          ..
+        },
       ..
     } = resource::get_check_permissions::<Deployment>(
       &deployment,
@@ -175,6 +186,7 @@ impl Resolve<ReadArgs> for SearchDeploymentLog {
     }
     let server = resource::get::<Server>(&server_id).await?;
     let res = periphery_client(&server)?
+      .with_docker_endpoint(docker_endpoint)
       .request(api::container::GetContainerLogSearch {
         name,
         terms,
@@ -195,7 +207,12 @@ impl Resolve<ReadArgs> for GetDeploymentStats {
   ) -> serror::Result<ContainerStats> {
     let Deployment {
       name,
-      config: DeploymentConfig { server_id, .. },
+      config:
+        DeploymentConfig {
+          server_id,
+          docker_endpoint,
+          ..
+        },
       ..
     } = resource::get_check_permissions::<Deployment>(
       &self.deployment,
@@ -210,6 +227,7 @@ impl Resolve<ReadArgs> for GetDeploymentStats {
     }
     let server = resource::get::<Server>(&server_id).await?;
     let res = periphery_client(&server)?
+      .with_docker_endpoint(docker_endpoint)
       .request(api::container::GetContainerStats { name })
       .await
       .context("failed to get stats from periphery")?;
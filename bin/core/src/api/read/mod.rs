@@ -104,8 +104,13 @@ enum ReadRequest {
   GetServer(GetServer),
   GetServerState(GetServerState),
   GetPeripheryVersion(GetPeripheryVersion),
+  ListPeripheryVersions(ListPeripheryVersions),
   GetServerActionState(GetServerActionState),
   GetHistoricalServerStats(GetHistoricalServerStats),
+  GetHistoricalContainerStats(GetHistoricalContainerStats),
+  GetServerDiskForecast(GetServerDiskForecast),
+  ListServerFiles(ListServerFiles),
+  GetServerFile(GetServerFile),
   ListServers(ListServers),
   ListFullServers(ListFullServers),
   InspectDockerContainer(InspectDockerContainer),
@@ -116,6 +121,9 @@ enum ReadRequest {
   InspectDockerImage(InspectDockerImage),
   ListDockerImageHistory(ListDockerImageHistory),
   InspectDockerVolume(InspectDockerVolume),
+  ListDockerVolumeBackups(ListDockerVolumeBackups),
+  GetDockerDiskUsage(GetDockerDiskUsage),
+  ListDockerEndpoints(ListDockerEndpoints),
   ListAllDockerContainers(ListAllDockerContainers),
   ListDockerContainers(ListDockerContainers),
   ListDockerNetworks(ListDockerNetworks),
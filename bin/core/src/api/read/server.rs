@@ -1,49 +1,63 @@
 use std::{
   cmp,
-  collections::HashMap,
+  collections::{BTreeMap, HashMap},
   sync::{Arc, OnceLock},
 };
 
 use anyhow::{Context, anyhow};
 use async_timing_util::{
-  FIFTEEN_SECONDS_MS, get_timelength_in_ms, unix_timestamp_ms,
+  FIFTEEN_SECONDS_MS, ONE_DAY_MS, get_timelength_in_ms,
+  unix_timestamp_ms,
 };
 use komodo_client::{
   api::read::*,
   entities::{
-    ResourceTarget,
+    I64, ResourceTarget, Timelength,
     deployment::Deployment,
     docker::{
       container::{Container, ContainerListItem},
+      disk_usage::DockerDiskUsage,
       image::{Image, ImageHistoryResponseItem},
       network::Network,
-      volume::Volume,
+      volume::{Volume, VolumeBackup},
     },
     permission::PermissionLevel,
     server::{
       Server, ServerActionState, ServerListItem, ServerState,
     },
     stack::{Stack, StackServiceNames},
-    stats::{SystemInformation, SystemProcess},
+    stats::{SystemInformation, SystemProcess, SystemStatsRollup},
     update::Log,
   },
 };
 use mungos::{
   find::find_collect,
-  mongodb::{bson::doc, options::FindOptions},
+  mongodb::{
+    bson::{Bson, doc},
+    options::FindOptions,
+  },
 };
 use periphery_client::api::{
   self as periphery,
   container::InspectContainer,
   image::{ImageHistory, InspectImage},
   network::InspectNetwork,
-  volume::InspectVolume,
+  volume::{InspectVolume, ListVolumeBackups},
 };
 use resolver_api::Resolve;
 use tokio::sync::Mutex;
 
 use crate::{
-  helpers::{periphery_client, query::get_all_tags},
+  config::core_config,
+  helpers::{
+    forecast::get_disk_forecasts,
+    periphery_client, periphery_version_matches_core,
+    query::get_all_tags,
+    rollup::{
+      FIVE_MINUTES_MS, ONE_HOUR_MS, merge_samples,
+      rollup_average_record,
+    },
+  },
   resource,
   stack::compose_container_match_regex,
   state::{action_states, db_client, server_status_cache},
@@ -101,19 +115,53 @@ impl Resolve<ReadArgs> for GetPeripheryVersion {
   }
 }
 
+impl Resolve<ReadArgs> for ListPeripheryVersions {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<ListPeripheryVersionsResponse> {
+    let servers = resource::list_for_user::<Server>(
+      Default::default(),
+      user,
+      &[],
+    )
+    .await?;
+    let mut res = Vec::with_capacity(servers.len());
+    for server in servers {
+      let version = server_status_cache()
+        .get(&server.id)
+        .await
+        .map(|s| s.version.clone())
+        .unwrap_or(String::from("unknown"));
+      res.push(PeripheryVersionItem {
+        matches_core: periphery_version_matches_core(&version),
+        server_id: server.id,
+        server_name: server.name,
+        state: server.info.state,
+        version,
+      });
+    }
+    res.sort_by(|a, b| a.server_name.cmp(&b.server_name));
+    Ok(ListPeripheryVersionsResponse {
+      core_version: env!("CARGO_PKG_VERSION").to_string(),
+      servers: res,
+    })
+  }
+}
+
 impl Resolve<ReadArgs> for GetServer {
   async fn resolve(
     self,
     ReadArgs { user }: &ReadArgs,
   ) -> serror::Result<Server> {
-    Ok(
-      resource::get_check_permissions::<Server>(
-        &self.server,
-        user,
-        PermissionLevel::Read,
-      )
-      .await?,
+    let mut server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read,
     )
+    .await?;
+    server.info.sanitize();
+    Ok(server)
   }
 }
 
@@ -148,7 +196,13 @@ impl Resolve<ReadArgs> for ListFullServers {
       resource::list_full_for_user::<Server>(
         self.query, user, &all_tags,
       )
-      .await?,
+      .await?
+      .into_iter()
+      .map(|mut server| {
+        server.info.sanitize();
+        server
+      })
+      .collect(),
     )
   }
 }
@@ -321,6 +375,177 @@ impl Resolve<ReadArgs> for GetHistoricalServerStats {
       PermissionLevel::Read,
     )
     .await?;
+    let timelength = granularity;
+    let granularity =
+      get_timelength_in_ms(granularity.to_string().parse().unwrap())
+        as i64;
+    let mut ts_vec = Vec::<i64>::new();
+    let curr_ts = unix_timestamp_ms() as i64;
+    let mut curr_ts = curr_ts
+      - curr_ts % granularity
+      - granularity * STATS_PER_PAGE * page as i64;
+    for _ in 0..STATS_PER_PAGE {
+      ts_vec.push(curr_ts);
+      curr_ts -= granularity;
+    }
+
+    // Use the raw stats while they are retained for the oldest
+    // requested timestamp, otherwise the rollup tier matching
+    // the granularity, or the finest tier still retained.
+    let config = core_config();
+    let now = unix_timestamp_ms() as i64;
+    let oldest_ts = curr_ts + granularity;
+    let retained = |days: u64| {
+      days == 0 || oldest_ts >= now - days as i64 * ONE_DAY_MS as i64
+    };
+    let rollup_tier = if retained(config.keep_stats_for_days) {
+      None
+    } else if granularity < ONE_HOUR_MS
+      && retained(config.keep_five_minute_stats_for_days)
+    {
+      Some((Timelength::FiveMinutes, FIVE_MINUTES_MS))
+    } else {
+      Some((Timelength::OneHour, ONE_HOUR_MS))
+    };
+
+    let Some((tier, tier_ms)) = rollup_tier else {
+      let stats = find_collect(
+        &db_client().stats,
+        doc! {
+          "sid": server.id,
+          "ts": { "$in": ts_vec },
+        },
+        FindOptions::builder()
+          .sort(doc! { "ts": -1 })
+          .skip(page as u64 * STATS_PER_PAGE as u64)
+          .limit(STATS_PER_PAGE)
+          .build(),
+      )
+      .await
+      .context("failed to pull stats from db")?;
+      let next_page = if stats.len() == STATS_PER_PAGE as usize {
+        Some(page + 1)
+      } else {
+        None
+      };
+      return Ok(GetHistoricalServerStatsResponse {
+        stats,
+        rollups: Vec::new(),
+        next_page,
+      });
+    };
+
+    // Granularity finer than the tier is served at the tier.
+    let (timelength, bucket_ms) = if granularity < tier_ms {
+      (tier, tier_ms)
+    } else {
+      (timelength, granularity)
+    };
+    let latest = now
+      - now % bucket_ms
+      - bucket_ms * STATS_PER_PAGE * page as i64;
+    let start = latest - bucket_ms * (STATS_PER_PAGE - 1);
+    let end = latest + bucket_ms;
+
+    // Merge all the tier rollups in each bucket,
+    // rather than sampling one per bucket.
+    let mut buckets = BTreeMap::<I64, Vec<SystemStatsRollup>>::new();
+    for rollup in find_collect(
+      &db_client().stats_rollups,
+      doc! {
+        "sid": &server.id,
+        "granularity": tier.to_string(),
+        "ts": { "$gte": start, "$lt": end },
+      },
+      None,
+    )
+    .await
+    .context("failed to pull stats rollups from db")?
+    {
+      buckets
+        .entry(rollup.ts - rollup.ts % bucket_ms)
+        .or_default()
+        .push(rollup);
+    }
+    let rollups = buckets
+      .into_iter()
+      .rev()
+      .map(|(ts, rollups)| {
+        merge_samples(ts, server.id.clone(), timelength, rollups)
+      })
+      .collect::<Vec<_>>();
+
+    // Rollups are only pruned after raw stats,
+    // so there may be older pages even if this one is sparse.
+    let next_page = if rollups.is_empty() {
+      None
+    } else {
+      Some(page + 1)
+    };
+    let res = GetHistoricalServerStatsResponse {
+      stats: rollups.iter().map(rollup_average_record).collect(),
+      rollups,
+      next_page,
+    };
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for GetHistoricalContainerStats {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<GetHistoricalContainerStatsResponse> {
+    let GetHistoricalContainerStats {
+      target,
+      service,
+      granularity,
+      page,
+    } = self;
+    let (server_id, container) = match target {
+      ResourceTarget::Deployment(deployment) => {
+        let deployment =
+          resource::get_check_permissions::<Deployment>(
+            &deployment,
+            user,
+            PermissionLevel::Read,
+          )
+          .await?;
+        (deployment.config.server_id, Bson::String(deployment.name))
+      }
+      ResourceTarget::Stack(stack) => {
+        let service =
+          service.context("Must pass 'service' for Stack target")?;
+        let stack = resource::get_check_permissions::<Stack>(
+          &stack,
+          user,
+          PermissionLevel::Read,
+        )
+        .await?;
+        let container_name = stack
+          .info
+          .deployed_services
+          .unwrap_or(stack.info.latest_services)
+          .into_iter()
+          .find(|s| s.service_name == service)
+          .with_context(|| {
+            format!(
+              "Did not find service {service} on stack {}",
+              stack.name
+            )
+          })?
+          .container_name;
+        // Match the same containers as compose_container_match_regex
+        let regex =
+          format!("^{}-?[0-9]*$", regex::escape(&container_name));
+        (stack.config.server_id, doc! { "$regex": regex }.into())
+      }
+      _ => {
+        return Err(
+          anyhow!("Target must be a Deployment or Stack").into(),
+        );
+      }
+    };
     let granularity =
       get_timelength_in_ms(granularity.to_string().parse().unwrap())
         as i64;
@@ -335,25 +560,82 @@ impl Resolve<ReadArgs> for GetHistoricalServerStats {
     }
 
     let stats = find_collect(
-      &db_client().stats,
+      &db_client().container_stats,
       doc! {
-        "sid": server.id,
+        "sid": server_id,
+        "container": container,
         "ts": { "$in": ts_vec },
       },
       FindOptions::builder()
         .sort(doc! { "ts": -1 })
-        .skip(page as u64 * STATS_PER_PAGE as u64)
         .limit(STATS_PER_PAGE)
         .build(),
     )
     .await
-    .context("failed to pull stats from db")?;
+    .context("failed to pull container stats from db")?;
     let next_page = if stats.len() == STATS_PER_PAGE as usize {
       Some(page + 1)
     } else {
       None
     };
-    let res = GetHistoricalServerStatsResponse { stats, next_page };
+    let res =
+      GetHistoricalContainerStatsResponse { stats, next_page };
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for GetServerDiskForecast {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<GetServerDiskForecastResponse> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read,
+    )
+    .await?;
+    let forecasts = get_disk_forecasts(&server.id).await?;
+    Ok(forecasts)
+  }
+}
+
+impl Resolve<ReadArgs> for ListServerFiles {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<ListServerFilesResponse> {
+    // Host files may contain secrets, so this needs the same
+    // permission as writing them.
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+    let res = periphery_client(&server)?
+      .request(periphery::files::ListFiles { path: self.path })
+      .await
+      .context("failed at call to periphery")?;
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for GetServerFile {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<GetServerFileResponse> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+    let res = periphery_client(&server)?
+      .request(periphery::files::ReadFile { path: self.path })
+      .await
+      .context("failed at call to periphery")?;
     Ok(res)
   }
 }
@@ -750,6 +1032,94 @@ impl Resolve<ReadArgs> for InspectDockerVolume {
   }
 }
 
+impl Resolve<ReadArgs> for ListDockerVolumeBackups {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<Vec<VolumeBackup>> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read,
+    )
+    .await?;
+    let cache = server_status_cache()
+      .get_or_insert_default(&server.id)
+      .await;
+    if cache.state != ServerState::Ok {
+      return Err(
+        anyhow!(
+          "Cannot list volume backups: server is {:?}",
+          cache.state
+        )
+        .into(),
+      );
+    }
+    let res = periphery_client(&server)?
+      .with_docker_endpoint(self.docker_endpoint.unwrap_or_default())
+      .request(ListVolumeBackups { name: self.volume })
+      .await?;
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for GetDockerDiskUsage {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<DockerDiskUsage> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read,
+    )
+    .await?;
+    let cache = server_status_cache()
+      .get_or_insert_default(&server.id)
+      .await;
+    if cache.state != ServerState::Ok {
+      return Err(
+        anyhow!("Cannot get disk usage: server is {:?}", cache.state)
+          .into(),
+      );
+    }
+    let res = periphery_client(&server)?
+      .request(periphery::GetDockerDiskUsage {})
+      .await?;
+    Ok(res)
+  }
+}
+
+impl Resolve<ReadArgs> for ListDockerEndpoints {
+  async fn resolve(
+    self,
+    ReadArgs { user }: &ReadArgs,
+  ) -> serror::Result<ListDockerEndpointsResponse> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Read,
+    )
+    .await?;
+    let cache = server_status_cache()
+      .get_or_insert_default(&server.id)
+      .await;
+    if cache.state != ServerState::Ok {
+      return Err(
+        anyhow!(
+          "Cannot list docker endpoints: server is {:?}",
+          cache.state
+        )
+        .into(),
+      );
+    }
+    let res = periphery_client(&server)?
+      .request(periphery::ListDockerEndpoints {})
+      .await?;
+    Ok(res)
+  }
+}
+
 impl Resolve<ReadArgs> for ListComposeProjects {
   async fn resolve(
     self,
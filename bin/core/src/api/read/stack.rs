@@ -79,6 +79,7 @@ impl Resolve<ReadArgs> for GetStackLog {
       get_stack_and_server(&stack, user, PermissionLevel::Read, true)
         .await?;
     let res = periphery_client(&server)?
+      .with_docker_endpoint(&stack.config.docker_endpoint)
       .request(GetComposeLog {
         project: stack.project_name(false),
         services,
@@ -108,6 +109,7 @@ impl Resolve<ReadArgs> for SearchStackLog {
       get_stack_and_server(&stack, user, PermissionLevel::Read, true)
         .await?;
     let res = periphery_client(&server)?
+      .with_docker_endpoint(&stack.config.docker_endpoint)
       .request(GetComposeLogSearch {
         project: stack.project_name(false),
         services,
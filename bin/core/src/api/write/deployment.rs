@@ -237,6 +237,7 @@ impl Resolve<WriteArgs> for RenameDeployment {
       let server =
         resource::get::<Server>(&deployment.config.server_id).await?;
       let log = periphery_client(&server)?
+        .with_docker_endpoint(&deployment.config.docker_endpoint)
         .request(api::container::RenameContainer {
           curr_name: deployment.name.clone(),
           new_name: name.clone(),
@@ -81,6 +81,12 @@ pub enum WriteRequest {
   UpdateServer(UpdateServer),
   RenameServer(RenameServer),
   CreateNetwork(CreateNetwork),
+  WriteServerFile(WriteServerFile),
+  DeleteServerFile(DeleteServerFile),
+  RotateServerCertificate(RotateServerCertificate),
+  ResetServerCertificate(ResetServerCertificate),
+  RotateServerPasskey(RotateServerPasskey),
+  ResetServerPasskey(ResetServerPasskey),
 
   // ==== DEPLOYMENT ====
   CreateDeployment(CreateDeployment),
@@ -1,3 +1,4 @@
+use anyhow::Context;
 use formatting::format_serror;
 use komodo_client::{
   api::write::*,
@@ -8,15 +9,17 @@ use komodo_client::{
     update::{Update, UpdateStatus},
   },
 };
+use mungos::{by_id::update_one_by_id, mongodb::bson::doc};
 use periphery_client::api;
 use resolver_api::Resolve;
 
 use crate::{
   helpers::{
-    periphery_client,
+    periphery_client, pinned_periphery_client, random_string,
     update::{add_update, make_update, update_update},
   },
   resource,
+  state::db_client,
 };
 
 use super::WriteArgs;
@@ -27,17 +30,21 @@ impl Resolve<WriteArgs> for CreateServer {
     self,
     WriteArgs { user }: &WriteArgs,
   ) -> serror::Result<Server> {
-    Ok(
+    let mut server =
       resource::create::<Server>(&self.name, self.config, user)
-        .await?,
-    )
+        .await?;
+    server.info.sanitize();
+    Ok(server)
   }
 }
 
 impl Resolve<WriteArgs> for DeleteServer {
   #[instrument(name = "DeleteServer", skip(args))]
   async fn resolve(self, args: &WriteArgs) -> serror::Result<Server> {
-    Ok(resource::delete::<Server>(&self.id, args).await?)
+    let mut server =
+      resource::delete::<Server>(&self.id, args).await?;
+    server.info.sanitize();
+    Ok(server)
   }
 }
 
@@ -47,7 +54,10 @@ impl Resolve<WriteArgs> for UpdateServer {
     self,
     WriteArgs { user }: &WriteArgs,
   ) -> serror::Result<Server> {
-    Ok(resource::update::<Server>(&self.id, self.config, user).await?)
+    let mut server =
+      resource::update::<Server>(&self.id, self.config, user).await?;
+    server.info.sanitize();
+    Ok(server)
   }
 }
 
@@ -101,3 +111,301 @@ impl Resolve<WriteArgs> for CreateNetwork {
     Ok(update)
   }
 }
+
+impl Resolve<WriteArgs> for WriteServerFile {
+  #[instrument(
+    name = "WriteServerFile",
+    skip_all,
+    fields(server = &self.server, path = &self.path)
+  )]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    let periphery = periphery_client(&server)?;
+
+    let mut update =
+      make_update(&server, Operation::WriteServerFile, user);
+    update.status = UpdateStatus::InProgress;
+    update.push_simple_log("File contents to write", &self.contents);
+    update.id = add_update(update.clone()).await?;
+
+    match periphery
+      .request(api::files::WriteFile {
+        path: self.path,
+        contents: self.contents,
+      })
+      .await
+    {
+      Ok(log) => update.logs.push(log),
+      Err(e) => update.push_error_log(
+        "write file",
+        format_serror(&e.context("failed to write file").into()),
+      ),
+    };
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+impl Resolve<WriteArgs> for DeleteServerFile {
+  #[instrument(name = "DeleteServerFile", skip(user))]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    let periphery = periphery_client(&server)?;
+
+    let mut update =
+      make_update(&server, Operation::DeleteServerFile, user);
+    update.status = UpdateStatus::InProgress;
+    update.id = add_update(update.clone()).await?;
+
+    match periphery
+      .request(api::files::DeleteFile { path: self.path })
+      .await
+    {
+      Ok(log) => update.logs.push(log),
+      Err(e) => update.push_error_log(
+        "delete file",
+        format_serror(&e.context("failed to delete file").into()),
+      ),
+    };
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+impl Resolve<WriteArgs> for RotateServerCertificate {
+  #[instrument(name = "RotateServerCertificate", skip(user))]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    // Ensure the current cert is pinned, so the new fingerprint
+    // is only accepted from the verified periphery.
+    let periphery = pinned_periphery_client(&server).await?;
+
+    let mut update =
+      make_update(&server, Operation::RotateServerCertificate, user);
+    update.status = UpdateStatus::InProgress;
+    update.id = add_update(update.clone()).await?;
+
+    match periphery.request(api::RotateSslCert {}).await {
+      Ok(api::RotateSslCertResponse { fingerprint }) => {
+        match set_certificate_fingerprint(&server.id, &fingerprint)
+          .await
+        {
+          Ok(_) => update.push_simple_log(
+            "rotate certificate",
+            format!(
+              "Pinned new certificate fingerprint: {fingerprint}"
+            ),
+          ),
+          Err(e) => update.push_error_log(
+            "rotate certificate",
+            format_serror(
+              &e.context(format!("Periphery rotated its certificate, but failed to pin the new fingerprint {fingerprint}. Reset the Server certificate to pin it on next connection.")).into(),
+            ),
+          ),
+        }
+      }
+      Err(e) => update.push_error_log(
+        "rotate certificate",
+        format_serror(
+          &e.context("failed to rotate certificate").into(),
+        ),
+      ),
+    };
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+impl Resolve<WriteArgs> for ResetServerCertificate {
+  #[instrument(name = "ResetServerCertificate", skip(user))]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    let mut update =
+      make_update(&server, Operation::ResetServerCertificate, user);
+
+    set_certificate_fingerprint(&server.id, "").await?;
+
+    update.push_simple_log(
+      "reset certificate",
+      format!(
+        "Cleared pinned certificate fingerprint: {}",
+        server.info.certificate_fingerprint
+      ),
+    );
+    update.finalize();
+    update.id = add_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+async fn set_certificate_fingerprint(
+  id: &str,
+  fingerprint: &str,
+) -> anyhow::Result<()> {
+  update_one_by_id(
+    &db_client().servers,
+    id,
+    doc! { "$set": { "info.certificate_fingerprint": fingerprint } },
+    None,
+  )
+  .await
+  .context("failed to update server certificate fingerprint")?;
+  Ok(())
+}
+
+impl Resolve<WriteArgs> for RotateServerPasskey {
+  #[instrument(name = "RotateServerPasskey", skip(user))]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> serror::Result<Update> {
+    let mut server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    let periphery = pinned_periphery_client(&server).await?;
+
+    let mut update =
+      make_update(&server, Operation::RotateServerPasskey, user);
+    update.status = UpdateStatus::InProgress;
+    update.id = add_update(update.clone()).await?;
+
+    let passkey = random_string(40);
+
+    // Periphery accepts both the current and new passkey until rollover.
+    let rotate = async {
+      periphery
+        .request(api::RotatePasskey {
+          passkey: passkey.clone(),
+        })
+        .await
+        .context("failed to send new passkey to periphery")?;
+      update_one_by_id(
+        &db_client().servers,
+        &server.id,
+        doc! { "$set": { "info.passkey": &passkey } },
+        None,
+      )
+      .await
+      .context("Periphery accepted the new passkey, but failed to store it. The previous passkey is still accepted.")?;
+      anyhow::Ok(())
+    };
+
+    match rotate.await {
+      Ok(_) => {
+        update.push_simple_log(
+          "rotate passkey",
+          "Periphery accepted the new passkey",
+        );
+        // Signing a request with the new passkey completes the rollover.
+        server.info.passkey = passkey;
+        match periphery_client(&server)?.health_check().await {
+          Ok(_) => update.push_simple_log(
+            "complete rollover",
+            "Periphery no longer accepts the previous passkey",
+          ),
+          Err(e) => update.push_error_log(
+            "complete rollover",
+            format_serror(
+              &e.context("failed to complete passkey rollover. It will complete on the next request to periphery.").into(),
+            ),
+          ),
+        }
+      }
+      Err(e) => update.push_error_log(
+        "rotate passkey",
+        format_serror(&e.context("failed to rotate passkey").into()),
+      ),
+    }
+
+    update.finalize();
+    update_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
+
+impl Resolve<WriteArgs> for ResetServerPasskey {
+  #[instrument(name = "ResetServerPasskey", skip(user))]
+  async fn resolve(
+    self,
+    WriteArgs { user }: &WriteArgs,
+  ) -> serror::Result<Update> {
+    let server = resource::get_check_permissions::<Server>(
+      &self.server,
+      user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    let mut update =
+      make_update(&server, Operation::ResetServerPasskey, user);
+
+    update_one_by_id(
+      &db_client().servers,
+      &server.id,
+      doc! { "$set": { "info.passkey": "" } },
+      None,
+    )
+    .await
+    .context("failed to clear server passkey")?;
+
+    update.push_simple_log(
+      "reset passkey",
+      "Cleared the passkey rotated for the Server, using the Core passkey",
+    );
+    update.finalize();
+    update.id = add_update(update.clone()).await?;
+
+    Ok(update)
+  }
+}
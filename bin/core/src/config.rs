@@ -60,6 +60,10 @@ pub fn core_config() -> &'static CoreConfig {
         .unwrap_or(config.passkey),
       webhook_secret: maybe_read_item_from_file(env.komodo_webhook_secret_file, env.komodo_webhook_secret)
         .unwrap_or(config.webhook_secret),
+      metrics_token: maybe_read_item_from_file(env.komodo_metrics_token_file, env.komodo_metrics_token)
+        .unwrap_or(config.metrics_token),
+      stats_export_token: maybe_read_item_from_file(env.komodo_stats_export_token_file, env.komodo_stats_export_token)
+        .unwrap_or(config.stats_export_token),
       database: DatabaseConfig {
         uri: maybe_read_item_from_file(env.komodo_database_uri_file,env.komodo_database_uri).unwrap_or(config.database.uri),
         address: env.komodo_database_address.unwrap_or(config.database.address),
@@ -141,6 +145,9 @@ pub fn core_config() -> &'static CoreConfig {
       port: env.komodo_port.unwrap_or(config.port),
       first_server: env.komodo_first_server.unwrap_or(config.first_server),
       frontend_path: env.komodo_frontend_path.unwrap_or(config.frontend_path),
+      max_upload_size_mb: env
+        .komodo_max_upload_size_mb
+        .unwrap_or(config.max_upload_size_mb),
       jwt_ttl: env
         .komodo_jwt_ttl
         .unwrap_or(config.jwt_ttl),
@@ -162,9 +169,30 @@ pub fn core_config() -> &'static CoreConfig {
       keep_stats_for_days: env
         .komodo_keep_stats_for_days
         .unwrap_or(config.keep_stats_for_days),
+      keep_five_minute_stats_for_days: env
+        .komodo_keep_five_minute_stats_for_days
+        .unwrap_or(config.keep_five_minute_stats_for_days),
+      keep_hourly_stats_for_days: env
+        .komodo_keep_hourly_stats_for_days
+        .unwrap_or(config.keep_hourly_stats_for_days),
       keep_alerts_for_days: env
         .komodo_keep_alerts_for_days
         .unwrap_or(config.keep_alerts_for_days),
+      container_restart_alert_threshold: env
+        .komodo_container_restart_alert_threshold
+        .unwrap_or(config.container_restart_alert_threshold),
+      container_restart_alert_window: env
+        .komodo_container_restart_alert_window
+        .unwrap_or(config.container_restart_alert_window),
+      metrics_enabled: env
+        .komodo_metrics_enabled
+        .unwrap_or(config.metrics_enabled),
+      stats_export_format: env
+        .komodo_stats_export_format
+        .unwrap_or(config.stats_export_format),
+      stats_export_url: env
+        .komodo_stats_export_url
+        .unwrap_or(config.stats_export_url),
       webhook_base_url: env
         .komodo_webhook_base_url
         .unwrap_or(config.webhook_base_url),
@@ -203,6 +231,8 @@ pub fn core_config() -> &'static CoreConfig {
       ssl_enabled: env.komodo_ssl_enabled.unwrap_or(config.ssl_enabled),
       ssl_key_file: env.komodo_ssl_key_file.unwrap_or(config.ssl_key_file),
       ssl_cert_file: env.komodo_ssl_cert_file.unwrap_or(config.ssl_cert_file),
+      periphery_client_cert_file: env.komodo_periphery_client_cert_file.or(config.periphery_client_cert_file),
+      periphery_client_key_file: env.komodo_periphery_client_key_file.or(config.periphery_client_key_file),
 
       // These can't be overridden on env
       secrets: config.secrets,
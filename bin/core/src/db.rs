@@ -14,7 +14,9 @@ use komodo_client::entities::{
   server::Server,
   server_template::ServerTemplate,
   stack::Stack,
-  stats::SystemStatsRecord,
+  stats::{
+    ContainerStatsRecord, SystemStatsRecord, SystemStatsRollup,
+  },
   sync::ResourceSync,
   tag::Tag,
   update::Update,
@@ -41,6 +43,8 @@ pub struct DbClient {
   pub updates: Collection<Update>,
   pub alerts: Collection<Alert>,
   pub stats: Collection<SystemStatsRecord>,
+  pub stats_rollups: Collection<SystemStatsRollup>,
+  pub container_stats: Collection<ContainerStatsRecord>,
   // RESOURCES
   pub servers: Collection<Server>,
   pub deployments: Collection<Deployment>,
@@ -111,6 +115,8 @@ impl DbClient {
       updates: mongo_indexed::collection(&db, true).await?,
       alerts: mongo_indexed::collection(&db, true).await?,
       stats: mongo_indexed::collection(&db, true).await?,
+      stats_rollups: mongo_indexed::collection(&db, true).await?,
+      container_stats: mongo_indexed::collection(&db, true).await?,
       // RESOURCES
       servers: resource_collection(&db, "Server").await?,
       deployments: resource_collection(&db, "Deployment").await?,
@@ -0,0 +1,142 @@
+use std::{
+  collections::HashMap,
+  path::PathBuf,
+  sync::{Arc, OnceLock},
+};
+
+use anyhow::Context;
+use async_timing_util::{
+  ONE_DAY_MS, get_timelength_in_ms, unix_timestamp_ms,
+};
+use komodo_client::entities::stats::DiskForecast;
+use mungos::{find::find_collect, mongodb::bson::doc};
+use tokio::sync::Mutex;
+
+use crate::{config::core_config, state::db_client};
+
+/// How far back in the stats history to look for growth.
+const FORECAST_LOOKBACK_MS: i64 = ONE_DAY_MS as i64;
+/// The spacing of the stats samples used in the forecast.
+const FORECAST_GRANULARITY_MS: i64 = 15 * 60 * 1000;
+/// The forecast only moves with the sample granularity,
+/// so there is no need to recompute it more often.
+const FORECAST_EXPIRY: u128 = FORECAST_GRANULARITY_MS as u128;
+/// Need at least this many samples to fit a trend.
+const MIN_SAMPLES: usize = 4;
+
+type ForecastCache =
+  Mutex<HashMap<String, Arc<(Vec<DiskForecast>, u128)>>>;
+fn forecast_cache() -> &'static ForecastCache {
+  static FORECAST_CACHE: OnceLock<ForecastCache> = OnceLock::new();
+  FORECAST_CACHE.get_or_init(Default::default)
+}
+
+/// Get the disk forecasts for the server,
+/// recomputing them from the stats history if the cached ones are stale.
+pub async fn get_disk_forecasts(
+  server_id: &str,
+) -> anyhow::Result<Vec<DiskForecast>> {
+  let mut lock = forecast_cache().lock().await;
+  match lock.get(server_id) {
+    Some(cached) if cached.1 > unix_timestamp_ms() => {
+      Ok(cached.0.clone())
+    }
+    _ => {
+      let forecasts = compute_disk_forecasts(server_id).await?;
+      lock.insert(
+        server_id.to_string(),
+        (forecasts.clone(), unix_timestamp_ms() + FORECAST_EXPIRY)
+          .into(),
+      );
+      Ok(forecasts)
+    }
+  }
+}
+
+async fn compute_disk_forecasts(
+  server_id: &str,
+) -> anyhow::Result<Vec<DiskForecast>> {
+  // Stats are recorded on the monitoring interval,
+  // so the sample spacing can't be finer than that.
+  let granularity = core_config()
+    .monitoring_interval
+    .try_into()
+    .map(|interval| get_timelength_in_ms(interval) as i64)
+    .unwrap_or_default()
+    .max(FORECAST_GRANULARITY_MS);
+  let curr_ts = unix_timestamp_ms() as i64;
+  let mut curr_ts = curr_ts - curr_ts % granularity;
+  let mut ts_vec = Vec::<i64>::new();
+  for _ in 0..(FORECAST_LOOKBACK_MS / granularity) {
+    ts_vec.push(curr_ts);
+    curr_ts -= granularity;
+  }
+
+  let stats = find_collect(
+    &db_client().stats,
+    doc! {
+      "sid": server_id,
+      "ts": { "$in": ts_vec },
+    },
+    None,
+  )
+  .await
+  .context("failed to pull stats from db")?;
+
+  // mount -> (ts, used_gb, total_gb)
+  let mut samples = HashMap::<PathBuf, Vec<(i64, f64, f64)>>::new();
+  for record in stats {
+    for disk in record.disks {
+      samples.entry(disk.mount).or_default().push((
+        record.ts,
+        disk.used_gb,
+        disk.total_gb,
+      ));
+    }
+  }
+
+  let mut forecasts = samples
+    .into_iter()
+    .filter_map(|(mount, mut samples)| {
+      if samples.len() < MIN_SAMPLES {
+        return None;
+      }
+      samples.sort_by_key(|(ts, _, _)| *ts);
+      let (_, used_gb, total_gb) = *samples.last()?;
+      let growth_gb_per_day =
+        growth_per_ms(&samples) * ONE_DAY_MS as f64;
+      let days_until_full = (growth_gb_per_day > 0.0)
+        .then(|| (total_gb - used_gb).max(0.0) / growth_gb_per_day);
+      Some(DiskForecast {
+        mount,
+        used_gb,
+        total_gb,
+        growth_gb_per_day,
+        days_until_full,
+      })
+    })
+    .collect::<Vec<_>>();
+  forecasts.sort_by(|a, b| a.mount.cmp(&b.mount));
+
+  Ok(forecasts)
+}
+
+/// Least squares slope of used_gb over ts.
+fn growth_per_ms(samples: &[(i64, f64, f64)]) -> f64 {
+  let n = samples.len() as f64;
+  // Offset the timestamps to keep the sums small.
+  let ts0 = samples[0].0;
+  let (sum_x, sum_y) =
+    samples.iter().fold((0.0, 0.0), |(x, y), (ts, used, _)| {
+      (x + (ts - ts0) as f64, y + used)
+    });
+  let (mean_x, mean_y) = (sum_x / n, sum_y / n);
+  let (cov, var) =
+    samples
+      .iter()
+      .fold((0.0, 0.0), |(cov, var), (ts, used, _)| {
+        let dx = (ts - ts0) as f64 - mean_x;
+        (cov + dx * (used - mean_y), var + dx * dx)
+      });
+  if var == 0.0 { 0.0 } else { cov / var }
+}
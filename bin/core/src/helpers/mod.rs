@@ -17,6 +17,7 @@ use komodo_client::{
 };
 use mongo_indexed::Document;
 use mungos::{
+  by_id::update_one_by_id,
   find::find_collect,
   mongodb::bson::{Bson, doc, oid::ObjectId, to_document},
 };
@@ -26,17 +27,19 @@ use resolver_api::Resolve;
 
 use crate::{
   api::write::WriteArgs, config::core_config, resource,
-  state::db_client,
+  state::{db_client, periphery_tunnels},
 };
 
 pub mod action_state;
 pub mod builder;
 pub mod cache;
 pub mod channel;
+pub mod forecast;
 pub mod interpolate;
 pub mod procedure;
 pub mod prune;
 pub mod query;
+pub mod rollup;
 pub mod update;
 
 // pub mod resource;
@@ -141,13 +144,76 @@ pub fn periphery_client(
     return Err(anyhow!("server not enabled"));
   }
 
+  // Use the passkey rotated for the server, if any
+  let passkey = if server.info.passkey.is_empty() {
+    &core_config().passkey
+  } else {
+    &server.info.passkey
+  };
+
   let client = PeripheryClient::new(
     &server.config.address,
-    &core_config().passkey,
+    passkey,
     Duration::from_secs(server.config.timeout_seconds as u64),
   );
 
-  Ok(client)
+  let client = if server.info.certificate_fingerprint.is_empty() {
+    client
+  } else {
+    client.with_certificate_fingerprint(
+      &server.info.certificate_fingerprint,
+    )
+  };
+
+  if !server.config.outbound {
+    return Ok(client);
+  }
+
+  let tunnel = periphery_tunnels()
+    .get(&server.id)
+    .map(|tunnel| tunnel.clone())
+    .context("periphery has not connected to core")?;
+
+  Ok(client.with_tunnel(tunnel))
+}
+
+/// Same as [periphery_client], but first pins the periphery cert
+/// if it isn't pinned yet (trust on first use).
+/// The passkey is only sent after the cert is pinned.
+pub async fn pinned_periphery_client(
+  server: &Server,
+) -> anyhow::Result<PeripheryClient> {
+  let client = periphery_client(server)?;
+  if !server.info.certificate_fingerprint.is_empty() {
+    return Ok(client);
+  }
+  let Some(fingerprint) =
+    client.fetch_certificate_fingerprint().await?
+  else {
+    return Ok(client);
+  };
+  update_one_by_id(
+    &db_client().servers,
+    &server.id,
+    doc! { "$set": { "info.certificate_fingerprint": &fingerprint } },
+    None,
+  )
+  .await
+  .context("failed to store server certificate fingerprint")?;
+  info!(
+    "pinned server {} certificate | fingerprint: {fingerprint}",
+    server.name
+  );
+  Ok(client.with_certificate_fingerprint(fingerprint))
+}
+
+/// Whether the periphery version reported by a server matches
+/// the version of this core. Unknown versions never match.
+pub fn periphery_version_matches_core(version: &str) -> bool {
+  let version = version.trim().trim_start_matches('v');
+  !version.is_empty()
+    && version != "unknown"
+    && version == env!("CARGO_PKG_VERSION")
 }
 
 #[instrument]
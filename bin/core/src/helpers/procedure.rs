@@ -848,6 +848,40 @@ async fn execute_execution(
       )
       .await?
     }
+    Execution::BackupVolume(req) => {
+      let req = ExecuteRequest::BackupVolume(req);
+      let update = init_execution_update(&req, &user).await?;
+      let ExecuteRequest::BackupVolume(req) = req else {
+        unreachable!()
+      };
+      let update_id = update.id.clone();
+      handle_resolve_result(
+        req
+          .resolve(&ExecuteArgs { user, update })
+          .await
+          .map_err(|e| e.error)
+          .context("Failed at BackupVolume"),
+        &update_id,
+      )
+      .await?
+    }
+    Execution::RestoreVolume(req) => {
+      let req = ExecuteRequest::RestoreVolume(req);
+      let update = init_execution_update(&req, &user).await?;
+      let ExecuteRequest::RestoreVolume(req) = req else {
+        unreachable!()
+      };
+      let update_id = update.id.clone();
+      handle_resolve_result(
+        req
+          .resolve(&ExecuteArgs { user, update })
+          .await
+          .map_err(|e| e.error)
+          .context("Failed at RestoreVolume"),
+        &update_id,
+      )
+      .await?
+    }
     Execution::PruneDockerBuilders(req) => {
       let req = ExecuteRequest::PruneDockerBuilders(req);
       let update = init_execution_update(&req, &user).await?;
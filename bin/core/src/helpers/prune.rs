@@ -14,14 +14,21 @@ pub fn spawn_prune_loop() {
   tokio::spawn(async move {
     loop {
       wait_until_timelength(Timelength::OneDay, 5000).await;
-      let (images_res, stats_res, alerts_res) =
-        tokio::join!(prune_images(), prune_stats(), prune_alerts());
+      let (images_res, stats_res, rollups_res, alerts_res) = tokio::join!(
+        prune_images(),
+        prune_stats(),
+        prune_stats_rollups(),
+        prune_alerts()
+      );
       if let Err(e) = images_res {
         error!("error in pruning images | {e:#}");
       }
       if let Err(e) = stats_res {
         error!("error in pruning stats | {e:#}");
       }
+      if let Err(e) = rollups_res {
+        error!("error in pruning stats rollups | {e:#}");
+      }
       if let Err(e) = alerts_res {
         error!("error in pruning alerts | {e:#}");
       }
@@ -75,6 +82,50 @@ async fn prune_stats() -> anyhow::Result<()> {
   if res.deleted_count > 0 {
     info!("deleted {} stats from db", res.deleted_count);
   }
+  let res = db_client()
+    .container_stats
+    .delete_many(doc! {
+      "ts": { "$lt": delete_before_ts }
+    })
+    .await?;
+  if res.deleted_count > 0 {
+    info!("deleted {} container stats from db", res.deleted_count);
+  }
+  Ok(())
+}
+
+async fn prune_stats_rollups() -> anyhow::Result<()> {
+  let config = core_config();
+  for (granularity, keep_for_days) in [
+    (
+      komodo_client::entities::Timelength::FiveMinutes,
+      config.keep_five_minute_stats_for_days,
+    ),
+    (
+      komodo_client::entities::Timelength::OneHour,
+      config.keep_hourly_stats_for_days,
+    ),
+  ] {
+    if keep_for_days == 0 {
+      continue;
+    }
+    let delete_before_ts = (unix_timestamp_ms()
+      - keep_for_days as u128 * ONE_DAY_MS)
+      as i64;
+    let res = db_client()
+      .stats_rollups
+      .delete_many(doc! {
+        "granularity": granularity.to_string(),
+        "ts": { "$lt": delete_before_ts }
+      })
+      .await?;
+    if res.deleted_count > 0 {
+      info!(
+        "deleted {} {granularity} stats rollups from db",
+        res.deleted_count
+      );
+    }
+  }
   Ok(())
 }
 
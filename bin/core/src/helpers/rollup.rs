@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use async_timing_util::{
+  ONE_DAY_MS, unix_timestamp_ms, wait_until_timelength,
+};
+use komodo_client::entities::{
+  I64, Timelength,
+  stats::{StatsAggregate, SystemStatsRecord, SystemStatsRollup},
+};
+use mungos::{find::find_collect, mongodb::bson::doc};
+
+use crate::{config::core_config, state::db_client};
+
+pub const FIVE_MINUTES_MS: i64 = 5 * 60 * 1000;
+pub const ONE_HOUR_MS: i64 = 60 * 60 * 1000;
+/// The number of buckets to roll up per db query.
+const BUCKETS_PER_CHUNK: i64 = 72;
+
+/// Keeps the 5 minute and hourly rollups of the
+/// raw system stats up to date.
+pub fn spawn_stats_rollup_loop() {
+  tokio::spawn(async move {
+    loop {
+      // Give the monitor loop time to record
+      // the last raw stats of the bucket.
+      wait_until_timelength(
+        async_timing_util::Timelength::FiveMinutes,
+        30_000,
+      )
+      .await;
+      if let Err(e) = rollup_stats(RollupSource::Raw).await {
+        error!("failed to roll up 5 minute stats | {e:#}");
+        continue;
+      }
+      if let Err(e) = rollup_stats(RollupSource::FiveMinutes).await {
+        error!("failed to roll up hourly stats | {e:#}");
+      }
+    }
+  });
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RollupSource {
+  /// Roll up the raw stats into 5 minute buckets
+  Raw,
+  /// Roll up the 5 minute buckets into hourly buckets
+  FiveMinutes,
+}
+
+impl RollupSource {
+  fn target(self) -> (Timelength, i64) {
+    match self {
+      RollupSource::Raw => (Timelength::FiveMinutes, FIVE_MINUTES_MS),
+      RollupSource::FiveMinutes => (Timelength::OneHour, ONE_HOUR_MS),
+    }
+  }
+
+  fn retention_days(self) -> u64 {
+    match self {
+      RollupSource::Raw => core_config().keep_stats_for_days,
+      RollupSource::FiveMinutes => {
+        core_config().keep_five_minute_stats_for_days
+      }
+    }
+  }
+
+  async fn earliest_ts(self) -> anyhow::Result<Option<I64>> {
+    let db = db_client();
+    let ts = match self {
+      RollupSource::Raw => db
+        .stats
+        .find_one(doc! {})
+        .sort(doc! { "ts": 1 })
+        .await
+        .context("failed to query db for earliest stats")?
+        .map(|record| record.ts),
+      RollupSource::FiveMinutes => db
+        .stats_rollups
+        .find_one(
+          doc! { "granularity": Timelength::FiveMinutes.to_string() },
+        )
+        .sort(doc! { "ts": 1 })
+        .await
+        .context("failed to query db for earliest stats rollup")?
+        .map(|rollup| rollup.ts),
+    };
+    Ok(ts)
+  }
+
+  async fn samples(
+    self,
+    start: I64,
+    end: I64,
+  ) -> anyhow::Result<Vec<SystemStatsRollup>> {
+    let db = db_client();
+    let samples = match self {
+      RollupSource::Raw => find_collect(
+        &db.stats,
+        doc! { "ts": { "$gte": start, "$lt": end } },
+        None,
+      )
+      .await
+      .context("failed to pull stats from db")?
+      .into_iter()
+      .map(raw_sample)
+      .collect(),
+      RollupSource::FiveMinutes => find_collect(
+        &db.stats_rollups,
+        doc! {
+          "granularity": Timelength::FiveMinutes.to_string(),
+          "ts": { "$gte": start, "$lt": end },
+        },
+        None,
+      )
+      .await
+      .context("failed to pull stats rollups from db")?,
+    };
+    Ok(samples)
+  }
+}
+
+async fn rollup_stats(source: RollupSource) -> anyhow::Result<()> {
+  let (granularity, bucket_ms) = source.target();
+  let db = db_client();
+
+  let now = unix_timestamp_ms() as i64;
+  // Only roll up complete buckets
+  let end = now - now % bucket_ms;
+
+  let latest = db
+    .stats_rollups
+    .find_one(doc! { "granularity": granularity.to_string() })
+    .sort(doc! { "ts": -1 })
+    .await
+    .context("failed to query db for latest stats rollup")?;
+  let mut start = match latest {
+    Some(rollup) => rollup.ts + bucket_ms,
+    None => match source.earliest_ts().await? {
+      Some(ts) => ts - ts % bucket_ms,
+      None => return Ok(()),
+    },
+  };
+  // Samples older than the source retention are already pruned
+  let retention_days = source.retention_days();
+  if retention_days > 0 {
+    let retention_start =
+      now - retention_days as i64 * ONE_DAY_MS as i64;
+    start = start.max(retention_start - retention_start % bucket_ms);
+  }
+
+  while start < end {
+    let chunk_end = (start + bucket_ms * BUCKETS_PER_CHUNK).min(end);
+
+    // (server id, bucket ts) -> samples
+    let mut buckets =
+      HashMap::<(String, I64), Vec<SystemStatsRollup>>::new();
+    for sample in source.samples(start, chunk_end).await? {
+      let ts = sample.ts - sample.ts % bucket_ms;
+      buckets
+        .entry((sample.sid.clone(), ts))
+        .or_default()
+        .push(sample);
+    }
+
+    let rollups = buckets
+      .into_iter()
+      .map(|((sid, ts), samples)| {
+        merge_samples(ts, sid, granularity, samples)
+      })
+      .collect::<Vec<_>>();
+    if !rollups.is_empty() {
+      db.stats_rollups
+        .insert_many(rollups)
+        .await
+        .context("failed to insert stats rollups")?;
+    }
+
+    start = chunk_end;
+  }
+
+  Ok(())
+}
+
+/// A raw stats record as a rollup of 1 sample.
+fn raw_sample(record: SystemStatsRecord) -> SystemStatsRollup {
+  let single = |value: f64| StatsAggregate {
+    min: value,
+    avg: value,
+    max: value,
+  };
+  SystemStatsRollup {
+    ts: record.ts,
+    sid: record.sid,
+    granularity: Default::default(),
+    count: 1,
+    cpu_perc: single(record.cpu_perc as f64),
+    mem_used_gb: single(record.mem_used_gb),
+    mem_total_gb: record.mem_total_gb,
+    disk_used_gb: single(record.disk_used_gb),
+    disk_total_gb: record.disk_total_gb,
+    network_ingress_bytes: single(record.network_ingress_bytes),
+    network_egress_bytes: single(record.network_egress_bytes),
+  }
+}
+
+/// Samples must be non empty.
+pub fn merge_samples(
+  ts: I64,
+  sid: String,
+  granularity: Timelength,
+  mut samples: Vec<SystemStatsRollup>,
+) -> SystemStatsRollup {
+  samples.sort_by_key(|sample| sample.ts);
+  let count = samples
+    .iter()
+    .map(|sample| sample.count)
+    .sum::<I64>()
+    .max(1);
+  let aggregate = |stat: fn(&SystemStatsRollup) -> StatsAggregate| {
+    let mut res = StatsAggregate {
+      min: f64::MAX,
+      avg: 0.0,
+      max: f64::MIN,
+    };
+    for sample in &samples {
+      let stat = stat(sample);
+      res.min = res.min.min(stat.min);
+      res.max = res.max.max(stat.max);
+      // Weight by the sample count to get the true average
+      res.avg += stat.avg * sample.count as f64;
+    }
+    res.avg /= count as f64;
+    res
+  };
+  let last = samples.last();
+  SystemStatsRollup {
+    ts,
+    sid,
+    granularity,
+    count,
+    cpu_perc: aggregate(|s| s.cpu_perc),
+    mem_used_gb: aggregate(|s| s.mem_used_gb),
+    mem_total_gb: last.map(|s| s.mem_total_gb).unwrap_or_default(),
+    disk_used_gb: aggregate(|s| s.disk_used_gb),
+    disk_total_gb: last.map(|s| s.disk_total_gb).unwrap_or_default(),
+    network_ingress_bytes: aggregate(|s| s.network_ingress_bytes),
+    network_egress_bytes: aggregate(|s| s.network_egress_bytes),
+  }
+}
+
+/// The rollup averages as a stats record,
+/// for clients which only graph the records.
+pub fn rollup_average_record(
+  rollup: &SystemStatsRollup,
+) -> SystemStatsRecord {
+  SystemStatsRecord {
+    ts: rollup.ts,
+    sid: rollup.sid.clone(),
+    cpu_perc: rollup.cpu_perc.avg as f32,
+    mem_used_gb: rollup.mem_used_gb.avg,
+    mem_total_gb: rollup.mem_total_gb,
+    disk_used_gb: rollup.disk_used_gb.avg,
+    disk_total_gb: rollup.disk_total_gb,
+    network_ingress_bytes: rollup.network_ingress_bytes.avg,
+    network_egress_bytes: rollup.network_egress_bytes.avg,
+    ..Default::default()
+  }
+}
@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use anyhow::Context;
 use komodo_client::entities::{
   Operation, ResourceTarget,
   action::Action,
+  alert::{Alert, AlertData, SeverityLevel},
   alerter::Alerter,
   build::Build,
   deployment::Deployment,
@@ -12,16 +15,20 @@ use komodo_client::entities::{
   server_template::ServerTemplate,
   stack::Stack,
   sync::ResourceSync,
-  update::{Update, UpdateListItem},
+  update::{Update, UpdateListItem, UpdateStatus},
   user::User,
 };
 use mungos::{
   by_id::{find_one_by_id, update_one_by_id},
   mongodb::bson::to_document,
 };
+use periphery_client::{
+  PeripheryClient, api::image::GetPullProgress,
+};
 
 use crate::{
-  api::execute::ExecuteRequest, resource, state::db_client,
+  alert::send_alerts, api::execute::ExecuteRequest,
+  metrics::record_update, resource, state::db_client,
 };
 
 use super::channel::update_channel;
@@ -54,6 +61,7 @@ pub async fn add_update(
     .as_object_id()
     .context("inserted_id is not object id")?
     .to_string();
+  record_update(&update);
   let id = update.id.clone();
   let update = update_list_item(update).await?;
   let _ = send_update(update).await;
@@ -73,6 +81,7 @@ pub async fn add_update_without_send(
     .as_object_id()
     .context("inserted_id is not object id")?
     .to_string();
+  record_update(update);
   Ok(id)
 }
 
@@ -81,11 +90,104 @@ pub async fn update_update(update: Update) -> anyhow::Result<()> {
   update_one_by_id(&db_client().updates, &update.id, mungos::update::Update::Set(to_document(&update)?), None)
     .await
     .context("failed to update the update on db. the update build process was deleted")?;
+  record_update(&update);
+  if update.status == UpdateStatus::Complete && !update.success {
+    tokio::spawn(alert_failed_update(update.clone()));
+  }
   let update = update_list_item(update).await?;
   let _ = send_update(update).await;
   Ok(())
 }
 
+/// Sends the failure alert for executions which
+/// don't handle alerting themselves (unlike RunBuild / BuildRepo).
+async fn alert_failed_update(update: Update) {
+  let res = async {
+    let (_, target_id) = update.target.extract_variant_id();
+    let data = match update.operation {
+      Operation::RunProcedure => {
+        let procedure = resource::get::<Procedure>(target_id).await?;
+        AlertData::ProcedureFailed {
+          id: procedure.id,
+          name: procedure.name,
+        }
+      }
+      Operation::RunAction => {
+        let action = resource::get::<Action>(target_id).await?;
+        AlertData::ActionFailed {
+          id: action.id,
+          name: action.name,
+        }
+      }
+      Operation::RunSync => {
+        let sync = resource::get::<ResourceSync>(target_id).await?;
+        AlertData::ResourceSyncFailed {
+          id: sync.id,
+          name: sync.name,
+        }
+      }
+      Operation::Deploy => {
+        let deployment =
+          resource::get::<Deployment>(target_id).await?;
+        if !deployment.config.send_alerts {
+          return Ok(());
+        }
+        let server_name =
+          resource::get::<Server>(&deployment.config.server_id)
+            .await
+            .map(|server| server.name)
+            .unwrap_or(String::from("unknown"));
+        AlertData::DeployFailed {
+          id: deployment.id,
+          name: deployment.name,
+          server_id: deployment.config.server_id,
+          server_name,
+        }
+      }
+      Operation::DeployStack | Operation::DeployStackService => {
+        let stack = resource::get::<Stack>(target_id).await?;
+        if !stack.config.send_alerts {
+          return Ok(());
+        }
+        let server_name =
+          resource::get::<Server>(&stack.config.server_id)
+            .await
+            .map(|server| server.name)
+            .unwrap_or(String::from("unknown"));
+        AlertData::StackDeployFailed {
+          id: stack.id,
+          name: stack.name,
+          server_id: stack.config.server_id,
+          server_name,
+        }
+      }
+      _ => return Ok(()),
+    };
+    warn!("{:?} unsuccessful, alerting...", update.operation);
+    let ts = komodo_timestamp();
+    let alert = Alert {
+      id: Default::default(),
+      target: update.target,
+      ts,
+      resolved_ts: Some(ts),
+      resolved: true,
+      level: SeverityLevel::Warning,
+      data,
+    };
+    send_alerts(&[alert.clone()]).await;
+    db_client()
+      .alerts
+      .insert_one(alert)
+      .await
+      .context("failed to record failed execution alert to db")?;
+    anyhow::Ok(())
+  }
+  .await;
+  if let Err(e) = res {
+    error!("failed to alert on failed execution | {e:#}");
+  }
+}
+
 #[instrument(level = "debug")]
 async fn update_list_item(
   update: Update,
@@ -236,6 +338,18 @@ pub async fn init_execution_update(
         resource::get::<Server>(&data.server).await?.id,
       ),
     ),
+    ExecuteRequest::BackupVolume(data) => (
+      Operation::BackupVolume,
+      ResourceTarget::Server(
+        resource::get::<Server>(&data.server).await?.id,
+      ),
+    ),
+    ExecuteRequest::RestoreVolume(data) => (
+      Operation::RestoreVolume,
+      ResourceTarget::Server(
+        resource::get::<Server>(&data.server).await?.id,
+      ),
+    ),
     ExecuteRequest::PruneDockerBuilders(data) => (
       Operation::PruneDockerBuilders,
       ResourceTarget::Server(
@@ -520,3 +634,45 @@ pub async fn init_execution_update(
 
   Ok(update)
 }
+
+/// How often the pull progress is polled into the update.
+const PULL_PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Awaits the periphery request which pulls images,
+/// meanwhile polling the pull progress into the in-progress update.
+/// The request must be sent with the update id as the `progress_id`.
+/// The progress log is replaced by the request logs once it finishes.
+pub async fn with_pull_progress<T>(
+  periphery: &PeripheryClient,
+  update: &Update,
+  request: impl Future<Output = T>,
+) -> T {
+  let mut request = std::pin::pin!(request);
+  if update.id.is_empty() {
+    return request.await;
+  }
+  loop {
+    if let Ok(res) =
+      tokio::time::timeout(PULL_PROGRESS_INTERVAL, &mut request).await
+    {
+      return res;
+    }
+    let progress = match periphery
+      .request(GetPullProgress {
+        id: update.id.clone(),
+      })
+      .await
+    {
+      Ok(Some(progress)) => progress,
+      // The pull hasn't started yet, or is already finished
+      Ok(None) => continue,
+      // Periphery doesn't support pull progress
+      Err(_) => return request.await,
+    };
+    let mut update = update.clone();
+    update.push_simple_log("Pull Progress", progress);
+    if let Err(e) = update_update(update).await {
+      warn!("Failed to update pull progress | {e:#}");
+    }
+  }
+}
@@ -21,6 +21,7 @@ mod config;
 mod db;
 mod helpers;
 mod listener;
+mod metrics;
 mod monitor;
 mod resource;
 mod stack;
@@ -37,6 +38,14 @@ async fn app() -> anyhow::Result<()> {
   info!("Komodo Core version: v{}", env!("CARGO_PKG_VERSION"));
   info!("{:?}", config.sanitized());
 
+  // Present the client cert to periphery, to crash on invalid cert
+  if let (Some(cert_file), Some(key_file)) = (
+    &config.periphery_client_cert_file,
+    &config.periphery_client_key_file,
+  ) {
+    periphery_client::tls::init_client_identity(cert_file, key_file)?;
+  }
+
   tokio::join!(
     // Init db_client check to crash on db init failure
     state::init_db_client(),
@@ -54,12 +63,15 @@ async fn app() -> anyhow::Result<()> {
 
   // Spawn tasks
   monitor::spawn_monitor_loop();
+  monitor::spawn_container_stats_loop();
+  monitor::spawn_docker_event_listeners();
   resource::spawn_resource_refresh_loop();
   resource::spawn_build_state_refresh_loop();
   resource::spawn_repo_state_refresh_loop();
   resource::spawn_procedure_state_refresh_loop();
   resource::spawn_action_state_refresh_loop();
   helpers::prune::spawn_prune_loop();
+  helpers::rollup::spawn_stats_rollup_loop();
 
   // Setup static frontend services
   let frontend_path = &config.frontend_path;
@@ -74,8 +86,10 @@ async fn app() -> anyhow::Result<()> {
     .nest("/read", api::read::router())
     .nest("/write", api::write::router())
     .nest("/execute", api::execute::router())
+    .nest("/copy", api::copy::router())
     .nest("/listener", listener::router())
     .nest("/ws", ws::router())
+    .nest("/metrics", metrics::router())
     .nest("/client", ts_client::router())
     .fallback_service(serve_frontend)
     .layer(
@@ -0,0 +1,703 @@
+//! Serves Prometheus metrics at `/metrics`,
+//! in the text exposition format.
+
+use std::{
+  collections::HashMap,
+  fmt::{Display, Write},
+  sync::{Mutex, OnceLock},
+  time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use axum::{
+  Router,
+  http::{HeaderMap, HeaderValue, header},
+  routing::get,
+};
+use derive_variants::ExtractVariant;
+use komodo_client::entities::{
+  Operation,
+  update::{Update, UpdateStatus},
+};
+use mungos::{find::find_collect, mongodb::bson::doc};
+use reqwest::StatusCode;
+use serror::AddStatusCodeError;
+use subtle::ConstantTimeEq;
+
+use crate::{
+  config::core_config,
+  state::{
+    build_state_cache, db_client, deployment_status_cache,
+    procedure_state_cache, server_status_cache, stack_status_cache,
+  },
+};
+
+pub fn router() -> Router {
+  Router::new().route("/", get(serve_metrics))
+}
+
+async fn serve_metrics(
+  headers: HeaderMap,
+) -> serror::Result<(HeaderMap, String)> {
+  let config = core_config();
+  if !config.metrics_enabled {
+    return Err(
+      anyhow!("Metrics are not enabled")
+        .status_code(StatusCode::NOT_FOUND),
+    );
+  }
+  if !config.metrics_token.is_empty() {
+    let token = headers
+      .get(header::AUTHORIZATION)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.strip_prefix("Bearer "));
+    // Compared in constant time so the token can't be guessed by timing
+    let valid = token.is_some_and(|token| {
+      token
+        .as_bytes()
+        .ct_eq(config.metrics_token.as_bytes())
+        .into()
+    });
+    if !valid {
+      return Err(
+        anyhow!("Invalid metrics token")
+          .status_code(StatusCode::UNAUTHORIZED),
+      );
+    }
+  }
+
+  let mut metrics = Metrics::default();
+  write_core_metrics(&mut metrics);
+  write_server_metrics(&mut metrics).await?;
+  write_resource_metrics(&mut metrics).await?;
+  write_alert_metrics(&mut metrics).await?;
+  write_update_metrics(&mut metrics);
+  write_monitor_metrics(&mut metrics);
+
+  let mut headers = HeaderMap::new();
+  headers.insert(
+    header::CONTENT_TYPE,
+    HeaderValue::from_static(
+      "text/plain; version=0.0.4; charset=utf-8",
+    ),
+  );
+  Ok((headers, metrics.out))
+}
+
+// =============
+// = RECORDING =
+// =============
+
+#[derive(Default, Clone, Copy)]
+struct UpdateMetrics {
+  success: u64,
+  failed: u64,
+  duration_sum: Duration,
+}
+
+fn update_metrics()
+-> &'static Mutex<HashMap<Operation, UpdateMetrics>> {
+  static UPDATE_METRICS: OnceLock<
+    Mutex<HashMap<Operation, UpdateMetrics>>,
+  > = OnceLock::new();
+  UPDATE_METRICS.get_or_init(Default::default)
+}
+
+/// Count the update and its duration, if it is complete.
+pub fn record_update(update: &Update) {
+  if update.status != UpdateStatus::Complete {
+    return;
+  }
+  let duration = update
+    .end_ts
+    .map(|end_ts| (end_ts - update.start_ts).max(0) as u64)
+    .unwrap_or_default();
+  let mut metrics = update_metrics().lock().unwrap();
+  let metrics = metrics.entry(update.operation).or_default();
+  if update.success {
+    metrics.success += 1;
+  } else {
+    metrics.failed += 1;
+  }
+  metrics.duration_sum += Duration::from_millis(duration);
+}
+
+#[derive(Default, Clone, Copy)]
+struct MonitorMetrics {
+  last: Duration,
+  sum: Duration,
+  count: u64,
+}
+
+fn monitor_metrics() -> &'static Mutex<MonitorMetrics> {
+  static MONITOR_METRICS: OnceLock<Mutex<MonitorMetrics>> =
+    OnceLock::new();
+  MONITOR_METRICS.get_or_init(Default::default)
+}
+
+/// Record the time taken by one pass of the monitor loop.
+pub fn record_monitor_loop(duration: Duration) {
+  let mut metrics = monitor_metrics().lock().unwrap();
+  metrics.last = duration;
+  metrics.sum += duration;
+  metrics.count += 1;
+}
+
+// ===========
+// = WRITING =
+// ===========
+
+#[derive(Default)]
+struct Metrics {
+  out: String,
+}
+
+impl Metrics {
+  fn family(&mut self, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(self.out, "# HELP {name} {help}");
+    let _ = writeln!(self.out, "# TYPE {name} {kind}");
+  }
+
+  fn sample(
+    &mut self,
+    name: &str,
+    labels: &[(&str, &str)],
+    value: impl Display,
+  ) {
+    self.out.push_str(name);
+    if !labels.is_empty() {
+      self.out.push('{');
+      for (i, (label, value)) in labels.iter().enumerate() {
+        if i > 0 {
+          self.out.push(',');
+        }
+        let _ = write!(self.out, "{label}=\"{}\"", escape(value));
+      }
+      self.out.push('}');
+    }
+    let _ = writeln!(self.out, " {value}");
+  }
+}
+
+fn escape(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n")
+}
+
+fn write_core_metrics(metrics: &mut Metrics) {
+  metrics.family("komodo_core_info", "gauge", "Komodo Core version.");
+  metrics.sample(
+    "komodo_core_info",
+    &[("version", env!("CARGO_PKG_VERSION"))],
+    1,
+  );
+}
+
+async fn write_server_metrics(
+  metrics: &mut Metrics,
+) -> anyhow::Result<()> {
+  let servers = find_collect(&db_client().servers, None, None)
+    .await
+    .context("failed to get servers from db")?;
+  let mut statuses = Vec::with_capacity(servers.len());
+  for server in servers {
+    let status = server_status_cache().get(&server.id).await;
+    statuses.push((server, status));
+  }
+
+  metrics.family(
+    "komodo_server_state",
+    "gauge",
+    "The server state, labeled by state.",
+  );
+  for (server, status) in &statuses {
+    let state = status
+      .as_ref()
+      .map(|status| format!("{:?}", status.state))
+      .unwrap_or_else(|| String::from("Unknown"));
+    metrics.sample(
+      "komodo_server_state",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+        ("state", state.as_str()),
+      ],
+      1,
+    );
+  }
+
+  let stats = statuses
+    .iter()
+    .filter_map(|(server, status)| {
+      Some((server, status.as_ref()?.stats.as_ref()?))
+    })
+    .collect::<Vec<_>>();
+
+  metrics.family(
+    "komodo_server_cpu_percent",
+    "gauge",
+    "Server CPU usage in percent.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_cpu_percent",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.cpu_perc,
+    );
+  }
+
+  metrics.family(
+    "komodo_server_memory_used_gb",
+    "gauge",
+    "Server memory used in GB.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_memory_used_gb",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.mem_used_gb,
+    );
+  }
+
+  metrics.family(
+    "komodo_server_memory_total_gb",
+    "gauge",
+    "Server memory total in GB.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_memory_total_gb",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.mem_total_gb,
+    );
+  }
+
+  metrics.family(
+    "komodo_server_swap_used_gb",
+    "gauge",
+    "Server swap used in GB.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_swap_used_gb",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.swap_used_gb,
+    );
+  }
+
+  metrics.family(
+    "komodo_server_swap_total_gb",
+    "gauge",
+    "Server swap total in GB.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_swap_total_gb",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.swap_total_gb,
+    );
+  }
+
+  metrics.family(
+    "komodo_server_load_average",
+    "gauge",
+    "Server load average, labeled by window.",
+  );
+  for (server, stats) in &stats {
+    for (window, load) in [
+      ("1m", stats.load_average.one),
+      ("5m", stats.load_average.five),
+      ("15m", stats.load_average.fifteen),
+    ] {
+      metrics.sample(
+        "komodo_server_load_average",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("window", window),
+        ],
+        load,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_disk_used_gb",
+    "gauge",
+    "Server disk used in GB, labeled by mount point.",
+  );
+  for (server, stats) in &stats {
+    for disk in &stats.disks {
+      metrics.sample(
+        "komodo_server_disk_used_gb",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("mount", disk.mount.to_string_lossy().as_ref()),
+        ],
+        disk.used_gb,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_disk_total_gb",
+    "gauge",
+    "Server disk total in GB, labeled by mount point.",
+  );
+  for (server, stats) in &stats {
+    for disk in &stats.disks {
+      metrics.sample(
+        "komodo_server_disk_total_gb",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("mount", disk.mount.to_string_lossy().as_ref()),
+        ],
+        disk.total_gb,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_disk_inodes_used",
+    "gauge",
+    "Server disk inodes used, labeled by mount point.",
+  );
+  for (server, stats) in &stats {
+    for disk in &stats.disks {
+      metrics.sample(
+        "komodo_server_disk_inodes_used",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("mount", disk.mount.to_string_lossy().as_ref()),
+        ],
+        disk.inodes_used,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_disk_inodes_total",
+    "gauge",
+    "Server disk inodes total, labeled by mount point.",
+  );
+  for (server, stats) in &stats {
+    for disk in &stats.disks {
+      metrics.sample(
+        "komodo_server_disk_inodes_total",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("mount", disk.mount.to_string_lossy().as_ref()),
+        ],
+        disk.inodes_total,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_disk_read_bytes_per_second",
+    "gauge",
+    "Server disk read throughput in bytes per second, labeled by mount point.",
+  );
+  for (server, stats) in &stats {
+    for disk in &stats.disks {
+      metrics.sample(
+        "komodo_server_disk_read_bytes_per_second",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("mount", disk.mount.to_string_lossy().as_ref()),
+        ],
+        disk.read_bytes_per_sec,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_disk_write_bytes_per_second",
+    "gauge",
+    "Server disk write throughput in bytes per second, labeled by mount point.",
+  );
+  for (server, stats) in &stats {
+    for disk in &stats.disks {
+      metrics.sample(
+        "komodo_server_disk_write_bytes_per_second",
+        &[
+          ("server_id", server.id.as_str()),
+          ("server", server.name.as_str()),
+          ("mount", disk.mount.to_string_lossy().as_ref()),
+        ],
+        disk.write_bytes_per_sec,
+      );
+    }
+  }
+
+  metrics.family(
+    "komodo_server_network_ingress_bytes",
+    "gauge",
+    "Server network ingress in bytes over the last polling interval.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_network_ingress_bytes",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.network_ingress_bytes,
+    );
+  }
+
+  metrics.family(
+    "komodo_server_network_egress_bytes",
+    "gauge",
+    "Server network egress in bytes over the last polling interval.",
+  );
+  for (server, stats) in &stats {
+    metrics.sample(
+      "komodo_server_network_egress_bytes",
+      &[
+        ("server_id", server.id.as_str()),
+        ("server", server.name.as_str()),
+      ],
+      stats.network_egress_bytes,
+    );
+  }
+
+  Ok(())
+}
+
+async fn write_resource_metrics(
+  metrics: &mut Metrics,
+) -> anyhow::Result<()> {
+  let db = db_client();
+  let (deployments, stacks, builds, procedures) = tokio::try_join!(
+    find_collect(&db.deployments, None, None),
+    find_collect(&db.stacks, None, None),
+    find_collect(&db.builds, None, None),
+    find_collect(&db.procedures, None, None),
+  )
+  .context("failed to get resources from db")?;
+
+  metrics.family(
+    "komodo_deployment_state",
+    "gauge",
+    "The deployment state, labeled by state.",
+  );
+  for deployment in deployments {
+    let state = deployment_status_cache()
+      .get(&deployment.id)
+      .await
+      .map(|status| status.curr.state)
+      .unwrap_or_default();
+    metrics.sample(
+      "komodo_deployment_state",
+      &[
+        ("deployment_id", deployment.id.as_str()),
+        ("deployment", deployment.name.as_str()),
+        ("state", state.to_string().as_str()),
+      ],
+      1,
+    );
+  }
+
+  metrics.family(
+    "komodo_stack_state",
+    "gauge",
+    "The stack state, labeled by state.",
+  );
+  for stack in stacks {
+    let state = stack_status_cache()
+      .get(&stack.id)
+      .await
+      .map(|status| status.curr.state)
+      .unwrap_or_default();
+    metrics.sample(
+      "komodo_stack_state",
+      &[
+        ("stack_id", stack.id.as_str()),
+        ("stack", stack.name.as_str()),
+        ("state", state.to_string().as_str()),
+      ],
+      1,
+    );
+  }
+
+  metrics.family(
+    "komodo_build_state",
+    "gauge",
+    "The build state, labeled by state.",
+  );
+  for build in builds {
+    let state =
+      build_state_cache().get(&build.id).await.unwrap_or_default();
+    metrics.sample(
+      "komodo_build_state",
+      &[
+        ("build_id", build.id.as_str()),
+        ("build", build.name.as_str()),
+        ("state", state.to_string().as_str()),
+      ],
+      1,
+    );
+  }
+
+  metrics.family(
+    "komodo_procedure_state",
+    "gauge",
+    "The procedure state, labeled by state.",
+  );
+  for procedure in procedures {
+    let state = procedure_state_cache()
+      .get(&procedure.id)
+      .await
+      .unwrap_or_default();
+    metrics.sample(
+      "komodo_procedure_state",
+      &[
+        ("procedure_id", procedure.id.as_str()),
+        ("procedure", procedure.name.as_str()),
+        ("state", state.to_string().as_str()),
+      ],
+      1,
+    );
+  }
+
+  Ok(())
+}
+
+async fn write_alert_metrics(
+  metrics: &mut Metrics,
+) -> anyhow::Result<()> {
+  let alerts = find_collect(
+    &db_client().alerts,
+    doc! { "resolved": false },
+    None,
+  )
+  .await
+  .context("failed to get open alerts from db")?;
+
+  // (type, level) -> count
+  let mut counts = HashMap::<(String, String), u64>::new();
+  for alert in alerts {
+    let key = (
+      format!("{:?}", alert.data.extract_variant()),
+      alert.level.to_string(),
+    );
+    *counts.entry(key).or_default() += 1;
+  }
+  let mut counts = counts.into_iter().collect::<Vec<_>>();
+  counts.sort();
+
+  metrics.family(
+    "komodo_open_alerts",
+    "gauge",
+    "The number of open alerts, labeled by alert type and level.",
+  );
+  for ((alert_type, level), count) in counts {
+    metrics.sample(
+      "komodo_open_alerts",
+      &[("type", alert_type.as_str()), ("level", level.as_str())],
+      count,
+    );
+  }
+
+  Ok(())
+}
+
+fn write_update_metrics(metrics: &mut Metrics) {
+  let mut updates = update_metrics()
+    .lock()
+    .unwrap()
+    .iter()
+    .map(|(operation, metrics)| (operation.to_string(), *metrics))
+    .collect::<Vec<_>>();
+  updates.sort_by(|a, b| a.0.cmp(&b.0));
+
+  metrics.family(
+    "komodo_updates_total",
+    "counter",
+    "The number of completed updates, labeled by operation and success.",
+  );
+  for (operation, update) in &updates {
+    metrics.sample(
+      "komodo_updates_total",
+      &[("operation", operation.as_str()), ("success", "true")],
+      update.success,
+    );
+    metrics.sample(
+      "komodo_updates_total",
+      &[("operation", operation.as_str()), ("success", "false")],
+      update.failed,
+    );
+  }
+
+  metrics.family(
+    "komodo_update_duration_seconds",
+    "summary",
+    "The duration of completed updates, labeled by operation.",
+  );
+  for (operation, update) in &updates {
+    metrics.sample(
+      "komodo_update_duration_seconds_sum",
+      &[("operation", operation.as_str())],
+      update.duration_sum.as_secs_f64(),
+    );
+    metrics.sample(
+      "komodo_update_duration_seconds_count",
+      &[("operation", operation.as_str())],
+      update.success + update.failed,
+    );
+  }
+}
+
+fn write_monitor_metrics(metrics: &mut Metrics) {
+  let monitor = *monitor_metrics().lock().unwrap();
+
+  metrics.family(
+    "komodo_monitor_loop_last_duration_seconds",
+    "gauge",
+    "The duration of the latest monitor loop pass.",
+  );
+  metrics.sample(
+    "komodo_monitor_loop_last_duration_seconds",
+    &[],
+    monitor.last.as_secs_f64(),
+  );
+
+  metrics.family(
+    "komodo_monitor_loop_duration_seconds",
+    "summary",
+    "The duration of monitor loop passes.",
+  );
+  metrics.sample(
+    "komodo_monitor_loop_duration_seconds_sum",
+    &[],
+    monitor.sum.as_secs_f64(),
+  );
+  metrics.sample(
+    "komodo_monitor_loop_duration_seconds_count",
+    &[],
+    monitor.count,
+  );
+}
@@ -15,6 +15,8 @@ use crate::{
 pub async fn alert_deployments(
   ts: i64,
   server_names: &HashMap<String, String>,
+  // Only alert for deployments on this server
+  server_id: Option<&str>,
 ) {
   let mut alerts = Vec::<Alert>::new();
   for status in deployment_status_cache().get_list().await {
@@ -42,7 +44,10 @@ pub async fn alert_deployments(
       else {
         continue;
       };
-      if !deployment.config.send_alerts {
+      if !deployment.config.send_alerts
+        || server_id
+          .is_some_and(|id| id != deployment.config.server_id)
+      {
         continue;
       }
       let target: ResourceTarget = (&deployment).into();
@@ -24,8 +24,26 @@ pub async fn check_alerts(ts: i64) {
 
   tokio::join!(
     server::alert_servers(ts, servers),
-    deployment::alert_deployments(ts, &server_names),
-    stack::alert_stacks(ts, &server_names)
+    deployment::alert_deployments(ts, &server_names, None),
+    stack::alert_stacks(ts, &server_names, None)
+  );
+}
+
+/// Only checks the container / stack state alerts on the server,
+/// called after an out of band cache update for the server.
+#[instrument(level = "debug")]
+pub async fn check_server_state_alerts(ts: i64, server_id: &str) {
+  let (_, server_names) = match get_all_servers_map().await {
+    Ok(res) => res,
+    Err(e) => {
+      error!("{e:#?}");
+      return;
+    }
+  };
+
+  tokio::join!(
+    deployment::alert_deployments(ts, &server_names, Some(server_id)),
+    stack::alert_stacks(ts, &server_names, Some(server_id))
   );
 }
 
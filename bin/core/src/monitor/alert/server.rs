@@ -17,6 +17,9 @@ use mungos::{
 
 use crate::{
   alert::send_alerts,
+  helpers::{
+    forecast::get_disk_forecasts, periphery_version_matches_core,
+  },
   state::{db_client, server_status_cache},
 };
 
@@ -32,13 +35,14 @@ pub async fn alert_servers(
 ) {
   let server_statuses = server_status_cache().get_list().await;
 
-  let (alerts, disk_alerts) = match get_open_alerts().await {
-    Ok(alerts) => alerts,
-    Err(e) => {
-      error!("{e:#}");
-      return;
-    }
-  };
+  let (alerts, disk_alerts, inode_alerts, forecast_alerts) =
+    match get_open_alerts().await {
+      Ok(alerts) => alerts,
+      Err(e) => {
+        error!("{e:#}");
+        return;
+      }
+    };
 
   let mut alerts_to_open = Vec::<(Alert, SendAlerts)>::new();
   let mut alerts_to_update = Vec::<(Alert, SendAlerts)>::new();
@@ -112,6 +116,53 @@ pub async fn alert_servers(
       _ => {}
     }
 
+    // ===================
+    // PERIPHERY VERSION
+    // ===================
+    let version_alert = server_alerts.as_ref().and_then(|alerts| {
+      alerts.get(&AlertDataVariant::PeripheryVersionMismatch)
+    });
+    let version_matches =
+      periphery_version_matches_core(&server_status.version);
+    match (server_status.state, version_matches, version_alert) {
+      (ServerState::Ok, false, None) => {
+        // open version mismatch alert
+        let alert = Alert {
+          id: Default::default(),
+          ts,
+          resolved: false,
+          resolved_ts: None,
+          level: SeverityLevel::Warning,
+          target: ResourceTarget::Server(server_status.id.clone()),
+          data: AlertData::PeripheryVersionMismatch {
+            id: server_status.id.clone(),
+            name: server.name.clone(),
+            region: optional_string(&server.config.region),
+            periphery_version: server_status.version.clone(),
+            core_version: env!("CARGO_PKG_VERSION").to_string(),
+          },
+        };
+        alerts_to_open
+          .push((alert, server.config.send_version_mismatch_alerts))
+      }
+      // Close an open alert once versions match,
+      // or the server is disabled.
+      (ServerState::Ok, true, Some(alert))
+      | (ServerState::Disabled, _, Some(alert)) => {
+        let mut alert = alert.clone();
+        alert.data = AlertData::PeripheryVersionMismatch {
+          id: server_status.id.clone(),
+          name: server.name.clone(),
+          region: optional_string(&server.config.region),
+          periphery_version: server_status.version.clone(),
+          core_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        alert_ids_to_close
+          .push((alert, server.config.send_version_mismatch_alerts));
+      }
+      _ => {}
+    }
+
     let Some(health) = &server_status.health else {
       continue;
     };
@@ -273,6 +324,67 @@ pub async fn alert_servers(
       _ => {}
     }
 
+    // ===================
+    // SERVER SWAP
+    // ===================
+    let swap_alert = server_alerts
+      .as_ref()
+      .and_then(|alerts| alerts.get(&AlertDataVariant::ServerSwap))
+      .cloned();
+    let swap_data = || AlertData::ServerSwap {
+      id: server_status.id.clone(),
+      name: server.name.clone(),
+      region: optional_string(&server.config.region),
+      used_gb: server_status
+        .stats
+        .as_ref()
+        .map(|s| s.swap_used_gb)
+        .unwrap_or(0.0),
+      total_gb: server_status
+        .stats
+        .as_ref()
+        .map(|s| s.swap_total_gb)
+        .unwrap_or(0.0),
+    };
+    match (
+      health.swap.level,
+      swap_alert,
+      health.swap.should_close_alert,
+    ) {
+      (SeverityLevel::Warning | SeverityLevel::Critical, None, _) => {
+        // open alert
+        let alert = Alert {
+          id: Default::default(),
+          ts,
+          resolved: false,
+          resolved_ts: None,
+          level: health.swap.level,
+          target: ResourceTarget::Server(server_status.id.clone()),
+          data: swap_data(),
+        };
+        alerts_to_open.push((alert, server.config.send_mem_alerts));
+      }
+      (
+        SeverityLevel::Warning | SeverityLevel::Critical,
+        Some(mut alert),
+        _,
+      ) => {
+        // modify alert level only if it has increased
+        if alert.level < health.swap.level {
+          alert.level = health.swap.level;
+          alert.data = swap_data();
+          alerts_to_update
+            .push((alert, server.config.send_mem_alerts));
+        }
+      }
+      (SeverityLevel::Ok, Some(mut alert), true) => {
+        alert.data = swap_data();
+        alert_ids_to_close
+          .push((alert, server.config.send_mem_alerts))
+      }
+      _ => {}
+    }
+
     // ===================
     // SERVER DISK
     // ===================
@@ -369,6 +481,160 @@ pub async fn alert_servers(
         }
       }
     }
+
+    // ===================
+    // SERVER DISK INODES
+    // ===================
+
+    let server_inode_alerts = inode_alerts
+      .get(&ResourceTarget::Server(server_status.id.clone()));
+
+    for (path, health) in &health.inodes {
+      let inode_alert = server_inode_alerts
+        .as_ref()
+        .and_then(|alerts| alerts.get(path))
+        .cloned();
+      let inode_data = || {
+        let disk = server_status.stats.as_ref().and_then(|stats| {
+          stats.disks.iter().find(|disk| disk.mount == *path)
+        });
+        AlertData::ServerDiskInodes {
+          id: server_status.id.clone(),
+          name: server.name.clone(),
+          region: optional_string(&server.config.region),
+          path: path.to_owned(),
+          used: disk.map(|d| d.inodes_used).unwrap_or_default(),
+          total: disk.map(|d| d.inodes_total).unwrap_or_default(),
+        }
+      };
+      match (health.level, inode_alert, health.should_close_alert) {
+        (
+          SeverityLevel::Warning | SeverityLevel::Critical,
+          None,
+          _,
+        ) => {
+          let alert = Alert {
+            id: Default::default(),
+            ts,
+            resolved: false,
+            resolved_ts: None,
+            level: health.level,
+            target: ResourceTarget::Server(server_status.id.clone()),
+            data: inode_data(),
+          };
+          alerts_to_open
+            .push((alert, server.config.send_disk_alerts));
+        }
+        (
+          SeverityLevel::Warning | SeverityLevel::Critical,
+          Some(mut alert),
+          _,
+        ) => {
+          // modify alert level only if it has increased
+          if alert.level < health.level {
+            alert.level = health.level;
+            alert.data = inode_data();
+            alerts_to_update
+              .push((alert, server.config.send_disk_alerts));
+          }
+        }
+        (SeverityLevel::Ok, Some(mut alert), true) => {
+          alert.level = health.level;
+          alert.data = inode_data();
+          alert_ids_to_close
+            .push((alert, server.config.send_disk_alerts))
+        }
+        _ => {}
+      }
+    }
+
+    // Need to close any open ones on disks no longer reported
+    if let Some(inode_alerts) = server_inode_alerts {
+      for (path, alert) in inode_alerts {
+        if !health.inodes.contains_key(path) {
+          let mut alert = alert.clone();
+          alert.level = SeverityLevel::Ok;
+          alert_ids_to_close
+            .push((alert, server.config.send_disk_alerts));
+        }
+      }
+    }
+
+    // ===================
+    // SERVER DISK FORECAST
+    // ===================
+
+    let server_forecast_alerts = forecast_alerts
+      .get(&ResourceTarget::Server(server_status.id.clone()));
+    let threshold = server.config.disk_forecast_warning_days;
+
+    let forecasts = if threshold > 0.0 {
+      match get_disk_forecasts(&server_status.id).await {
+        Ok(forecasts) => forecasts,
+        Err(e) => {
+          warn!(
+            "failed to get disk forecasts for server {} | {e:#}",
+            server.name
+          );
+          continue;
+        }
+      }
+    } else {
+      Vec::new()
+    };
+
+    for forecast in &forecasts {
+      let forecast_alert = server_forecast_alerts
+        .as_ref()
+        .and_then(|alerts| alerts.get(&forecast.mount))
+        .cloned();
+      let days_until_full =
+        forecast.days_until_full.filter(|days| *days < threshold);
+      match (days_until_full, forecast_alert) {
+        (Some(days_until_full), None) => {
+          let alert = Alert {
+            id: Default::default(),
+            ts,
+            resolved: false,
+            resolved_ts: None,
+            level: SeverityLevel::Warning,
+            target: ResourceTarget::Server(server_status.id.clone()),
+            data: AlertData::ServerDiskForecast {
+              id: server_status.id.clone(),
+              name: server.name.clone(),
+              region: optional_string(&server.config.region),
+              path: forecast.mount.clone(),
+              used_gb: forecast.used_gb,
+              total_gb: forecast.total_gb,
+              growth_gb_per_day: forecast.growth_gb_per_day,
+              days_until_full,
+            },
+          };
+          alerts_to_open
+            .push((alert, server.config.send_disk_alerts));
+        }
+        (None, Some(alert)) => {
+          let mut alert = alert.clone();
+          alert.level = SeverityLevel::Ok;
+          alert_ids_to_close
+            .push((alert, server.config.send_disk_alerts));
+        }
+        _ => {}
+      }
+    }
+
+    // Close any open ones on disks no longer forecast,
+    // or if the forecast alert has been disabled.
+    if let Some(forecast_alerts) = server_forecast_alerts {
+      for (path, alert) in forecast_alerts {
+        if !forecasts.iter().any(|forecast| forecast.mount == *path) {
+          let mut alert = alert.clone();
+          alert.level = SeverityLevel::Ok;
+          alert_ids_to_close
+            .push((alert, server.config.send_disk_alerts));
+        }
+      }
+    }
   }
 
   tokio::join!(
@@ -532,8 +798,12 @@ async fn resolve_alerts(alerts: &[(Alert, SendAlerts)]) {
 }
 
 #[instrument(level = "debug")]
-async fn get_open_alerts()
--> anyhow::Result<(OpenAlertMap, OpenDiskAlertMap)> {
+async fn get_open_alerts() -> anyhow::Result<(
+  OpenAlertMap,
+  OpenDiskAlertMap,
+  OpenDiskAlertMap,
+  OpenDiskAlertMap,
+)> {
   let alerts = find_collect(
     &db_client().alerts,
     doc! { "resolved": false },
@@ -544,6 +814,8 @@ async fn get_open_alerts()
 
   let mut map = OpenAlertMap::new();
   let mut disk_map = OpenDiskAlertMap::new();
+  let mut inode_map = OpenDiskAlertMap::new();
+  let mut forecast_map = OpenDiskAlertMap::new();
 
   for alert in alerts {
     match &alert.data {
@@ -551,6 +823,16 @@ async fn get_open_alerts()
         let inner = disk_map.entry(alert.target.clone()).or_default();
         inner.insert(path.to_owned(), alert);
       }
+      AlertData::ServerDiskInodes { path, .. } => {
+        let inner =
+          inode_map.entry(alert.target.clone()).or_default();
+        inner.insert(path.to_owned(), alert);
+      }
+      AlertData::ServerDiskForecast { path, .. } => {
+        let inner =
+          forecast_map.entry(alert.target.clone()).or_default();
+        inner.insert(path.to_owned(), alert);
+      }
       _ => {
         let inner = map.entry(alert.target.clone()).or_default();
         inner.insert(alert.data.extract_variant(), alert);
@@ -558,5 +840,5 @@ async fn get_open_alerts()
     }
   }
 
-  Ok((map, disk_map))
+  Ok((map, disk_map, inode_map, forecast_map))
 }
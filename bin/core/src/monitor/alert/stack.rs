@@ -16,6 +16,8 @@ use crate::{
 pub async fn alert_stacks(
   ts: i64,
   server_names: &HashMap<String, String>,
+  // Only alert for stacks on this server
+  server_id: Option<&str>,
 ) {
   let mut alerts = Vec::<Alert>::new();
   for status in stack_status_cache().get_list().await {
@@ -41,7 +43,9 @@ pub async fn alert_stacks(
       else {
         continue;
       };
-      if !stack.config.send_alerts {
+      if !stack.config.send_alerts
+        || server_id.is_some_and(|id| id != stack.config.server_id)
+      {
         continue;
       }
       let target: ResourceTarget = (&stack).into();
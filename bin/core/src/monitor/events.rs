@@ -0,0 +1,176 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{Context, anyhow};
+use futures::{
+  StreamExt,
+  future::try_join_all,
+  stream::{SelectAll, select_all},
+};
+use komodo_client::entities::{komodo_timestamp, server::Server};
+use mungos::find::find_collect;
+use periphery_client::{
+  PeripheryWebSocket,
+  api::{
+    ListDockerEndpoints,
+    events::{DockerEvent, DockerEventType},
+  },
+};
+use tokio::{task::JoinHandle, time::Instant};
+use tokio_tungstenite::tungstenite;
+
+use crate::{
+  helpers::pinned_periphery_client, resource, state::db_client,
+};
+
+use super::{
+  alert::check_server_state_alerts, lock_server_refresh,
+  update_cache_for_server,
+};
+
+/// How often to (re)connect to servers which aren't subscribed.
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(30);
+/// Events usually come in bursts (eg. compose up),
+/// so wait for this long after the first before refreshing.
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Subscribes to the Docker events of each Server,
+/// refreshing its status cache as soon as containers change,
+/// rather than on the next monitoring poll.
+/// Servers using an outbound connection are only polled.
+pub fn spawn_docker_event_listeners() {
+  tokio::spawn(async move {
+    // server id -> (address, listener)
+    let mut listeners =
+      HashMap::<String, (String, JoinHandle<()>)>::new();
+    loop {
+      match find_collect(&db_client().servers, None, None).await {
+        Ok(servers) => {
+          let servers = servers
+            .into_iter()
+            .filter(|server| {
+              server.config.enabled && !server.config.outbound
+            })
+            .map(|server| (server.id.clone(), server))
+            .collect::<HashMap<_, _>>();
+          listeners.retain(|id, (address, listener)| {
+            let keep = !listener.is_finished()
+              && servers.get(id).is_some_and(|server| {
+                &server.config.address == address
+              });
+            if !keep {
+              listener.abort();
+            }
+            keep
+          });
+          for (id, server) in servers {
+            listeners.entry(id).or_insert_with(|| {
+              (
+                server.config.address.clone(),
+                tokio::spawn(listen_for_docker_events(server)),
+              )
+            });
+          }
+        }
+        Err(e) => {
+          error!("failed to get server list (docker events) | {e:#}");
+        }
+      }
+      tokio::time::sleep(RECONNECT_INTERVAL).await;
+    }
+  });
+}
+
+async fn listen_for_docker_events(server: Server) {
+  if let Err(e) = handle_docker_events(&server).await {
+    // Older periphery doesn't serve events,
+    // and unreachable servers are already reported by the monitor.
+    debug!("Server {} docker events closed | {e:#}", server.name);
+  }
+}
+
+async fn handle_docker_events(server: &Server) -> anyhow::Result<()> {
+  let periphery = pinned_periphery_client(server).await?;
+  let endpoints = periphery
+    .request(ListDockerEndpoints {})
+    .await
+    .context("failed to list docker endpoints")?;
+  // Subscribe to the default endpoint and every additional one,
+  // as the status cache includes the containers on all of them.
+  let sockets = try_join_all(
+    std::iter::once(String::new())
+      .chain(endpoints)
+      .map(|endpoint| {
+        let periphery =
+          periphery.clone().with_docker_endpoint(endpoint);
+        async move { periphery.subscribe_docker_events().await }
+      }),
+  )
+  .await?;
+  let mut socket = select_all(sockets);
+  loop {
+    let event = next_state_change(&mut socket).await?;
+    trace!("Server {} docker event | {event:?}", server.name);
+    // Take the rest of the burst before refreshing
+    let deadline = Instant::now() + DEBOUNCE;
+    while let Ok(res) = tokio::time::timeout_at(
+      deadline,
+      next_state_change(&mut socket),
+    )
+    .await
+    {
+      res?;
+    }
+    refresh_server(&server.id).await;
+  }
+}
+
+/// Skips events which don't change anything in the status cache.
+async fn next_state_change(
+  socket: &mut SelectAll<PeripheryWebSocket>,
+) -> anyhow::Result<DockerEvent> {
+  loop {
+    let text = match socket.next().await {
+      Some(Ok(tungstenite::Message::Text(text))) => text,
+      Some(Ok(tungstenite::Message::Close(_))) | None => {
+        return Err(anyhow!("connection closed"));
+      }
+      Some(Ok(_)) => continue,
+      Some(Err(e)) => {
+        return Err(
+          anyhow::Error::from(e).context("failed to read event"),
+        );
+      }
+    };
+    let event = serde_json::from_str::<DockerEvent>(text.as_str())
+      .context("invalid docker event")?;
+    if changes_state(&event) {
+      return Ok(event);
+    }
+  }
+}
+
+fn changes_state(event: &DockerEvent) -> bool {
+  if event.ty != DockerEventType::Container {
+    return true;
+  }
+  let action = event.action.as_str();
+  !(action.starts_with("exec_")
+    || matches!(
+      action,
+      "attach" | "resize" | "top" | "export" | "commit" | "copy"
+    ))
+}
+
+async fn refresh_server(server_id: &str) {
+  // Get the latest server, as the config may have changed.
+  let server = match resource::get::<Server>(server_id).await {
+    Ok(server) => server,
+    Err(e) => {
+      warn!("failed to get server for docker event | {e:#}");
+      return;
+    }
+  };
+  let _lock = lock_server_refresh(&server.id).await;
+  update_cache_for_server(&server).await;
+  check_server_state_alerts(komodo_timestamp(), &server.id).await;
+}
@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fmt::Write, sync::OnceLock};
+
+use anyhow::{Context, anyhow};
+use komodo_client::entities::{
+  config::core::StatsExportFormat,
+  stats::{ContainerStatsRecord, SystemStatsRecord},
+};
+use serde_json::{Value, json};
+
+use crate::config::core_config;
+
+/// A set of stats sharing the same labels.
+struct Point<'a> {
+  measurement: &'static str,
+  tags: Vec<(&'static str, &'a str)>,
+  fields: Vec<(&'static str, f64)>,
+}
+
+/// Pushes the stats recorded on this monitoring tick
+/// to the configured time series database.
+#[instrument(level = "debug", skip_all)]
+pub async fn export_stats(
+  ts: i64,
+  // server id -> server name
+  server_names: &HashMap<String, String>,
+  system_stats: &[SystemStatsRecord],
+  container_stats: &[ContainerStatsRecord],
+) {
+  let config = core_config();
+  if config.stats_export_format == StatsExportFormat::None
+    || config.stats_export_url.is_empty()
+  {
+    return;
+  }
+  let points =
+    stats_points(server_names, system_stats, container_stats);
+  if points.is_empty() {
+    return;
+  }
+  let (body, content_type) = match config.stats_export_format {
+    StatsExportFormat::None => return,
+    StatsExportFormat::Otlp => {
+      (otlp_body(ts, &points).to_string(), "application/json")
+    }
+    StatsExportFormat::Influx => {
+      (influx_body(ts, &points), "text/plain; charset=utf-8")
+    }
+  };
+  if let Err(e) = push(body, content_type).await {
+    warn!("failed to export stats | {e:#}");
+  }
+}
+
+async fn push(
+  body: String,
+  content_type: &'static str,
+) -> anyhow::Result<()> {
+  let config = core_config();
+  let mut req = http_client()
+    .post(&config.stats_export_url)
+    .header("Content-Type", content_type)
+    .body(body);
+  if !config.stats_export_token.is_empty() {
+    req = req.header("Authorization", &config.stats_export_token);
+  }
+  let res = req.send().await.context("failed to send request")?;
+  let status = res.status();
+  if status.is_success() {
+    return Ok(());
+  }
+  let text = res.text().await.unwrap_or_default();
+  Err(anyhow!("{status} | {text}"))
+}
+
+fn http_client() -> &'static reqwest::Client {
+  static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+  CLIENT.get_or_init(reqwest::Client::new)
+}
+
+fn stats_points<'a>(
+  server_names: &'a HashMap<String, String>,
+  system_stats: &'a [SystemStatsRecord],
+  container_stats: &'a [ContainerStatsRecord],
+) -> Vec<Point<'a>> {
+  let server_tags = |sid: &'a String| {
+    vec![
+      ("server_id", sid.as_str()),
+      (
+        "server",
+        server_names
+          .get(sid)
+          .map(String::as_str)
+          .unwrap_or_default(),
+      ),
+    ]
+  };
+  let mut points = Vec::new();
+  for stats in system_stats {
+    points.push(Point {
+      measurement: "server",
+      tags: server_tags(&stats.sid),
+      fields: vec![
+        ("cpu_perc", stats.cpu_perc as f64),
+        ("mem_used_gb", stats.mem_used_gb),
+        ("mem_total_gb", stats.mem_total_gb),
+        ("swap_used_gb", stats.swap_used_gb),
+        ("swap_total_gb", stats.swap_total_gb),
+        ("disk_used_gb", stats.disk_used_gb),
+        ("disk_total_gb", stats.disk_total_gb),
+        ("load_average_1m", stats.load_average.one),
+        ("load_average_5m", stats.load_average.five),
+        ("load_average_15m", stats.load_average.fifteen),
+        ("network_ingress_bytes", stats.network_ingress_bytes),
+        ("network_egress_bytes", stats.network_egress_bytes),
+      ],
+    });
+    for disk in &stats.disks {
+      let mut tags = server_tags(&stats.sid);
+      tags.push(("mount", disk.mount.to_str().unwrap_or_default()));
+      points.push(Point {
+        measurement: "server_disk",
+        tags,
+        fields: vec![
+          ("used_gb", disk.used_gb),
+          ("total_gb", disk.total_gb),
+          ("inodes_used", disk.inodes_used as f64),
+          ("inodes_total", disk.inodes_total as f64),
+          ("read_bytes_per_sec", disk.read_bytes_per_sec),
+          ("write_bytes_per_sec", disk.write_bytes_per_sec),
+        ],
+      });
+    }
+  }
+  for stats in container_stats {
+    let mut tags = server_tags(&stats.sid);
+    tags.push(("container", stats.container.as_str()));
+    points.push(Point {
+      measurement: "container",
+      tags,
+      fields: vec![
+        ("cpu_perc", stats.cpu_perc),
+        ("mem_perc", stats.mem_perc),
+        ("mem_used_bytes", stats.mem_used_bytes),
+        ("mem_limit_bytes", stats.mem_limit_bytes),
+        ("network_ingress_bytes", stats.network_ingress_bytes),
+        ("network_egress_bytes", stats.network_egress_bytes),
+        ("block_read_bytes", stats.block_read_bytes),
+        ("block_write_bytes", stats.block_write_bytes),
+        ("pids", stats.pids as f64),
+      ],
+    });
+  }
+  points
+}
+
+/// Each point is written as one line,
+/// eg. `komodo_server,server_id=..,server=.. cpu_perc=12.5,... <ts ns>`
+fn influx_body(ts: i64, points: &[Point]) -> String {
+  let ts_ns = ts as i128 * 1_000_000;
+  let mut body = String::new();
+  for point in points {
+    // Influx doesn't accept NaN / inf field values
+    let fields = point
+      .fields
+      .iter()
+      .filter(|(_, value)| value.is_finite())
+      .collect::<Vec<_>>();
+    if fields.is_empty() {
+      continue;
+    }
+    body.push_str("komodo_");
+    body.push_str(point.measurement);
+    for (key, value) in &point.tags {
+      // Empty tag values are not allowed
+      if value.is_empty() {
+        continue;
+      }
+      let _ = write!(body, ",{key}={}", escape_influx_tag(value));
+    }
+    for (i, (key, value)) in fields.into_iter().enumerate() {
+      let sep = if i == 0 { ' ' } else { ',' };
+      let _ = write!(body, "{sep}{key}={value}");
+    }
+    let _ = writeln!(body, " {ts_ns}");
+  }
+  body
+}
+
+fn escape_influx_tag(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace(',', "\\,")
+    .replace('=', "\\=")
+    .replace(' ', "\\ ")
+}
+
+/// Each field is written as a gauge named
+/// `komodo.<measurement>.<field>`, with the tags as attributes.
+fn otlp_body(ts: i64, points: &[Point]) -> Value {
+  let ts_ns = (ts as i128 * 1_000_000).to_string();
+  // Preserve the metric order for readability
+  let mut names = Vec::<String>::new();
+  let mut data_points = HashMap::<String, Vec<Value>>::new();
+  for point in points {
+    let attributes = point
+      .tags
+      .iter()
+      .map(|(key, value)| {
+        json!({ "key": key, "value": { "stringValue": value } })
+      })
+      .collect::<Vec<_>>();
+    for (field, value) in &point.fields {
+      let name = format!("komodo.{}.{field}", point.measurement);
+      let entry =
+        data_points.entry(name.clone()).or_insert_with(|| {
+          names.push(name);
+          Vec::new()
+        });
+      entry.push(json!({
+        "attributes": attributes,
+        "timeUnixNano": ts_ns,
+        "asDouble": value,
+      }));
+    }
+  }
+  let metrics = names
+    .into_iter()
+    .map(|name| {
+      let data_points = data_points.remove(&name).unwrap_or_default();
+      json!({ "name": name, "gauge": { "dataPoints": data_points } })
+    })
+    .collect::<Vec<_>>();
+  json!({
+    "resourceMetrics": [{
+      "resource": {
+        "attributes": [{
+          "key": "service.name",
+          "value": {
+            "stringValue": core_config().logging.opentelemetry_service_name
+          }
+        }]
+      },
+      "scopeMetrics": [{
+        "scope": {
+          "name": "komodo",
+          "version": env!("CARGO_PKG_VERSION"),
+        },
+        "metrics": metrics,
+      }]
+    }]
+  })
+}
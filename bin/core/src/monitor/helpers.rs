@@ -138,6 +138,8 @@ fn get_server_health(
     cpu_perc,
     mem_used_gb,
     mem_total_gb,
+    swap_used_gb,
+    swap_total_gb,
     disks,
     ..
   }: &SystemStats,
@@ -147,8 +149,12 @@ fn get_server_health(
     cpu_critical,
     mem_warning,
     mem_critical,
+    swap_warning,
+    swap_critical,
     disk_warning,
     disk_critical,
+    inode_warning,
+    inode_critical,
     ..
   } = &server.config;
   let mut health = ServerHealth::default();
@@ -172,10 +178,28 @@ fn get_server_health(
     health.mem.should_close_alert = true
   }
 
+  // Hosts without swap never alert
+  let swap_perc = if *swap_total_gb > 0.0 {
+    100.0 * swap_used_gb / swap_total_gb
+  } else {
+    0.0
+  };
+  if swap_perc >= *swap_critical {
+    health.swap.level = SeverityLevel::Critical
+  } else if swap_perc >= *swap_warning {
+    health.swap.level = SeverityLevel::Warning
+  } else if swap_perc
+    < swap_warning - (ALERT_PERCENTAGE_THRESHOLD as f64)
+  {
+    health.swap.should_close_alert = true
+  }
+
   for SingleDiskUsage {
     mount,
     used_gb,
     total_gb,
+    inodes_used,
+    inodes_total,
     ..
   } in disks
   {
@@ -191,6 +215,23 @@ fn get_server_health(
       state.should_close_alert = true;
     };
     health.disks.insert(mount.clone(), state);
+
+    // Some file systems (eg btrfs) don't report inodes
+    if *inodes_total <= 0 {
+      continue;
+    }
+    let perc = 100.0 * *inodes_used as f64 / *inodes_total as f64;
+    let mut state = ServerHealthState::default();
+    if perc >= *inode_critical {
+      state.level = SeverityLevel::Critical;
+    } else if perc >= *inode_warning {
+      state.level = SeverityLevel::Warning;
+    } else if perc
+      < inode_warning - (ALERT_PERCENTAGE_THRESHOLD as f64)
+    {
+      state.should_close_alert = true;
+    };
+    health.inodes.insert(mount.clone(), state);
   }
 
   health
@@ -1,3 +1,9 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, OnceLock},
+  time::Instant,
+};
+
 use async_timing_util::wait_until_timelength;
 use futures::future::join_all;
 use helpers::insert_stacks_status_unknown;
@@ -15,24 +21,35 @@ use komodo_client::entities::{
 use mungos::{find::find_collect, mongodb::bson::doc};
 use periphery_client::api::{self, git::GetLatestCommit};
 use serror::Serror;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 use crate::{
   config::core_config,
-  helpers::periphery_client,
-  monitor::{alert::check_alerts, record::record_server_stats},
+  helpers::pinned_periphery_client,
+  metrics::record_monitor_loop,
+  monitor::{
+    alert::check_alerts,
+    export::export_stats,
+    record::{record_container_stats, record_server_stats},
+  },
   state::{db_client, deployment_status_cache, repo_status_cache},
 };
 
+pub use self::events::spawn_docker_event_listeners;
+
 use self::helpers::{
   insert_deployments_status_unknown, insert_repos_status_unknown,
   insert_server_status,
 };
 
 mod alert;
+mod events;
+mod export;
 mod helpers;
 mod lists;
 mod record;
 mod resources;
+mod restarts;
 
 #[derive(Default, Debug)]
 pub struct History<Curr: Default, Prev> {
@@ -99,6 +116,7 @@ pub fn spawn_monitor_loop() {
 }
 
 async fn refresh_server_cache(ts: i64) {
+  let timer = Instant::now();
   let servers =
     match find_collect(&db_client().servers, None, None).await {
       Ok(servers) => servers,
@@ -109,11 +127,86 @@ async fn refresh_server_cache(ts: i64) {
         return;
       }
     };
-  let futures = servers.into_iter().map(|server| async move {
-    update_cache_for_server(&server).await;
+  // Hold every server until the alerts are checked,
+  // so docker events don't alert on the same state changes.
+  let mut locks = Vec::with_capacity(servers.len());
+  for server in &servers {
+    locks.push(lock_server_refresh(&server.id).await);
+  }
+  let futures = servers.iter().map(|server| async move {
+    update_cache_for_server(server).await;
   });
   join_all(futures).await;
-  tokio::join!(check_alerts(ts), record_server_stats(ts));
+  check_alerts(ts).await;
+  // Recording the stats doesn't touch the cache,
+  // so docker events don't need to wait on it.
+  drop(locks);
+  let server_stats = record_server_stats(ts).await;
+  record_monitor_loop(timer.elapsed());
+  let server_names = server_names(servers);
+  // Don't hold up the next tick on a slow export target
+  tokio::spawn(async move {
+    export_stats(ts, &server_names, &server_stats, &[]).await
+  });
+}
+
+/// Container stats are read from each container,
+/// which can take a while on servers with many containers,
+/// so they are recorded separately from the server cache refresh.
+pub fn spawn_container_stats_loop() {
+  let interval: async_timing_util::Timelength = core_config()
+    .monitoring_interval
+    .try_into()
+    .expect("Invalid monitoring interval");
+  tokio::spawn(async move {
+    loop {
+      let ts = (wait_until_timelength(interval, ADDITIONAL_MS).await
+        - ADDITIONAL_MS) as i64;
+      let servers =
+        match find_collect(&db_client().servers, None, None).await {
+          Ok(servers) => servers,
+          Err(e) => {
+            error!(
+              "failed to get server list (container stats) | {e:#}"
+            );
+            continue;
+          }
+        };
+      let container_stats =
+        record_container_stats(ts, &servers).await;
+      let server_names = server_names(servers);
+      tokio::spawn(async move {
+        export_stats(ts, &server_names, &[], &container_stats).await
+      });
+    }
+  });
+}
+
+/// server id -> server name
+fn server_names(servers: Vec<Server>) -> HashMap<String, String> {
+  servers
+    .into_iter()
+    .map(|server| (server.id, server.name))
+    .collect()
+}
+
+type RefreshLocks = Mutex<HashMap<String, Arc<Mutex<()>>>>;
+fn refresh_locks() -> &'static RefreshLocks {
+  static REFRESH_LOCKS: OnceLock<RefreshLocks> = OnceLock::new();
+  REFRESH_LOCKS.get_or_init(Default::default)
+}
+
+/// Serializes refreshing the status cache of a server with
+/// checking the alerts on it, as the alerts compare the
+/// previous and current state in the cache.
+async fn lock_server_refresh(server_id: &str) -> OwnedMutexGuard<()> {
+  let lock = refresh_locks()
+    .lock()
+    .await
+    .entry(server_id.to_string())
+    .or_default()
+    .clone();
+  lock.lock_owned().await
 }
 
 #[instrument(level = "debug")]
@@ -159,11 +252,23 @@ pub async fn update_cache_for_server(server: &Server) {
     return;
   }
 
-  let Ok(periphery) = periphery_client(server) else {
-    error!(
-      "somehow periphery not ok to create. should not be reached."
-    );
-    return;
+  let periphery = match pinned_periphery_client(server).await {
+    Ok(periphery) => periphery,
+    Err(e) => {
+      insert_deployments_status_unknown(deployments).await;
+      insert_repos_status_unknown(repos).await;
+      insert_stacks_status_unknown(stacks).await;
+      insert_server_status(
+        server,
+        ServerState::NotOk,
+        String::from("unknown"),
+        None,
+        (None, None, None, None, None),
+        Serror::from(&e),
+      )
+      .await;
+      return;
+    }
   };
 
   let version = match periphery.request(api::GetVersion {}).await {
@@ -216,6 +321,7 @@ pub async fn update_cache_for_server(server: &Server) {
       tokio::join!(
         resources::update_deployment_cache(
           server.name.clone(),
+          &periphery,
           deployments,
           &containers,
           &images,
@@ -223,6 +329,7 @@ pub async fn update_cache_for_server(server: &Server) {
         ),
         resources::update_stack_cache(
           server.name.clone(),
+          &periphery,
           stacks,
           &containers,
           &images
@@ -1,11 +1,21 @@
-use komodo_client::entities::stats::{
-  SystemStatsRecord, TotalDiskUsage, sum_disk_usage,
+use futures::future::join_all;
+use komodo_client::entities::{
+  server::{Server, ServerState},
+  stats::{
+    ContainerStatsRecord, SystemStatsRecord, TotalDiskUsage,
+    sum_disk_usage,
+  },
 };
+use periphery_client::api::container::GetContainerStatsRecords;
 
-use crate::state::{db_client, server_status_cache};
+use crate::{
+  helpers::periphery_client,
+  state::{db_client, server_status_cache},
+};
 
+/// Returns the recorded stats
 #[instrument(level = "debug")]
-pub async fn record_server_stats(ts: i64) {
+pub async fn record_server_stats(ts: i64) -> Vec<SystemStatsRecord> {
   let status = server_status_cache().get_list().await;
   let records = status
     .into_iter()
@@ -28,13 +38,73 @@ pub async fn record_server_stats(ts: i64) {
         disks: stats.disks.clone(),
         network_ingress_bytes: stats.network_ingress_bytes,
         network_egress_bytes: stats.network_egress_bytes,
+        network_usage_interface: stats
+          .network_usage_interface
+          .clone(),
+        load_average: stats.load_average,
+        swap_used_gb: stats.swap_used_gb,
+        swap_total_gb: stats.swap_total_gb,
       })
     })
     .collect::<Vec<_>>();
   if !records.is_empty() {
-    let res = db_client().stats.insert_many(records).await;
+    let res = db_client().stats.insert_many(&records).await;
     if let Err(e) = res {
       error!("failed to record server stats | {e:#}");
     }
   }
+  records
+}
+
+/// Returns the recorded stats
+#[instrument(level = "debug", skip(servers))]
+pub async fn record_container_stats(
+  ts: i64,
+  servers: &[Server],
+) -> Vec<ContainerStatsRecord> {
+  let futures = servers
+    .iter()
+    .filter(|server| {
+      server.config.enabled && server.config.stats_monitoring
+    })
+    .map(|server| async move {
+      // Skip servers which aren't reachable this cycle
+      let status = server_status_cache().get(&server.id).await?;
+      if status.state != ServerState::Ok {
+        return None;
+      }
+      let stats = periphery_client(server)
+        .ok()?
+        .request(GetContainerStatsRecords {})
+        .await
+        .inspect_err(|e| {
+          warn!(
+            "failed to get container stats for server {} | {e:#}",
+            server.name
+          )
+        })
+        .ok()?;
+      let records = stats
+        .into_iter()
+        .map(|stats| ContainerStatsRecord {
+          ts,
+          sid: server.id.clone(),
+          ..stats
+        })
+        .collect::<Vec<_>>();
+      Some(records)
+    });
+  let records = join_all(futures)
+    .await
+    .into_iter()
+    .flatten()
+    .flatten()
+    .collect::<Vec<_>>();
+  if !records.is_empty() {
+    let res = db_client().container_stats.insert_many(&records).await;
+    if let Err(e) = res {
+      error!("failed to record container stats | {e:#}");
+    }
+  }
+  records
 }
@@ -20,6 +20,7 @@ use komodo_client::{
     user::auto_redeploy_user,
   },
 };
+use periphery_client::PeripheryClient;
 
 use crate::{
   alert::send_alerts,
@@ -35,7 +36,10 @@ use crate::{
   },
 };
 
-use super::{CachedDeploymentStatus, CachedStackStatus, History};
+use super::{
+  CachedDeploymentStatus, CachedStackStatus, History,
+  restarts::{RestartCheck, check_container_restarts},
+};
 
 fn deployment_alert_sent_cache() -> &'static Mutex<HashSet<String>> {
   static CACHE: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
@@ -44,17 +48,39 @@ fn deployment_alert_sent_cache() -> &'static Mutex<HashSet<String>> {
 
 pub async fn update_deployment_cache(
   server_name: String,
+  periphery: &PeripheryClient,
   deployments: Vec<Deployment>,
   containers: &[ContainerListItem],
   images: &[ImageListItem],
   builds: &[Build],
 ) {
   let deployment_status_cache = deployment_status_cache();
+  let mut restart_checks = Vec::new();
   for deployment in deployments {
     let container = containers
       .iter()
-      .find(|container| container.name == deployment.name)
+      .find(|container| {
+        container.name == deployment.name
+          && container.docker_endpoint
+            == deployment.config.docker_endpoint
+      })
       .cloned();
+    if let Some(container) =
+      container.as_ref().filter(|_| deployment.config.send_alerts)
+    {
+      restart_checks.push(RestartCheck {
+        target: ResourceTarget::Deployment(deployment.id.clone()),
+        id: deployment.id.clone(),
+        name: deployment.name.clone(),
+        server_id: deployment.config.server_id.clone(),
+        server_name: server_name.clone(),
+        docker_endpoint: deployment.config.docker_endpoint.clone(),
+        service: None,
+        container: deployment.name.clone(),
+        state: container.state,
+        status: container.status.clone(),
+      });
+    }
     let prev = deployment_status_cache
       .get(&deployment.id)
       .await
@@ -87,6 +113,13 @@ pub async fn update_deployment_cache(
         }
       }
     };
+    // Images are only listed from the default docker endpoint
+    let images: &[ImageListItem] =
+      if deployment.config.docker_endpoint.is_empty() {
+        images
+      } else {
+        &[]
+      };
     let update_available = if let Some(ContainerListItem {
       image_id: Some(curr_image_id),
       ..
@@ -219,6 +252,7 @@ pub async fn update_deployment_cache(
       )
       .await;
   }
+  check_container_restarts(periphery, restart_checks).await;
 }
 
 /// (StackId, Service)
@@ -231,12 +265,29 @@ fn stack_alert_sent_cache()
 
 pub async fn update_stack_cache(
   server_name: String,
+  periphery: &PeripheryClient,
   stacks: Vec<Stack>,
   containers: &[ContainerListItem],
   images: &[ImageListItem],
 ) {
   let stack_status_cache = stack_status_cache();
+  let mut restart_checks = Vec::new();
   for stack in stacks {
+    // Only the containers on the docker endpoint the stack deploys to
+    let containers = containers
+      .iter()
+      .filter(|container| {
+        container.docker_endpoint == stack.config.docker_endpoint
+      })
+      .cloned()
+      .collect::<Vec<_>>();
+    // Images are only listed from the default docker endpoint
+    let images: &[ImageListItem] =
+      if stack.config.docker_endpoint.is_empty() {
+        images
+      } else {
+        &[]
+      };
     let services = extract_services_from_stack(&stack);
     let mut services_with_containers = services.iter().map(|StackServiceNames { service_name, container_name, image }| {
       let container = containers.iter().find(|container| {
@@ -322,6 +373,26 @@ pub async fn update_stack_cache(
       }
     }).collect::<Vec<_>>();
 
+    if stack.config.send_alerts {
+      restart_checks.extend(
+        services_with_containers.iter().filter_map(|service| {
+          let container = service.container.as_ref()?;
+          Some(RestartCheck {
+            target: ResourceTarget::Stack(stack.id.clone()),
+            id: stack.id.clone(),
+            name: stack.name.clone(),
+            server_id: stack.config.server_id.clone(),
+            server_name: server_name.clone(),
+            docker_endpoint: stack.config.docker_endpoint.clone(),
+            service: Some(service.service.clone()),
+            container: container.name.clone(),
+            state: container.state,
+            status: container.status.clone(),
+          })
+        }),
+      );
+    }
+
     let mut images_with_update = Vec::new();
     let mut services_to_update = Vec::new();
 
@@ -344,7 +415,7 @@ pub async fn update_stack_cache(
     let state = get_stack_state_from_containers(
       &stack.config.ignore_services,
       &services,
-      containers,
+      &containers,
     );
     if !services_to_update.is_empty()
       && stack.config.auto_update
@@ -420,4 +491,5 @@ pub async fn update_stack_cache(
       .insert(stack.id, History { curr: status, prev }.into())
       .await;
   }
+  check_container_restarts(periphery, restart_checks).await;
 }
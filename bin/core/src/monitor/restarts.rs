@@ -0,0 +1,244 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  sync::{Mutex, OnceLock},
+};
+
+use async_timing_util::get_timelength_in_ms;
+use futures::future::join_all;
+use komodo_client::entities::{
+  I64, ResourceTarget,
+  alert::{Alert, AlertData, SeverityLevel},
+  docker::container::ContainerStateStatusEnum,
+  komodo_timestamp,
+};
+use periphery_client::{
+  PeripheryClient, api::container::InspectContainer,
+};
+
+use crate::{
+  alert::send_alerts, config::core_config, state::db_client,
+};
+
+/// A Deployment / Stack service container to check
+/// for restart loops and OOM kills.
+#[derive(Debug, Clone)]
+pub struct RestartCheck {
+  pub target: ResourceTarget,
+  /// The id of the deployment / stack
+  pub id: String,
+  /// The name of the deployment / stack
+  pub name: String,
+  pub server_id: String,
+  pub server_name: String,
+  pub docker_endpoint: String,
+  /// The stack service name, if the container belongs to a stack
+  pub service: Option<String>,
+  pub container: String,
+  /// The state from the container list
+  pub state: ContainerStateStatusEnum,
+  /// The status from the container list, ie `Up 2 hours`
+  pub status: Option<String>,
+}
+
+#[derive(Default)]
+struct RestartHistory {
+  /// (ts, restart count) samples within the window.
+  samples: VecDeque<(i64, I64)>,
+  /// Whether the current restart loop has already been alerted.
+  loop_alerted: bool,
+  /// The `FinishedAt` of the last OOM kill alerted on.
+  oom_alerted: Option<String>,
+  /// The list state / status at the last inspect.
+  /// The container is only inspected again once these change,
+  /// as it can't have restarted in between.
+  inspected: Option<(ContainerStateStatusEnum, Option<String>)>,
+}
+
+/// (ServerId, DockerEndpoint, ContainerName) -> RestartHistory
+fn restart_history_cache()
+-> &'static Mutex<HashMap<(String, String, String), RestartHistory>> {
+  static CACHE: OnceLock<
+    Mutex<HashMap<(String, String, String), RestartHistory>>,
+  > = OnceLock::new();
+  CACHE.get_or_init(Default::default)
+}
+
+pub async fn check_container_restarts(
+  periphery: &PeripheryClient,
+  checks: Vec<RestartCheck>,
+) {
+  if checks.is_empty() {
+    return;
+  }
+  let futures = checks
+    .into_iter()
+    .map(|check| check_container(periphery, check));
+  let alerts = join_all(futures)
+    .await
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+  if alerts.is_empty() {
+    return;
+  }
+  send_alerts(&alerts).await;
+  let res = db_client().alerts.insert_many(alerts).await;
+  if let Err(e) = res {
+    error!("failed to record container restart alerts to db | {e:#}");
+  }
+}
+
+async fn check_container(
+  periphery: &PeripheryClient,
+  check: RestartCheck,
+) -> Vec<Alert> {
+  let key = (
+    check.server_id.clone(),
+    check.docker_endpoint.clone(),
+    check.container.clone(),
+  );
+  let list_state = (check.state, check.status.clone());
+
+  let unchanged = restart_history_cache()
+    .lock()
+    .unwrap()
+    .get(&key)
+    .map(|history| history.inspected.as_ref() == Some(&list_state))
+    .unwrap_or_default();
+
+  // (restart count, oom killed, finished at)
+  let inspected = if unchanged {
+    None
+  } else {
+    match periphery
+      .clone()
+      .with_docker_endpoint(&check.docker_endpoint)
+      .request(InspectContainer {
+        name: check.container.clone(),
+      })
+      .await
+    {
+      Ok(container) => {
+        let state = container.state.unwrap_or_default();
+        Some((
+          container.restart_count.unwrap_or_default(),
+          state.oom_killed.unwrap_or_default(),
+          state.finished_at,
+        ))
+      }
+      Err(e) => {
+        warn!(
+          "failed to inspect container {} for restart check | {e:#}",
+          check.container
+        );
+        return Vec::new();
+      }
+    }
+  };
+
+  let config = core_config();
+  let window_ms = config
+    .container_restart_alert_window
+    .try_into()
+    .map(|window| get_timelength_in_ms(window) as i64)
+    .unwrap_or(15 * 60 * 1000);
+  let threshold = config.container_restart_alert_threshold as I64;
+
+  let ts = komodo_timestamp();
+
+  let mut alerts = Vec::new();
+  let mut cache = restart_history_cache().lock().unwrap();
+  let history = cache.entry(key).or_default();
+
+  let (restart_count, oom_killed, finished_at) = match inspected {
+    Some(inspected) => {
+      history.inspected = Some(list_state);
+      inspected
+    }
+    // Carry the last count forward so the window keeps moving.
+    None => (
+      history
+        .samples
+        .back()
+        .map(|(_, count)| *count)
+        .unwrap_or_default(),
+      false,
+      None,
+    ),
+  };
+
+  // The restart count resets when the container is recreated.
+  if history
+    .samples
+    .back()
+    .map(|(_, count)| *count > restart_count)
+    .unwrap_or_default()
+  {
+    history.samples.clear();
+    history.loop_alerted = false;
+  }
+  history.samples.push_back((ts, restart_count));
+  // Keep the latest sample from before the window as the baseline.
+  while history
+    .samples
+    .get(1)
+    .map(|(sample_ts, _)| *sample_ts <= ts - window_ms)
+    .unwrap_or_default()
+  {
+    history.samples.pop_front();
+  }
+
+  let restarts = history
+    .samples
+    .front()
+    .map(|(_, baseline)| restart_count - baseline)
+    .unwrap_or_default();
+
+  if threshold > 0 && restarts >= threshold {
+    if !history.loop_alerted {
+      history.loop_alerted = true;
+      alerts.push(Alert {
+        id: Default::default(),
+        ts,
+        resolved: true,
+        resolved_ts: ts.into(),
+        level: SeverityLevel::Critical,
+        target: check.target.clone(),
+        data: AlertData::ContainerRestartLoop {
+          id: check.id.clone(),
+          name: check.name.clone(),
+          server_id: check.server_id.clone(),
+          server_name: check.server_name.clone(),
+          service: check.service.clone(),
+          container: check.container.clone(),
+          restarts,
+          window_minutes: window_ms / 60_000,
+        },
+      });
+    }
+  } else {
+    history.loop_alerted = false;
+  }
+
+  if oom_killed && history.oom_alerted != finished_at {
+    history.oom_alerted = finished_at;
+    alerts.push(Alert {
+      id: Default::default(),
+      ts,
+      resolved: true,
+      resolved_ts: ts.into(),
+      level: SeverityLevel::Critical,
+      target: check.target,
+      data: AlertData::ContainerOomKilled {
+        id: check.id,
+        name: check.name,
+        server_id: check.server_id,
+        server_name: check.server_name,
+        service: check.service,
+        container: check.container,
+      },
+    });
+  }
+
+  alerts
+}
@@ -230,7 +230,8 @@ impl super::KomodoResource for Deployment {
       return Ok(());
     }
     let periphery = match periphery_client(&server) {
-      Ok(periphery) => periphery,
+      Ok(periphery) => periphery
+        .with_docker_endpoint(&deployment.config.docker_endpoint),
       Err(e) => {
         // This case won't ever happen, as periphery_client only fallible if the server is disabled.
         // Leaving it for completeness sake
@@ -540,6 +540,24 @@ async fn validate_config(
           .await?;
           params.server = server.id;
         }
+        Execution::BackupVolume(params) => {
+          let server = super::get_check_permissions::<Server>(
+            &params.server,
+            user,
+            PermissionLevel::Execute,
+          )
+          .await?;
+          params.server = server.id;
+        }
+        Execution::RestoreVolume(params) => {
+          let server = super::get_check_permissions::<Server>(
+            &params.server,
+            user,
+            PermissionLevel::Execute,
+          )
+          .await?;
+          params.server = server.id;
+        }
         Execution::PruneDockerBuilders(params) => {
           let server = super::get_check_permissions::<Server>(
             &params.server,
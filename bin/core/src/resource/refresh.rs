@@ -93,7 +93,7 @@ async fn refresh_deployments() {
       {
         let name = deployment.name.clone();
         if let Err(e) =
-          pull_deployment_inner(deployment, server).await
+          pull_deployment_inner(deployment, server, None).await
         {
           warn!(
             "Failed to pull latest image for Deployment {name} | {e:#}"
@@ -4,7 +4,8 @@ use komodo_client::entities::{
   resource::Resource,
   server::{
     PartialServerConfig, Server, ServerConfig, ServerConfigDiff,
-    ServerListItem, ServerListItemInfo, ServerQuerySpecifics,
+    ServerInfo, ServerListItem, ServerListItemInfo,
+    ServerQuerySpecifics,
   },
   update::Update,
   user::User,
@@ -21,7 +22,7 @@ impl super::KomodoResource for Server {
   type Config = ServerConfig;
   type PartialConfig = PartialServerConfig;
   type ConfigDiff = ServerConfigDiff;
-  type Info = ();
+  type Info = ServerInfo;
   type ListItem = ServerListItem;
   type QuerySpecifics = ServerQuerySpecifics;
 
@@ -256,7 +256,9 @@ impl super::KomodoResource for Stack {
     }
 
     let periphery = match periphery_client(&server) {
-      Ok(periphery) => periphery,
+      Ok(periphery) => {
+        periphery.with_docker_endpoint(&stack.config.docker_endpoint)
+      }
       Err(e) => {
         // This case won't ever happen, as periphery_client only fallible if the server is disabled.
         // Leaving it for completeness sake
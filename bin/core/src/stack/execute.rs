@@ -51,7 +51,8 @@ pub async fn execute_compose<T: ExecuteCompose>(
   // Send update here for frontend to recheck action state
   update_update(update.clone()).await?;
 
-  let periphery = periphery_client(&server)?;
+  let periphery = periphery_client(&server)?
+    .with_docker_endpoint(&stack.config.docker_endpoint);
 
   if !services.is_empty() {
     update.logs.push(Log::simple(
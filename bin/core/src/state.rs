@@ -4,6 +4,7 @@ use std::{
 };
 
 use anyhow::Context;
+use dashmap::DashMap;
 use komodo_client::entities::{
   action::ActionState,
   build::BuildState,
@@ -16,6 +17,7 @@ use komodo_client::entities::{
 use octorust::auth::{
   Credentials, InstallationTokenGenerator, JWTCredentials,
 };
+use periphery_client::tunnel::PeripheryTunnel;
 
 use crate::{
   auth::jwt::JwtClient,
@@ -196,3 +198,12 @@ pub fn action_state_cache() -> &'static ActionStateCache {
     OnceLock::new();
   ACTION_STATE_CACHE.get_or_init(Default::default)
 }
+
+/// The outbound periphery connections, keyed by Server id.
+pub fn periphery_tunnels() -> &'static DashMap<String, PeripheryTunnel>
+{
+  static PERIPHERY_TUNNELS: OnceLock<
+    DashMap<String, PeripheryTunnel>,
+  > = OnceLock::new();
+  PERIPHERY_TUNNELS.get_or_init(Default::default)
+}
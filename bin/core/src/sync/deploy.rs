@@ -379,6 +379,7 @@ fn build_cache_for_deployment<'a>(
           || diff.image.is_some()
           || diff.image_registry_account.is_some()
           || diff.skip_secret_interp.is_some()
+          || diff.engine_api.is_some()
           || diff.network.is_some()
           || diff.restart.is_some()
           || diff.command.is_some()
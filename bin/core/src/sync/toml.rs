@@ -685,6 +685,20 @@ impl ToToml for Procedure {
               .map(|r| &r.name)
               .unwrap_or(&String::new()),
           ),
+          Execution::BackupVolume(exec) => exec.server.clone_from(
+            all
+              .servers
+              .get(&exec.server)
+              .map(|r| &r.name)
+              .unwrap_or(&String::new()),
+          ),
+          Execution::RestoreVolume(exec) => exec.server.clone_from(
+            all
+              .servers
+              .get(&exec.server)
+              .map(|r| &r.name)
+              .unwrap_or(&String::new()),
+          ),
           Execution::PruneDockerBuilders(exec) => {
             exec.server.clone_from(
               all
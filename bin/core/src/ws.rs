@@ -28,8 +28,19 @@ use crate::{
   },
 };
 
+mod logs;
+mod terminal;
+mod tunnel;
+
 pub fn router() -> Router {
-  Router::new().route("/update", get(ws_handler))
+  Router::new()
+    .route("/update", get(ws_handler))
+    .route("/terminal/deployment", get(terminal::deployment_exec))
+    .route("/terminal/stack", get(terminal::stack_exec))
+    .route("/log/deployment", get(logs::follow_deployment_log))
+    .route("/log/stack", get(logs::follow_stack_log))
+    .route("/log/container", get(logs::follow_container_log))
+    .route("/periphery", get(tunnel::periphery_tunnel))
 }
 
 #[instrument(level = "debug")]
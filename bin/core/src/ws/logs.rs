@@ -0,0 +1,234 @@
+use std::cmp;
+
+use anyhow::{Context, anyhow};
+use axum::{
+  extract::{
+    WebSocketUpgrade,
+    ws::{Message, WebSocket},
+  },
+  response::IntoResponse,
+};
+use formatting::format_serror;
+use futures::{SinkExt, StreamExt};
+use komodo_client::{
+  entities::{
+    deployment::Deployment, permission::PermissionLevel,
+    server::Server, user::User,
+  },
+  ws::{FollowContainerLog, FollowDeploymentLog, FollowStackLog},
+};
+use periphery_client::{PeripheryWebSocket, api as periphery};
+use serde::de::DeserializeOwned;
+use tokio_tungstenite::tungstenite;
+
+use crate::{
+  helpers::periphery_client, resource, stack::get_stack_and_server,
+};
+
+use super::ws_login;
+
+const MAX_LOG_LENGTH: u64 = 5000;
+
+#[instrument(level = "debug")]
+pub async fn follow_deployment_log(
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| {
+    handle_follow_log(socket, connect_deployment_log)
+  })
+}
+
+#[instrument(level = "debug")]
+pub async fn follow_stack_log(
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| handle_follow_log(socket, connect_stack_log))
+}
+
+#[instrument(level = "debug")]
+pub async fn follow_container_log(
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| {
+    handle_follow_log(socket, connect_container_log)
+  })
+}
+
+async fn connect_deployment_log(
+  FollowDeploymentLog {
+    deployment,
+    tail,
+    terms,
+    combinator,
+    invert,
+    timestamps,
+  }: FollowDeploymentLog,
+  user: User,
+) -> anyhow::Result<PeripheryWebSocket> {
+  let deployment = resource::get_check_permissions::<Deployment>(
+    &deployment,
+    &user,
+    PermissionLevel::Read,
+  )
+  .await?;
+  if deployment.config.server_id.is_empty() {
+    return Err(anyhow!("Deployment has no server configured"));
+  }
+  let server =
+    resource::get::<Server>(&deployment.config.server_id).await?;
+  periphery_client(&server)?
+    .with_docker_endpoint(deployment.config.docker_endpoint)
+    .follow_container_log(&periphery::container::FollowContainerLog {
+      name: deployment.name,
+      tail: cmp::min(tail, MAX_LOG_LENGTH),
+      terms,
+      combinator,
+      invert,
+      timestamps,
+    })
+    .await
+}
+
+async fn connect_stack_log(
+  FollowStackLog {
+    stack,
+    services,
+    tail,
+    terms,
+    combinator,
+    invert,
+    timestamps,
+  }: FollowStackLog,
+  user: User,
+) -> anyhow::Result<PeripheryWebSocket> {
+  let (stack, server) =
+    get_stack_and_server(&stack, &user, PermissionLevel::Read, true)
+      .await?;
+  for service in &services {
+    check_log_target("Service", service)?;
+  }
+  periphery_client(&server)?
+    .with_docker_endpoint(&stack.config.docker_endpoint)
+    .follow_compose_log(&periphery::compose::FollowComposeLog {
+      project: stack.project_name(false),
+      services,
+      tail: cmp::min(tail, MAX_LOG_LENGTH),
+      terms,
+      combinator,
+      invert,
+      timestamps,
+    })
+    .await
+}
+
+async fn connect_container_log(
+  FollowContainerLog {
+    server,
+    container,
+    tail,
+    terms,
+    combinator,
+    invert,
+    timestamps,
+  }: FollowContainerLog,
+  user: User,
+) -> anyhow::Result<PeripheryWebSocket> {
+  let server = resource::get_check_permissions::<Server>(
+    &server,
+    &user,
+    PermissionLevel::Read,
+  )
+  .await?;
+  check_log_target("Container", &container)?;
+  periphery_client(&server)?
+    .follow_container_log(&periphery::container::FollowContainerLog {
+      name: container,
+      tail: cmp::min(tail, MAX_LOG_LENGTH),
+      terms,
+      combinator,
+      invert,
+      timestamps,
+    })
+    .await
+}
+
+/// Periphery passes the names to the cli as args,
+/// so they can't be read as flags.
+fn check_log_target(kind: &str, name: &str) -> anyhow::Result<()> {
+  if name.is_empty() || name.starts_with('-') {
+    return Err(anyhow!("{kind} name is invalid: {name:?}"));
+  }
+  Ok(())
+}
+
+/// After login, waits for the follow request as the next message,
+/// then relays the log lines from periphery until either side closes.
+async fn handle_follow_log<R, F>(
+  socket: WebSocket,
+  connect: impl FnOnce(R, User) -> F,
+) where
+  R: DeserializeOwned,
+  F: Future<Output = anyhow::Result<PeripheryWebSocket>>,
+{
+  let Some((mut socket, user)) = ws_login(socket).await else {
+    return;
+  };
+
+  let periphery = async {
+    let request = match socket.recv().await {
+      Some(Ok(Message::Text(text))) => {
+        serde_json::from_str::<R>(text.as_str())
+          .context("failed to parse follow log request")?
+      }
+      _ => return Err(anyhow!("did not receive follow log request")),
+    };
+    connect(request, user)
+      .await
+      .context("failed to connect to periphery log")
+  };
+
+  let periphery = match periphery.await {
+    Ok(periphery) => periphery,
+    Err(e) => {
+      let _ =
+        socket.send(Message::text(format_serror(&e.into()))).await;
+      let _ = socket.close().await;
+      return;
+    }
+  };
+
+  let (mut client_tx, mut client_rx) = socket.split();
+  let (mut periphery_tx, mut periphery_rx) = periphery.split();
+
+  let periphery_to_client = async {
+    while let Some(Ok(msg)) = periphery_rx.next().await {
+      let msg = match msg {
+        tungstenite::Message::Text(text) => {
+          Message::text(text.as_str().to_string())
+        }
+        tungstenite::Message::Close(_) => break,
+        _ => continue,
+      };
+      if client_tx.send(msg).await.is_err() {
+        break;
+      }
+    }
+  };
+
+  // Only need to watch for the client closing
+  let client_closed = async {
+    while let Some(Ok(msg)) = client_rx.next().await {
+      if let Message::Close(_) = msg {
+        break;
+      }
+    }
+  };
+
+  tokio::select! {
+    _ = periphery_to_client => {},
+    _ = client_closed => {},
+  }
+
+  let _ = periphery_tx.close().await;
+  let _ = client_tx.close().await;
+}
@@ -0,0 +1,254 @@
+use anyhow::{Context, anyhow};
+use axum::{
+  extract::{
+    Query, WebSocketUpgrade,
+    ws::{self, WebSocket},
+  },
+  response::IntoResponse,
+};
+use formatting::format_serror;
+use futures::{SinkExt, StreamExt};
+use komodo_client::{
+  entities::{
+    Operation, ResourceTarget, deployment::Deployment,
+    komodo_timestamp, permission::PermissionLevel, server::Server,
+    server::ServerState, update::UpdateStatus, user::User,
+  },
+  ws::{ConnectDeploymentExecQuery, ConnectStackExecQuery},
+};
+use periphery_client::api::terminal::ContainerExecQuery;
+use tokio_tungstenite::tungstenite;
+
+use crate::{
+  helpers::{
+    periphery_client,
+    query::get_server_with_state,
+    update::{add_update, make_update, update_update},
+  },
+  resource,
+  stack::get_stack_and_server,
+  state::stack_status_cache,
+};
+
+use super::ws_login;
+
+/// The container to open the shell in,
+/// and where to record the session.
+struct ExecTarget {
+  target: ResourceTarget,
+  operation: Operation,
+  server: Server,
+  docker_endpoint: String,
+  container: String,
+}
+
+#[instrument(level = "debug")]
+pub async fn deployment_exec(
+  Query(ConnectDeploymentExecQuery { deployment, shell }): Query<
+    ConnectDeploymentExecQuery,
+  >,
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| async move {
+    let Some((socket, user)) = ws_login(socket).await else {
+      return;
+    };
+    let target = deployment_exec_target(&deployment, &user).await;
+    proxy_exec(socket, user, target, shell).await
+  })
+}
+
+#[instrument(level = "debug")]
+pub async fn stack_exec(
+  Query(ConnectStackExecQuery {
+    stack,
+    service,
+    shell,
+  }): Query<ConnectStackExecQuery>,
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| async move {
+    let Some((socket, user)) = ws_login(socket).await else {
+      return;
+    };
+    let target = stack_exec_target(&stack, &service, &user).await;
+    proxy_exec(socket, user, target, shell).await
+  })
+}
+
+async fn deployment_exec_target(
+  deployment: &str,
+  user: &User,
+) -> anyhow::Result<ExecTarget> {
+  let deployment = resource::get_check_permissions::<Deployment>(
+    deployment,
+    user,
+    PermissionLevel::Execute,
+  )
+  .await?;
+  if deployment.config.server_id.is_empty() {
+    return Err(anyhow!("Deployment has no server configured"));
+  }
+  let (server, state) =
+    get_server_with_state(&deployment.config.server_id).await?;
+  if state != ServerState::Ok {
+    return Err(anyhow!(
+      "cannot exec into container when server is unreachable or disabled"
+    ));
+  }
+  Ok(ExecTarget {
+    target: ResourceTarget::Deployment(deployment.id),
+    operation: Operation::ExecDeployment,
+    server,
+    docker_endpoint: deployment.config.docker_endpoint,
+    container: deployment.name,
+  })
+}
+
+async fn stack_exec_target(
+  stack: &str,
+  service: &str,
+  user: &User,
+) -> anyhow::Result<ExecTarget> {
+  let (stack, server) =
+    get_stack_and_server(stack, user, PermissionLevel::Execute, true)
+      .await?;
+  let container = stack_status_cache()
+    .get(&stack.id)
+    .await
+    .and_then(|status| {
+      status
+        .curr
+        .services
+        .iter()
+        .find(|s| s.service == service)?
+        .container
+        .as_ref()
+        .map(|container| container.name.clone())
+    })
+    .with_context(|| {
+      format!("no container found for service {service}")
+    })?;
+  Ok(ExecTarget {
+    target: ResourceTarget::Stack(stack.id),
+    operation: Operation::ExecStackService,
+    server,
+    docker_endpoint: stack.config.docker_endpoint,
+    container,
+  })
+}
+
+/// Passes the messages between the client and the periphery
+/// terminal, recording the session in an Update.
+async fn proxy_exec(
+  mut socket: WebSocket,
+  user: User,
+  target: anyhow::Result<ExecTarget>,
+  shell: String,
+) {
+  let connect = async {
+    let target = target?;
+    let periphery = periphery_client(&target.server)?
+      .with_docker_endpoint(&target.docker_endpoint)
+      .connect_container_exec(&ContainerExecQuery {
+        container: target.container.clone(),
+        shell: shell.clone(),
+      })
+      .await
+      .context("failed to connect to periphery terminal")?;
+    anyhow::Ok((target, periphery))
+  };
+  let (
+    ExecTarget {
+      target,
+      operation,
+      container,
+      ..
+    },
+    periphery,
+  ) = match connect.await {
+    Ok(res) => res,
+    Err(e) => {
+      let _ = socket
+        .send(ws::Message::text(format_serror(&e.into())))
+        .await;
+      let _ = socket.close().await;
+      return;
+    }
+  };
+
+  let mut update = make_update(target, operation, &user);
+  update.status = UpdateStatus::InProgress;
+  update.push_simple_log(
+    "Open Shell",
+    format!("Opened '{shell}' in container '{container}'"),
+  );
+  match add_update(update.clone()).await {
+    Ok(id) => update.id = id,
+    Err(e) => {
+      // Don't allow unrecorded sessions
+      let _ = socket
+        .send(ws::Message::text(format_serror(&e.into())))
+        .await;
+      let _ = socket.close().await;
+      return;
+    }
+  }
+
+  let (mut client_tx, mut client_rx) = socket.split();
+  let (mut periphery_tx, mut periphery_rx) = periphery.split();
+
+  let client_to_periphery = async {
+    while let Some(Ok(msg)) = client_rx.next().await {
+      let msg = match msg {
+        ws::Message::Binary(bytes) => {
+          tungstenite::Message::Binary(bytes)
+        }
+        ws::Message::Text(text) => {
+          tungstenite::Message::text(text.as_str().to_string())
+        }
+        ws::Message::Close(_) => break,
+        _ => continue,
+      };
+      if periphery_tx.send(msg).await.is_err() {
+        break;
+      }
+    }
+    let _ = periphery_tx.close().await;
+  };
+
+  let periphery_to_client = async {
+    while let Some(Ok(msg)) = periphery_rx.next().await {
+      let msg = match msg {
+        tungstenite::Message::Binary(bytes) => {
+          ws::Message::Binary(bytes)
+        }
+        tungstenite::Message::Text(text) => {
+          ws::Message::text(text.as_str().to_string())
+        }
+        tungstenite::Message::Close(_) => break,
+        _ => continue,
+      };
+      if client_tx.send(msg).await.is_err() {
+        break;
+      }
+    }
+    let _ = client_tx.close().await;
+  };
+
+  tokio::select! {
+    _ = client_to_periphery => {},
+    _ = periphery_to_client => {},
+  }
+
+  let duration_secs =
+    (komodo_timestamp() - update.start_ts) as f64 / 1000.0;
+  update.push_simple_log(
+    "Close Shell",
+    format!("Session closed after {duration_secs:.1}s"),
+  );
+  update.finalize();
+  if let Err(e) = update_update(update).await {
+    warn!("failed to record closed shell session | {e:#}");
+  }
+}
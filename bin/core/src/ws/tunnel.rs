@@ -0,0 +1,244 @@
+use std::time::Duration;
+
+use anyhow::{Context, anyhow};
+use axum::{
+  extract::{
+    WebSocketUpgrade,
+    ws::{Message, WebSocket},
+  },
+  http::{HeaderMap, StatusCode},
+  response::Response,
+};
+use dashmap::mapref::entry::Entry;
+use futures::{SinkExt, StreamExt};
+use komodo_client::entities::{komodo_timestamp, server::Server};
+use mungos::{by_id::update_one_by_id, mongodb::bson::doc};
+use periphery_client::{
+  api,
+  auth::{
+    NONCE_HEADER, REPLAY_WINDOW_MS, SIGNATURE_HEADER,
+    TIMESTAMP_HEADER, check_nonce,
+  },
+  tunnel::{
+    PeripheryTunnel, TUNNEL_SERVER_HEADER, TunnelRequest,
+    TunnelResponse, verify_tunnel_connection,
+  },
+};
+use serror::AddStatusCode;
+use tokio::sync::mpsc;
+
+use crate::{
+  config::core_config,
+  helpers::{periphery_client, random_string},
+  resource,
+  state::{db_client, periphery_tunnels},
+};
+
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periphery agents configured with `core_address` connect here,
+/// and core sends the requests for the Server back over the connection.
+#[instrument(level = "debug", skip_all)]
+pub async fn periphery_tunnel(
+  headers: HeaderMap,
+  ws: WebSocketUpgrade,
+) -> serror::Result<Response> {
+  let (server, auth) = authenticate_periphery(&headers)
+    .await
+    .status_code(StatusCode::UNAUTHORIZED)?;
+  Ok(ws.on_upgrade(move |socket| {
+    handle_periphery_tunnel(socket, server, auth)
+  }))
+}
+
+/// The passkey the periphery signed the connection with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TunnelAuth {
+  /// The passkey assigned to the Server.
+  Server,
+  /// The Core passkey, only accepted until
+  /// the Server is assigned a passkey of its own.
+  Core,
+}
+
+async fn authenticate_periphery(
+  headers: &HeaderMap,
+) -> anyhow::Result<(Server, TunnelAuth)> {
+  let header = |name: &str| {
+    headers
+      .get(name)
+      .with_context(|| format!("missing {name} header"))?
+      .to_str()
+      .with_context(|| format!("invalid {name} header"))
+  };
+  let id_or_name = header(TUNNEL_SERVER_HEADER)?;
+  let signature = header(SIGNATURE_HEADER)?;
+  let nonce = header(NONCE_HEADER)?;
+  let timestamp = header(TIMESTAMP_HEADER)?
+    .parse::<i64>()
+    .context("timestamp is not a number")?;
+
+  if (komodo_timestamp() - timestamp).abs() > REPLAY_WINDOW_MS {
+    return Err(anyhow!(
+      "timestamp is outside the allowed window, check the clocks of core and periphery are in sync"
+    ));
+  }
+
+  let server = resource::get::<Server>(id_or_name).await?;
+  if !server.config.outbound {
+    return Err(anyhow!(
+      "Server {} is not configured for outbound connection",
+      server.name
+    ));
+  }
+
+  let (passkey, auth) = if server.info.passkey.is_empty() {
+    (&core_config().passkey, TunnelAuth::Core)
+  } else {
+    (&server.info.passkey, TunnelAuth::Server)
+  };
+  if passkey.is_empty()
+    || !verify_tunnel_connection(
+      passkey, id_or_name, timestamp, nonce, signature,
+    )
+  {
+    return Err(anyhow!("invalid signature"));
+  }
+  if !check_nonce(nonce, timestamp) {
+    return Err(anyhow!("nonce was already used"));
+  }
+  if auth == TunnelAuth::Core
+    && periphery_tunnels().contains_key(&server.id)
+  {
+    return Err(anyhow!(
+      "Server {} is already connected",
+      server.name
+    ));
+  }
+
+  Ok((server, auth))
+}
+
+async fn handle_periphery_tunnel(
+  socket: WebSocket,
+  server: Server,
+  auth: TunnelAuth,
+) {
+  let (tunnel, mut requests) = PeripheryTunnel::new();
+  // Only a periphery authenticated with the Server passkey
+  // can replace a live connection, eg. reconnecting over a stale one.
+  match periphery_tunnels().entry(server.id.clone()) {
+    Entry::Occupied(mut entry) if auth == TunnelAuth::Server => {
+      entry.insert(tunnel.clone());
+    }
+    Entry::Occupied(_) => {
+      warn!("Server {} is already connected", server.name);
+      return;
+    }
+    Entry::Vacant(entry) => {
+      entry.insert(tunnel.clone());
+    }
+  }
+  info!("Server {} connected over outbound connection", server.name);
+
+  if auth == TunnelAuth::Core {
+    let server = server.clone();
+    tokio::spawn(async move {
+      match assign_server_passkey(&server).await {
+        Ok(_) => {
+          info!("Server {} assigned its own passkey", server.name)
+        }
+        Err(e) => warn!(
+          "failed to assign Server {} its own passkey | {e:#}",
+          server.name
+        ),
+      }
+    });
+  }
+
+  if let Err(e) = relay(socket, &tunnel, &mut requests).await {
+    warn!(
+      "Server {} outbound connection failed | {e:#}",
+      server.name
+    );
+  }
+
+  tunnel.close();
+  periphery_tunnels()
+    .remove_if(&server.id, |_, current| current.is_same(&tunnel));
+  info!("Server {} outbound connection closed", server.name);
+}
+
+/// Once assigned, the Server can only connect with its own passkey,
+/// so holding the Core passkey isn't enough to connect as it.
+async fn assign_server_passkey(
+  server: &Server,
+) -> anyhow::Result<()> {
+  let passkey = random_string(40);
+  periphery_client(server)?
+    .request(api::RotatePasskey {
+      passkey: passkey.clone(),
+    })
+    .await
+    .context("failed to send passkey to periphery")?;
+  update_one_by_id(
+    &db_client().servers,
+    &server.id,
+    doc! { "$set": { "info.passkey": &passkey } },
+    None,
+  )
+  .await
+  .context("failed to store server passkey")?;
+  Ok(())
+}
+
+async fn relay(
+  socket: WebSocket,
+  tunnel: &PeripheryTunnel,
+  requests: &mut mpsc::Receiver<TunnelRequest>,
+) -> anyhow::Result<()> {
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+  let mut ping = tokio::time::interval(PING_INTERVAL);
+
+  loop {
+    tokio::select! {
+      request = requests.recv() => {
+        let Some(request) = request else {
+          return Ok(());
+        };
+        let request = serde_json::to_string(&request)
+          .context("failed to serialize periphery request")?;
+        ws_sender
+          .send(Message::text(request))
+          .await
+          .context("failed to send request to periphery")?;
+      }
+      msg = ws_receiver.next() => {
+        let msg = match msg {
+          Some(msg) => {
+            msg.context("failed to read message from periphery")?
+          }
+          None => return Ok(()),
+        };
+        match msg {
+          Message::Text(text) => {
+            match serde_json::from_str::<TunnelResponse>(text.as_str()) {
+              Ok(response) => tunnel.respond(response),
+              Err(e) => {
+                warn!("invalid response from periphery | {e:?}")
+              }
+            }
+          }
+          Message::Close(_) => return Ok(()),
+          _ => {}
+        }
+      }
+      _ = ping.tick() => {
+        ws_sender
+          .send(Message::Ping(Default::default()))
+          .await
+          .context("failed to ping periphery")?;
+      }
+    }
+  }
+}
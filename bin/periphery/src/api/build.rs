@@ -9,6 +9,7 @@ use komodo_client::{
   entities::{
     EnvironmentVar, Version,
     build::{Build, BuildConfig},
+    config::periphery::ContainerEngine,
     environment_vars_from_str, get_image_name, optional_string,
     to_komodo_name,
     update::Log,
@@ -22,7 +23,7 @@ use resolver_api::Resolve;
 
 use crate::{
   config::periphery_config,
-  docker::docker_login,
+  docker::{docker_cli, docker_login},
   helpers::{parse_extra_args, parse_labels},
 };
 
@@ -121,11 +122,28 @@ impl Resolve<super::Args> for build::Build {
     let buildx = if *use_buildx { " buildx" } else { "" };
     let image_tags =
       image_tags(&image_name, image_tag, version, &additional_tags);
-    let maybe_push = if should_push { " --push" } else { "" };
+    let tag_args = image_tags
+      .iter()
+      .map(|tag| format!(" -t {tag}"))
+      .collect::<String>();
+    let docker = docker_cli();
+    // `podman build` can't push, so push each tag after.
+    let (maybe_push, push_after) =
+      match (should_push, periphery_config().container_engine) {
+        (false, _) => ("", String::new()),
+        (true, ContainerEngine::Docker) => (" --push", String::new()),
+        (true, ContainerEngine::Podman) => (
+          "",
+          image_tags
+            .iter()
+            .map(|tag| format!(" && {docker} push {tag}"))
+            .collect(),
+        ),
+      };
 
     // Construct command
     let command = format!(
-      "docker{buildx} build{build_args}{command_secret_args}{extra_args}{labels}{image_tags}{maybe_push} -f {dockerfile_path} .",
+      "{docker}{buildx} build{build_args}{command_secret_args}{extra_args}{labels}{tag_args}{maybe_push} -f {dockerfile_path} .{push_after}",
     );
 
     if *skip_secret_interp {
@@ -158,21 +176,26 @@ fn image_tags(
   custom_tag: &str,
   version: &Version,
   additional: &[String],
-) -> String {
+) -> Vec<String> {
   let Version { major, minor, .. } = version;
   let custom_tag = if custom_tag.is_empty() {
     String::new()
   } else {
     format!("-{custom_tag}")
   };
-  let additional = additional
-    .iter()
-    .map(|tag| format!(" -t {image_name}:{tag}{custom_tag}"))
-    .collect::<Vec<_>>()
-    .join("");
-  format!(
-    " -t {image_name}:latest{custom_tag} -t {image_name}:{version}{custom_tag} -t {image_name}:{major}.{minor}{custom_tag} -t {image_name}:{major}{custom_tag}{additional}",
+  [
+    format!("{image_name}:latest{custom_tag}"),
+    format!("{image_name}:{version}{custom_tag}"),
+    format!("{image_name}:{major}.{minor}{custom_tag}"),
+    format!("{image_name}:{major}{custom_tag}"),
+  ]
+  .into_iter()
+  .chain(
+    additional
+      .iter()
+      .map(|tag| format!("{image_name}:{tag}{custom_tag}")),
   )
+  .collect()
 }
 
 fn parse_build_args(build_args: &[EnvironmentVar]) -> String {
@@ -252,7 +275,12 @@ async fn parse_secret_args(
 impl Resolve<super::Args> for PruneBuilders {
   #[instrument(name = "PruneBuilders", skip_all)]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = String::from("docker builder prune -a -f");
+    let args = match periphery_config().container_engine {
+      ContainerEngine::Docker => "builder prune -a -f",
+      // Podman keeps the build cache with the images
+      ContainerEngine::Podman => "image prune --build-cache -f",
+    };
+    let command = format!("{} {args}", docker_cli());
     Ok(run_komodo_command("Prune Builders", None, command).await)
   }
 }
@@ -262,7 +290,12 @@ impl Resolve<super::Args> for PruneBuilders {
 impl Resolve<super::Args> for PruneBuildx {
   #[instrument(name = "PruneBuildx", skip_all)]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = String::from("docker buildx prune -a -f");
+    let args = match periphery_config().container_engine {
+      ContainerEngine::Docker => "buildx prune -a -f",
+      // Podman keeps the build cache with the images
+      ContainerEngine::Podman => "image prune --build-cache -f",
+    };
+    let command = format!("{} {args}", docker_cli());
     Ok(run_komodo_command("Prune Buildx", None, command).await)
   }
 }
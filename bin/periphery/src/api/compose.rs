@@ -1,11 +1,12 @@
-use std::{fmt::Write, path::PathBuf};
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf};
 
 use anyhow::{Context, anyhow};
 use command::run_komodo_command;
 use formatting::format_serror;
 use git::{GitRes, write_commit_file};
 use komodo_client::entities::{
-  FileContents, stack::ComposeProject, to_komodo_name, update::Log,
+  FileContents, config::periphery::ContainerEngine,
+  stack::ComposeProject, to_komodo_name, update::Log,
 };
 use periphery_client::api::{compose::*, git::RepoActionResponse};
 use resolver_api::Resolve;
@@ -13,9 +14,12 @@ use serde::{Deserialize, Serialize};
 use tokio::fs;
 
 use crate::{
-  compose::{WriteStackRes, compose_up, docker_compose, write_stack},
+  compose::{
+    WriteStackRes, compose_pull, compose_up, docker_compose,
+    write_stack,
+  },
   config::periphery_config,
-  docker::docker_login,
+  docker::{docker_client, docker_login, registry_credentials},
   helpers::{log_grep, pull_or_clone_stack},
 };
 
@@ -25,6 +29,10 @@ impl Resolve<super::Args> for ListComposeProjects {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<ComposeProject>> {
+    if periphery_config().container_engine == ContainerEngine::Podman
+    {
+      return Ok(list_projects_from_labels().await?);
+    }
     let docker_compose = docker_compose();
     let res = run_komodo_command(
       "List Projects",
@@ -68,6 +76,58 @@ impl Resolve<super::Args> for ListComposeProjects {
   }
 }
 
+/// `podman-compose` doesn't support `ls`,
+/// so the projects are collected from the container labels,
+/// which both compose providers set.
+async fn list_projects_from_labels()
+-> anyhow::Result<Vec<ComposeProject>> {
+  // project -> (state -> count, compose files)
+  let mut projects =
+    BTreeMap::<String, (BTreeMap<String, usize>, Vec<String>)>::new();
+  for container in docker_client()?.list_containers().await? {
+    let Some(project) = container.labels.get(PROJECT_LABEL) else {
+      continue;
+    };
+    let (states, compose_files) =
+      projects.entry(project.clone()).or_default();
+    *states
+      .entry(format!("{:?}", container.state).to_lowercase())
+      .or_default() += 1;
+    for file in container
+      .labels
+      .get(CONFIG_FILES_LABEL)
+      .map(|files| files.split(','))
+      .into_iter()
+      .flatten()
+    {
+      if !compose_files.iter().any(|f| f == file) {
+        compose_files.push(file.to_string());
+      }
+    }
+  }
+  Ok(
+    projects
+      .into_iter()
+      .map(|(name, (states, compose_files))| ComposeProject {
+        name,
+        // Matches the `docker compose ls` status, eg. `running(2), exited(1)`
+        status: Some(
+          states
+            .into_iter()
+            .map(|(state, count)| format!("{state}({count})"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        ),
+        compose_files,
+      })
+      .collect(),
+  )
+}
+
+const PROJECT_LABEL: &str = "com.docker.compose.project";
+const CONFIG_FILES_LABEL: &str =
+  "com.docker.compose.project.config_files";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DockerComposeLsItem {
   #[serde(default, alias = "Name")]
@@ -308,6 +368,7 @@ impl Resolve<super::Args> for ComposePull {
       services,
       git_token,
       registry_token,
+      progress_id,
     } = self;
     let mut res = ComposePullResponse::default();
 
@@ -346,14 +407,6 @@ impl Resolve<super::Args> for ComposePull {
       }
     }
 
-    let docker_compose = docker_compose();
-
-    let service_args = if services.is_empty() {
-      String::new()
-    } else {
-      format!(" {}", services.join(" "))
-    };
-
     let file_args = if stack.config.file_paths.is_empty() {
       String::from("compose.yaml")
     } else {
@@ -379,6 +432,11 @@ impl Resolve<super::Args> for ComposePull {
       })
       .context("failed to login to image registry")?;
     }
+    let credentials = registry_credentials(
+      &stack.config.registry_provider,
+      &stack.config.registry_account,
+      registry_token.as_deref(),
+    )?;
 
     let env_file = env_file_path
       .map(|path| format!(" --env-file {path}"))
@@ -395,12 +453,14 @@ impl Resolve<super::Args> for ComposePull {
 
     let project_name = stack.project_name(false);
 
-    let log = run_komodo_command(
-      "Compose Pull",
-      run_directory.as_ref(),
-      format!(
-        "{docker_compose} -p {project_name} -f {file_args}{additional_env_files}{env_file} pull{service_args}",
+    let log = compose_pull(
+      &run_directory,
+      &format!(
+        "-p {project_name} -f {file_args}{additional_env_files}{env_file}"
       ),
+      &services,
+      credentials,
+      progress_id.as_deref(),
     )
     .await;
 
@@ -431,6 +491,7 @@ impl Resolve<super::Args> for ComposeUp {
       git_token,
       registry_token,
       replacers,
+      progress_id,
     } = self;
     let mut res = ComposeUpResponse::default();
     if let Err(e) = compose_up(
@@ -440,6 +501,7 @@ impl Resolve<super::Args> for ComposeUp {
       registry_token,
       &mut res,
       replacers,
+      progress_id.as_deref(),
     )
     .await
     {
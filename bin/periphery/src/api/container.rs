@@ -3,6 +3,7 @@ use command::run_komodo_command;
 use futures::future::join_all;
 use komodo_client::entities::{
   docker::container::{Container, ContainerListItem, ContainerStats},
+  stats::ContainerStatsRecord,
   to_komodo_name,
   update::Log,
 };
@@ -10,7 +11,10 @@ use periphery_client::api::container::*;
 use resolver_api::Resolve;
 
 use crate::{
-  docker::{container_stats, docker_client, stop_container_command},
+  docker::{
+    container_stats, docker_cli, docker_client,
+    stop_container_command,
+  },
   helpers::log_grep,
 };
 
@@ -26,7 +30,7 @@ impl Resolve<super::Args> for InspectContainer {
     self,
     _: &super::Args,
   ) -> serror::Result<Container> {
-    Ok(docker_client().inspect_container(&self.name).await?)
+    Ok(docker_client()?.inspect_container(&self.name).await?)
   }
 }
 
@@ -42,8 +46,10 @@ impl Resolve<super::Args> for GetContainerLog {
     } = self;
     let timestamps =
       timestamps.then_some(" --timestamps").unwrap_or_default();
-    let command =
-      format!("docker logs {name} --tail {tail}{timestamps}");
+    let command = format!(
+      "{} logs {name} --tail {tail}{timestamps}",
+      docker_cli()
+    );
     Ok(run_komodo_command("Get container log", None, command).await)
   }
 }
@@ -64,7 +70,8 @@ impl Resolve<super::Args> for GetContainerLogSearch {
     let timestamps =
       timestamps.then_some(" --timestamps").unwrap_or_default();
     let command = format!(
-      "docker logs {name} --tail 5000{timestamps} 2>&1 | {grep}"
+      "{} logs {name} --tail 5000{timestamps} 2>&1 | {grep}",
+      docker_cli()
     );
     Ok(
       run_komodo_command("Get container log grep", None, command)
@@ -100,6 +107,18 @@ impl Resolve<super::Args> for GetContainerStatsList {
   }
 }
 
+//
+
+impl Resolve<super::Args> for GetContainerStatsRecords {
+  #[instrument(name = "GetContainerStatsRecords", level = "debug")]
+  async fn resolve(
+    self,
+    _: &super::Args,
+  ) -> serror::Result<Vec<ContainerStatsRecord>> {
+    Ok(docker_client()?.container_stats_records().await?)
+  }
+}
+
 // =========
 //  ACTIONS
 // =========
@@ -111,7 +130,7 @@ impl Resolve<super::Args> for StartContainer {
       run_komodo_command(
         "Docker Start",
         None,
-        format!("docker start {}", self.name),
+        format!("{} start {}", docker_cli(), self.name),
       )
       .await,
     )
@@ -127,7 +146,7 @@ impl Resolve<super::Args> for RestartContainer {
       run_komodo_command(
         "Docker Restart",
         None,
-        format!("docker restart {}", self.name),
+        format!("{} restart {}", docker_cli(), self.name),
       )
       .await,
     )
@@ -143,7 +162,7 @@ impl Resolve<super::Args> for PauseContainer {
       run_komodo_command(
         "Docker Pause",
         None,
-        format!("docker pause {}", self.name),
+        format!("{} pause {}", docker_cli(), self.name),
       )
       .await,
     )
@@ -157,7 +176,7 @@ impl Resolve<super::Args> for UnpauseContainer {
       run_komodo_command(
         "Docker Unpause",
         None,
-        format!("docker unpause {}", self.name),
+        format!("{} unpause {}", docker_cli(), self.name),
       )
       .await,
     )
@@ -198,15 +217,19 @@ impl Resolve<super::Args> for RemoveContainer {
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
     let RemoveContainer { name, signal, time } = self;
     let stop_command = stop_container_command(&name, signal, time);
-    let command =
-      format!("{stop_command} && docker container rm {name}");
+    let command = format!(
+      "{stop_command} && {} container rm {name}",
+      docker_cli()
+    );
     let log =
       run_komodo_command("Docker Stop and Remove", None, command)
         .await;
     if log.stderr.contains("unknown flag: --signal") {
       let stop_command = stop_container_command(&name, None, time);
-      let command =
-        format!("{stop_command} && docker container rm {name}");
+      let command = format!(
+        "{stop_command} && {} container rm {name}",
+        docker_cli()
+      );
       let mut log =
         run_komodo_command("Docker Stop and Remove", None, command)
           .await;
@@ -235,7 +258,8 @@ impl Resolve<super::Args> for RenameContainer {
       new_name,
     } = self;
     let new = to_komodo_name(&new_name);
-    let command = format!("docker rename {curr_name} {new}");
+    let command =
+      format!("{} rename {curr_name} {new}", docker_cli());
     Ok(run_komodo_command("Docker Rename", None, command).await)
   }
 }
@@ -245,7 +269,7 @@ impl Resolve<super::Args> for RenameContainer {
 impl Resolve<super::Args> for PruneContainers {
   #[instrument(name = "PruneContainers", skip_all)]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = String::from("docker container prune -f");
+    let command = format!("{} container prune -f", docker_cli());
     Ok(run_komodo_command("Prune Containers", None, command).await)
   }
 }
@@ -258,7 +282,7 @@ impl Resolve<super::Args> for StartAllContainers {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<Log>> {
-    let containers = docker_client()
+    let containers = docker_client()?
       .list_containers()
       .await
       .context("failed to list all containers on host")?;
@@ -267,7 +291,7 @@ impl Resolve<super::Args> for StartAllContainers {
         if labels.contains_key("komodo.skip") {
           return None;
         }
-        let command = format!("docker start {name}");
+        let command = format!("{} start {name}", docker_cli());
         Some(async move {
           run_komodo_command(&command.clone(), None, command).await
         })
@@ -285,7 +309,7 @@ impl Resolve<super::Args> for RestartAllContainers {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<Log>> {
-    let containers = docker_client()
+    let containers = docker_client()?
       .list_containers()
       .await
       .context("failed to list all containers on host")?;
@@ -294,7 +318,7 @@ impl Resolve<super::Args> for RestartAllContainers {
         if labels.contains_key("komodo.skip") {
           return None;
         }
-        let command = format!("docker restart {name}");
+        let command = format!("{} restart {name}", docker_cli());
         Some(async move {
           run_komodo_command(&command.clone(), None, command).await
         })
@@ -312,7 +336,7 @@ impl Resolve<super::Args> for PauseAllContainers {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<Log>> {
-    let containers = docker_client()
+    let containers = docker_client()?
       .list_containers()
       .await
       .context("failed to list all containers on host")?;
@@ -321,7 +345,7 @@ impl Resolve<super::Args> for PauseAllContainers {
         if labels.contains_key("komodo.skip") {
           return None;
         }
-        let command = format!("docker pause {name}");
+        let command = format!("{} pause {name}", docker_cli());
         Some(async move {
           run_komodo_command(&command.clone(), None, command).await
         })
@@ -339,7 +363,7 @@ impl Resolve<super::Args> for UnpauseAllContainers {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<Log>> {
-    let containers = docker_client()
+    let containers = docker_client()?
       .list_containers()
       .await
       .context("failed to list all containers on host")?;
@@ -348,7 +372,7 @@ impl Resolve<super::Args> for UnpauseAllContainers {
         if labels.contains_key("komodo.skip") {
           return None;
         }
-        let command = format!("docker unpause {name}");
+        let command = format!("{} unpause {name}", docker_cli());
         Some(async move {
           run_komodo_command(&command.clone(), None, command).await
         })
@@ -366,7 +390,7 @@ impl Resolve<super::Args> for StopAllContainers {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<Log>> {
-    let containers = docker_client()
+    let containers = docker_client()?
       .list_containers()
       .await
       .context("failed to list all containers on host")?;
@@ -377,7 +401,7 @@ impl Resolve<super::Args> for StopAllContainers {
         }
         Some(async move {
           run_komodo_command(
-            &format!("docker stop {name}"),
+            &format!("{} stop {name}", docker_cli()),
             None,
             stop_container_command(name, None, None),
           )
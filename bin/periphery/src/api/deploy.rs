@@ -1,4 +1,9 @@
-use anyhow::Context;
+use std::{collections::HashMap, net::IpAddr};
+
+use anyhow::{Context, anyhow};
+use bollard::secret::{
+  HostConfig, PortBinding, RestartPolicy, RestartPolicyNameEnum,
+};
 use command::{
   run_komodo_command, run_komodo_command_with_interpolation,
 };
@@ -10,7 +15,7 @@ use komodo_client::{
       Conversion, Deployment, DeploymentConfig, DeploymentImage,
       RestartMode, conversions_from_str, extract_registry_domain,
     },
-    environment_vars_from_str, to_komodo_name,
+    environment_vars_from_str, komodo_timestamp, to_komodo_name,
     update::Log,
   },
   parsers::QUOTE_PATTERN,
@@ -20,7 +25,10 @@ use resolver_api::Resolve;
 
 use crate::{
   config::periphery_config,
-  docker::{docker_login, pull_image},
+  docker::{
+    docker_cli, docker_client, docker_login, pull_image,
+    registry_credentials,
+  },
   helpers::{parse_extra_args, parse_labels},
 };
 
@@ -41,6 +49,7 @@ impl Resolve<super::Args> for Deploy {
       stop_time,
       registry_token,
       replacers: core_replacers,
+      progress_id,
     } = self;
     let image = if let DeploymentImage::Image { image } =
       &deployment.config.image
@@ -59,22 +68,48 @@ impl Resolve<super::Args> for Deploy {
       ));
     };
 
-    if let Err(e) = docker_login(
-      &extract_registry_domain(image)?,
-      &deployment.config.image_registry_account,
-      registry_token.as_deref(),
-    )
-    .await
-    {
-      return Ok(Log::error(
-        "docker login",
-        format_serror(
-          &e.context("failed to login to docker registry").into(),
-        ),
-      ));
+    // Validate before touching the existing container
+    let engine_deploy = if deployment.config.engine_api {
+      match engine_deploy(&deployment, image) {
+        Ok(engine_deploy) => Some(engine_deploy),
+        Err(log) => return Ok(log),
+      }
+    } else {
+      None
+    };
+
+    let credentials = async {
+      let domain = extract_registry_domain(image)?;
+      let account = &deployment.config.image_registry_account;
+      docker_login(&domain, account, registry_token.as_deref())
+        .await?;
+      registry_credentials(
+        &domain,
+        account,
+        registry_token.as_deref(),
+      )
     }
+    .await;
+    let credentials = match credentials {
+      Ok(credentials) => credentials,
+      Err(e) => {
+        return Ok(Log::error(
+          "docker login",
+          format_serror(
+            &e.context("failed to login to docker registry").into(),
+          ),
+        ));
+      }
+    };
 
-    let _ = pull_image(image).await;
+    let pull_log =
+      pull_image(image, credentials, progress_id.as_deref()).await;
+    // The image may only exist locally, eg. built on the host.
+    if !pull_log.success
+      && docker_client()?.inspect_image(image).await.is_err()
+    {
+      return Ok(pull_log);
+    }
     debug!("image pulled");
     let _ = (RemoveContainer {
       name: deployment.name.clone(),
@@ -85,6 +120,10 @@ impl Resolve<super::Args> for Deploy {
     .await;
     debug!("container stopped and removed");
 
+    if let Some(engine_deploy) = engine_deploy {
+      return Ok(engine_deploy.run(&core_replacers).await);
+    }
+
     let command = docker_run_command(&deployment, image)
       .context("Unable to generate valid docker run command")?;
     debug!("docker run command: {command}");
@@ -150,7 +189,8 @@ fn docker_run_command(
   let command = parse_command(command);
   let extra_args = parse_extra_args(extra_args);
   let command = format!(
-    "docker run -d --name {name}{ports}{volumes}{network}{restart}{environment}{labels}{extra_args} {image}{command}"
+    "{} run -d --name {name}{ports}{volumes}{network}{restart}{environment}{labels}{extra_args} {image}{command}",
+    docker_cli()
   );
   Ok(command)
 }
@@ -202,3 +242,615 @@ fn parse_command(command: &str) -> String {
     format!(" {command}")
   }
 }
+
+/// The container config for deploying through the Docker Engine API.
+struct EngineDeploy {
+  name: String,
+  config: bollard::container::Config<String>,
+  /// Sanitizes interpolated Periphery secrets out of the log.
+  replacers: Vec<(String, String)>,
+}
+
+impl EngineDeploy {
+  async fn run(self, core_replacers: &[(String, String)]) -> Log {
+    let EngineDeploy {
+      name,
+      config,
+      mut replacers,
+    } = self;
+    replacers.extend_from_slice(core_replacers);
+    let start_ts = komodo_timestamp();
+    let command = svi::replace_in_string(
+      &serde_json::to_string_pretty(&config).unwrap_or_default(),
+      &replacers,
+    );
+    let res = async {
+      docker_client()?
+        .create_and_start_container(&name, config)
+        .await
+    }
+    .await;
+    let (success, stdout, stderr) = match res {
+      Ok(warnings) => (
+        true,
+        format!("Container {name} created and started"),
+        warnings.join("\n"),
+      ),
+      Err(e) => (false, String::new(), format_serror(&e.into())),
+    };
+    Log {
+      stage: String::from("Docker Engine Deploy"),
+      command,
+      stdout,
+      stderr: svi::replace_in_string(&stderr, &replacers),
+      success,
+      start_ts,
+      end_ts: komodo_timestamp(),
+    }
+  }
+}
+
+/// Validates the whole config up front, returning every problem at once
+/// in the error log rather than failing on the first.
+fn engine_deploy(
+  Deployment {
+    name,
+    config:
+      DeploymentConfig {
+        skip_secret_interp,
+        volumes,
+        ports,
+        network,
+        command,
+        restart,
+        environment,
+        labels,
+        extra_args,
+        ..
+      },
+    ..
+  }: &Deployment,
+  image: &str,
+) -> Result<EngineDeploy, Log> {
+  let mut replacers = Vec::new();
+  let mut interpolate = |field: &str, value: &str| {
+    if *skip_secret_interp {
+      return Ok(value.to_string());
+    }
+    let (value, more) = svi::interpolate_variables(
+      value,
+      &periphery_config().secrets,
+      svi::Interpolator::DoubleBrackets,
+      true,
+    )
+    .with_context(|| {
+      format!("Failed to interpolate Periphery secrets into {field}")
+    })?;
+    replacers.extend(more);
+    anyhow::Ok(value)
+  };
+  let interpolated = (|| {
+    anyhow::Ok((
+      interpolate("Ports", ports)?,
+      interpolate("Volumes", volumes)?,
+      interpolate("Environment", environment)?,
+      interpolate("Labels", labels)?,
+      interpolate("Command", command)?,
+    ))
+  })();
+  let (ports, volumes, environment, labels, command) =
+    match interpolated {
+      Ok(interpolated) => interpolated,
+      Err(e) => {
+        return Err(Log::error(
+          "Validate Container Config",
+          format_serror(&e.into()),
+        ));
+      }
+    };
+
+  let mut errors = Vec::new();
+
+  if !extra_args.is_empty() {
+    errors.push(format!(
+      "Extra args are not supported with the Engine API: {}",
+      extra_args.join(" ")
+    ));
+  }
+
+  let network = network.trim();
+  if network.is_empty() {
+    errors.push(String::from("Network cannot be empty"));
+  }
+
+  let (exposed_ports, port_bindings) =
+    match conversions_from_str(&ports) {
+      // Docker ignores published ports on the host network
+      Ok(_) if network == "host" => Default::default(),
+      Ok(ports) => engine_ports(&ports, &mut errors),
+      Err(e) => {
+        errors.push(format!("Invalid ports: {e:#}"));
+        Default::default()
+      }
+    };
+
+  let binds = match conversions_from_str(&volumes) {
+    Ok(volumes) => engine_binds(&volumes, &mut errors),
+    Err(e) => {
+      errors.push(format!("Invalid volumes: {e:#}"));
+      Default::default()
+    }
+  };
+
+  let env = match environment_vars_from_str(&environment) {
+    Ok(environment) => environment
+      .into_iter()
+      .filter_map(|EnvironmentVar { variable, value }| {
+        if variable.is_empty() || variable.contains(['=', ' ', '\t'])
+        {
+          errors.push(format!(
+            "Invalid environment variable name: '{variable}'"
+          ));
+          return None;
+        }
+        Some(format!("{variable}={}", unquote(&value)))
+      })
+      .collect(),
+    Err(e) => {
+      errors.push(format!("Invalid environment: {e:#}"));
+      Default::default()
+    }
+  };
+
+  let labels = match environment_vars_from_str(&labels) {
+    Ok(labels) => labels
+      .into_iter()
+      .filter_map(|EnvironmentVar { variable, value }| {
+        if variable.is_empty()
+          || variable.contains(char::is_whitespace)
+        {
+          errors.push(format!("Invalid label key: '{variable}'"));
+          return None;
+        }
+        Some((variable, unquote(&value).to_string()))
+      })
+      .collect(),
+    Err(e) => {
+      errors.push(format!("Invalid labels: {e:#}"));
+      Default::default()
+    }
+  };
+
+  let cmd = match split_command(&command) {
+    Ok(cmd) => cmd,
+    Err(e) => {
+      errors.push(format!("Invalid command: {e:#}"));
+      Default::default()
+    }
+  };
+
+  if !errors.is_empty() {
+    return Err(Log::error(
+      "Validate Container Config",
+      svi::replace_in_string(&errors.join("\n"), &replacers),
+    ));
+  }
+
+  let restart_policy = match restart {
+    RestartMode::NoRestart => RestartPolicy {
+      name: Some(RestartPolicyNameEnum::NO),
+      maximum_retry_count: None,
+    },
+    RestartMode::OnFailure => RestartPolicy {
+      name: Some(RestartPolicyNameEnum::ON_FAILURE),
+      maximum_retry_count: Some(10),
+    },
+    RestartMode::Always => RestartPolicy {
+      name: Some(RestartPolicyNameEnum::ALWAYS),
+      maximum_retry_count: None,
+    },
+    RestartMode::UnlessStopped => RestartPolicy {
+      name: Some(RestartPolicyNameEnum::UNLESS_STOPPED),
+      maximum_retry_count: None,
+    },
+  };
+
+  let config = bollard::container::Config {
+    image: Some(image.to_string()),
+    cmd: (!cmd.is_empty()).then_some(cmd),
+    env: Some(env),
+    labels: Some(labels),
+    exposed_ports: Some(exposed_ports),
+    host_config: Some(HostConfig {
+      binds: Some(binds),
+      port_bindings: Some(port_bindings),
+      network_mode: Some(network.to_string()),
+      restart_policy: Some(restart_policy),
+      ..Default::default()
+    }),
+    ..Default::default()
+  };
+
+  Ok(EngineDeploy {
+    name: to_komodo_name(name),
+    config,
+    replacers,
+  })
+}
+
+type ExposedPorts = HashMap<String, HashMap<(), ()>>;
+type PortBindings = HashMap<String, Option<Vec<PortBinding>>>;
+
+/// Supports `[ip:]host:container[/protocol]`,
+/// where the ports can be ranges of the same length.
+fn engine_ports(
+  ports: &[Conversion],
+  errors: &mut Vec<String>,
+) -> (ExposedPorts, PortBindings) {
+  let mut exposed_ports = ExposedPorts::new();
+  let mut port_bindings = PortBindings::new();
+  for Conversion { local, container } in ports {
+    // The line is split on the first ':',
+    // which may be inside the host ip.
+    let spec = format!("{local}:{container}");
+    let Some((host, container)) = spec.rsplit_once(':') else {
+      errors.push(format!("Invalid port mapping: '{spec}'"));
+      continue;
+    };
+    let (container, protocol) =
+      container.split_once('/').unwrap_or((container, "tcp"));
+    if !matches!(protocol, "tcp" | "udp" | "sctp") {
+      errors.push(format!(
+        "Invalid port protocol '{protocol}' in '{spec}'"
+      ));
+      continue;
+    }
+    let (host_ip, host_port) = match host.rsplit_once(':') {
+      Some((ip, port)) => {
+        let ip = ip.trim_start_matches('[').trim_end_matches(']');
+        if ip.parse::<IpAddr>().is_err() {
+          errors.push(format!("Invalid host ip '{ip}' in '{spec}'"));
+          continue;
+        }
+        (Some(ip.to_string()), port)
+      }
+      None => (None, host),
+    };
+    let Some(container) = parse_port_range(container) else {
+      errors.push(format!(
+        "Invalid container port '{container}' in '{spec}'"
+      ));
+      continue;
+    };
+    // Empty host port publishes on a random port
+    let host_port = if host_port.is_empty() {
+      None
+    } else {
+      match parse_port_range(host_port) {
+        Some(host_port)
+          if host_port.1 - host_port.0
+            == container.1 - container.0 =>
+        {
+          Some(host_port)
+        }
+        Some(_) => {
+          errors.push(format!(
+            "Host and container port ranges are different lengths in '{spec}'"
+          ));
+          continue;
+        }
+        None => {
+          errors.push(format!(
+            "Invalid host port '{host_port}' in '{spec}'"
+          ));
+          continue;
+        }
+      }
+    };
+    for (i, port) in (container.0..=container.1).enumerate() {
+      let key = format!("{port}/{protocol}");
+      exposed_ports.insert(key.clone(), HashMap::new());
+      port_bindings
+        .entry(key)
+        .or_insert_with(|| Some(Vec::new()))
+        .get_or_insert_default()
+        .push(PortBinding {
+          host_ip: host_ip.clone(),
+          host_port: host_port
+            .map(|(start, _)| (start + i as u16).to_string()),
+        });
+    }
+  }
+  (exposed_ports, port_bindings)
+}
+
+/// eg. `8080` or `8080-8090`
+fn parse_port_range(ports: &str) -> Option<(u16, u16)> {
+  let (start, end) = match ports.split_once('-') {
+    Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+    None => {
+      let port = ports.parse().ok()?;
+      (port, port)
+    }
+  };
+  (start > 0 && start <= end).then_some((start, end))
+}
+
+/// Supports `source:target[:options]`,
+/// where the source is an absolute path or a named volume.
+fn engine_binds(
+  volumes: &[Conversion],
+  errors: &mut Vec<String>,
+) -> Vec<String> {
+  let mut binds = Vec::new();
+  for Conversion { local, container } in volumes {
+    let spec = format!("{local}:{container}");
+    let (target, options) =
+      container.split_once(':').unwrap_or((container, ""));
+    let valid_source = local.starts_with('/')
+      || (!local.is_empty()
+        && local
+          .chars()
+          .next()
+          .is_some_and(|c| c.is_ascii_alphanumeric())
+        && local
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c)));
+    if !valid_source {
+      errors.push(format!(
+        "Volume source must be an absolute path or volume name in '{spec}'"
+      ));
+      continue;
+    }
+    if !target.starts_with('/') {
+      errors.push(format!(
+        "Volume target must be an absolute path in '{spec}'"
+      ));
+      continue;
+    }
+    if let Some(option) = options.split(',').find(|option| {
+      !option.is_empty() && !VOLUME_OPTIONS.contains(option)
+    }) {
+      errors.push(format!(
+        "Invalid volume option '{option}' in '{spec}'"
+      ));
+      continue;
+    }
+    binds.push(spec);
+  }
+  binds
+}
+
+const VOLUME_OPTIONS: &[&str] = &[
+  "ro",
+  "rw",
+  "z",
+  "Z",
+  "shared",
+  "slave",
+  "private",
+  "rshared",
+  "rslave",
+  "rprivate",
+  "nocopy",
+  "consistent",
+  "cached",
+  "delegated",
+];
+
+/// Without a shell, wrapping quotes aren't removed by docker.
+fn unquote(value: &str) -> &str {
+  if value.len() > 1
+    && value.starts_with(QUOTE_PATTERN)
+    && value.ends_with(QUOTE_PATTERN)
+  {
+    &value[1..value.len() - 1]
+  } else {
+    value
+  }
+}
+
+/// Splits the command into args like a shell would,
+/// supporting quotes and backslash escapes,
+/// but without any expansion.
+fn split_command(command: &str) -> anyhow::Result<Vec<String>> {
+  let mut args = Vec::new();
+  let mut arg = String::new();
+  let mut in_arg = false;
+  let mut quote = None::<char>;
+  let mut chars = command.trim().chars();
+  while let Some(c) = chars.next() {
+    match (quote, c) {
+      (Some(q), c) if c == q => quote = None,
+      (Some('"'), '\\') | (None, '\\') => {
+        let escaped = chars
+          .next()
+          .context("Command ends with escape character")?;
+        arg.push(escaped);
+        // A word can be only an escaped char, eg `\-`
+        in_arg = true;
+      }
+      (Some(_), c) => arg.push(c),
+      (None, '"' | '\'') => {
+        quote = Some(c);
+        in_arg = true;
+      }
+      (None, c) if c.is_whitespace() => {
+        if in_arg {
+          args.push(std::mem::take(&mut arg));
+          in_arg = false;
+        }
+      }
+      (None, c) => {
+        arg.push(c);
+        in_arg = true;
+      }
+    }
+  }
+  if let Some(quote) = quote {
+    return Err(anyhow!("Unclosed {quote} in command"));
+  }
+  if in_arg {
+    args.push(arg);
+  }
+  Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn conversion(local: &str, container: &str) -> Conversion {
+    Conversion {
+      local: local.to_string(),
+      container: container.to_string(),
+    }
+  }
+
+  #[test]
+  fn split_command_words() {
+    assert_eq!(
+      split_command("  sh -c  'echo hi'  ").unwrap(),
+      ["sh", "-c", "echo hi"]
+    );
+    assert_eq!(
+      split_command(r#"echo "a \"b\" c" d\ e"#).unwrap(),
+      ["echo", r#"a "b" c"#, "d e"]
+    );
+    assert_eq!(split_command(r"'a\b'").unwrap(), [r"a\b"]);
+    assert_eq!(split_command(r#"a "" b"#).unwrap(), ["a", "", "b"]);
+    assert!(split_command("").unwrap().is_empty());
+  }
+
+  #[test]
+  fn split_command_escape_only_word() {
+    assert_eq!(
+      split_command(r"cmd \- x").unwrap(),
+      ["cmd", "-", "x"]
+    );
+    assert_eq!(split_command(r"cmd \-").unwrap(), ["cmd", "-"]);
+  }
+
+  #[test]
+  fn split_command_errors() {
+    assert!(split_command("echo 'hi").is_err());
+    assert!(split_command(r#"echo "hi"#).is_err());
+    assert!(split_command(r"echo \").is_err());
+  }
+
+  #[test]
+  fn port_range() {
+    assert_eq!(parse_port_range("8080"), Some((8080, 8080)));
+    assert_eq!(parse_port_range("8080-8090"), Some((8080, 8090)));
+    assert_eq!(parse_port_range("8090-8080"), None);
+    assert_eq!(parse_port_range("0"), None);
+    assert_eq!(parse_port_range("70000"), None);
+    assert_eq!(parse_port_range("http"), None);
+    assert_eq!(parse_port_range(""), None);
+  }
+
+  #[test]
+  fn ports() {
+    let mut errors = Vec::new();
+    let (exposed, bindings) = engine_ports(
+      &[
+        conversion("8080", "80"),
+        conversion("127.0.0.1:5353", "53/udp"),
+        conversion("[::1]:9000-9001", "9000-9001"),
+        conversion("", "3000"),
+      ],
+      &mut errors,
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(exposed.len(), 5);
+    let binding = |key: &str| {
+      bindings.get(key).cloned().flatten().unwrap_or_default()
+    };
+    assert_eq!(
+      binding("80/tcp"),
+      [PortBinding {
+        host_ip: None,
+        host_port: Some("8080".to_string()),
+      }]
+    );
+    assert_eq!(
+      binding("53/udp"),
+      [PortBinding {
+        host_ip: Some("127.0.0.1".to_string()),
+        host_port: Some("5353".to_string()),
+      }]
+    );
+    assert_eq!(
+      binding("9001/tcp"),
+      [PortBinding {
+        host_ip: Some("::1".to_string()),
+        host_port: Some("9001".to_string()),
+      }]
+    );
+    assert_eq!(
+      binding("3000/tcp"),
+      [PortBinding {
+        host_ip: None,
+        host_port: None,
+      }]
+    );
+  }
+
+  #[test]
+  fn ports_errors() {
+    let mut errors = Vec::new();
+    let (exposed, _) = engine_ports(
+      &[
+        conversion("8080", "80/http"),
+        conversion("localhost:8080", "80"),
+        conversion("8080-8082", "80-81"),
+        conversion("8080", "port"),
+        conversion("host", "80"),
+      ],
+      &mut errors,
+    );
+    assert!(exposed.is_empty());
+    assert_eq!(errors.len(), 5, "{errors:?}");
+  }
+
+  #[test]
+  fn binds() {
+    let mut errors = Vec::new();
+    let binds = engine_binds(
+      &[
+        conversion("/etc/komodo", "/config:ro"),
+        conversion("data_volume", "/data"),
+        conversion(
+          "/var/run/docker.sock",
+          "/var/run/docker.sock:rw,z",
+        ),
+      ],
+      &mut errors,
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    assert_eq!(
+      binds,
+      [
+        "/etc/komodo:/config:ro",
+        "data_volume:/data",
+        "/var/run/docker.sock:/var/run/docker.sock:rw,z",
+      ]
+    );
+  }
+
+  #[test]
+  fn binds_errors() {
+    let mut errors = Vec::new();
+    let binds = engine_binds(
+      &[
+        conversion("./relative", "/data"),
+        conversion("", "/data"),
+        conversion("volume", "data"),
+        conversion("volume", "/data:rx"),
+      ],
+      &mut errors,
+    );
+    assert!(binds.is_empty());
+    assert_eq!(errors.len(), 4, "{errors:?}");
+  }
+}
@@ -0,0 +1,357 @@
+use std::{
+  io::ErrorKind,
+  path::{Component, Path, PathBuf},
+  time::UNIX_EPOCH,
+};
+
+use anyhow::{Context, anyhow};
+use komodo_client::entities::{
+  FileContents, server::HostFileEntry, update::Log,
+};
+use periphery_client::api::files::*;
+use resolver_api::Resolve;
+use tokio::fs;
+
+use crate::config::periphery_config;
+
+/// Larger files are most likely not config files,
+/// and too large to edit in the browser.
+const MAX_READ_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+impl Resolve<super::Args> for ListFiles {
+  #[instrument(name = "ListFiles", level = "debug")]
+  async fn resolve(
+    self,
+    _: &super::Args,
+  ) -> serror::Result<Vec<HostFileEntry>> {
+    if self.path.is_empty() {
+      let mut entries = Vec::new();
+      for root in file_roots().await {
+        let Ok(metadata) = fs::metadata(&root).await else {
+          continue;
+        };
+        let path = root.display().to_string();
+        entries.push(host_file_entry(path.clone(), path, &metadata));
+      }
+      return Ok(entries);
+    }
+    let (dir, _) = resolve_path(&self.path).await?;
+    let mut read_dir = fs::read_dir(&dir)
+      .await
+      .with_context(|| format!("Failed to read directory {dir:?}"))?;
+    let mut entries = Vec::new();
+    while let Some(entry) = read_dir
+      .next_entry()
+      .await
+      .with_context(|| format!("Failed to read directory {dir:?}"))?
+    {
+      let path = entry.path();
+      // Follow symlinks for the entry type, falling back to the link itself
+      // if it is broken.
+      let metadata = match fs::metadata(&path).await {
+        Ok(metadata) => metadata,
+        Err(_) => match fs::symlink_metadata(&path).await {
+          Ok(metadata) => metadata,
+          Err(_) => continue,
+        },
+      };
+      entries.push(host_file_entry(
+        entry.file_name().to_string_lossy().to_string(),
+        path.display().to_string(),
+        &metadata,
+      ));
+    }
+    // Directories first
+    entries.sort_by(|a, b| {
+      b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(entries)
+  }
+}
+
+//
+
+impl Resolve<super::Args> for ReadFile {
+  #[instrument(name = "ReadFile", level = "debug")]
+  async fn resolve(
+    self,
+    _: &super::Args,
+  ) -> serror::Result<FileContents> {
+    let (path, _) = resolve_path(&self.path).await?;
+    let metadata = fs::metadata(&path).await.with_context(|| {
+      format!("Failed to read metadata of {path:?}")
+    })?;
+    if metadata.is_dir() {
+      return Err(anyhow!("{path:?} is a directory").into());
+    }
+    if metadata.len() > MAX_READ_FILE_BYTES {
+      return Err(
+        anyhow!(
+          "{path:?} is too large to read ({} bytes, max {MAX_READ_FILE_BYTES})",
+          metadata.len()
+        )
+        .into(),
+      );
+    }
+    let contents =
+      fs::read_to_string(&path).await.with_context(|| {
+        format!("Failed to read {path:?}, it may not be a text file")
+      })?;
+    Ok(FileContents {
+      path: path.display().to_string(),
+      contents,
+    })
+  }
+}
+
+//
+
+impl Resolve<super::Args> for WriteFile {
+  #[instrument(name = "WriteFile", skip_all, fields(path = &self.path))]
+  async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
+    let (path, is_root) = resolve_path(&self.path).await?;
+    if is_root || fs::metadata(&path).await.is_ok_and(|m| m.is_dir())
+    {
+      return Err(anyhow!("{path:?} is a directory").into());
+    }
+    // Ensure parent directory exists
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).await.with_context(|| {
+        format!("Failed to initialize parent directory {parent:?}")
+      })?;
+    }
+    fs::write(&path, self.contents)
+      .await
+      .with_context(|| format!("Failed to write file to {path:?}"))?;
+    Ok(Log::simple(
+      "Write file",
+      format!("File contents written to {path:?}"),
+    ))
+  }
+}
+
+//
+
+impl Resolve<super::Args> for DeleteFile {
+  #[instrument(name = "DeleteFile")]
+  async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
+    let path = Path::new(&self.path);
+    // Resolve the parent, so a symlink is removed itself
+    // rather than the file it points to.
+    let (Some(parent), Some(name)) =
+      (path.parent(), path.file_name())
+    else {
+      return Err(anyhow!("Cannot delete {path:?}").into());
+    };
+    let (parent, _) = resolve_path(&parent.to_string_lossy()).await?;
+    let path = parent.join(name);
+    if file_roots().await.contains(&path) {
+      return Err(anyhow!("Cannot delete file root {path:?}").into());
+    }
+    let metadata =
+      fs::symlink_metadata(&path).await.with_context(|| {
+        format!("Failed to read metadata of {path:?}")
+      })?;
+    if metadata.is_dir() {
+      fs::remove_dir_all(&path).await.with_context(|| {
+        format!("Failed to delete directory {path:?}")
+      })?;
+    } else {
+      fs::remove_file(&path)
+        .await
+        .with_context(|| format!("Failed to delete file {path:?}"))?;
+    }
+    Ok(Log::simple("Delete file", format!("Deleted {path:?}")))
+  }
+}
+
+//
+
+/// The canonicalized directories the file apis are confined to.
+/// Roots which don't exist on the host are skipped.
+async fn file_roots() -> Vec<PathBuf> {
+  let config = periphery_config();
+  let mut roots = Vec::new();
+  for root in [&config.stack_dir, &config.repo_dir]
+    .into_iter()
+    .chain(&config.file_roots)
+  {
+    let Ok(root) = fs::canonicalize(root).await else {
+      continue;
+    };
+    if !roots.contains(&root) {
+      roots.push(root);
+    }
+  }
+  roots
+}
+
+/// Resolves any symlinks in the path, and ensures the result
+/// is inside one of the file roots. The path doesn't need to exist.
+/// Also returns whether the path is a file root itself.
+async fn resolve_path(path: &str) -> anyhow::Result<(PathBuf, bool)> {
+  resolve_path_in(path, &file_roots().await).await
+}
+
+async fn resolve_path_in(
+  path: &str,
+  roots: &[PathBuf],
+) -> anyhow::Result<(PathBuf, bool)> {
+  let path = Path::new(path);
+  if !path.is_absolute() {
+    return Err(anyhow!("Path must be absolute, got {path:?}"));
+  }
+  if path.components().any(|c| c == Component::ParentDir) {
+    return Err(anyhow!("Path cannot contain '..', got {path:?}"));
+  }
+  // Canonicalize the deepest existing ancestor,
+  // then add back the components which don't exist yet.
+  let mut existing = path;
+  let mut missing = Vec::new();
+  let canonical = loop {
+    match fs::canonicalize(existing).await {
+      Ok(canonical) => break canonical,
+      Err(e) if e.kind() == ErrorKind::NotFound => {
+        let (Some(parent), Some(name)) =
+          (existing.parent(), existing.file_name())
+        else {
+          return Err(anyhow!("Failed to resolve {path:?}"));
+        };
+        missing.push(name);
+        existing = parent;
+      }
+      Err(e) => {
+        return Err(e)
+          .with_context(|| format!("Failed to resolve {path:?}"));
+      }
+    }
+  };
+  let resolved = missing
+    .into_iter()
+    .rev()
+    .fold(canonical, |path, name| path.join(name));
+  if !roots.iter().any(|root| resolved.starts_with(root)) {
+    return Err(anyhow!(
+      "{path:?} is outside of the allowed file roots"
+    ));
+  }
+  let is_root = roots.contains(&resolved);
+  Ok((resolved, is_root))
+}
+
+fn host_file_entry(
+  name: String,
+  path: String,
+  metadata: &std::fs::Metadata,
+) -> HostFileEntry {
+  let is_dir = metadata.is_dir();
+  HostFileEntry {
+    name,
+    path,
+    is_dir,
+    size: if is_dir { 0 } else { metadata.len() as i64 },
+    modified: metadata
+      .modified()
+      .ok()
+      .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+      .map(|modified| modified.as_millis() as i64)
+      .unwrap_or_default(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A file root with a sibling directory outside of it.
+  struct TestDirs {
+    base: PathBuf,
+    root: PathBuf,
+    outside: PathBuf,
+  }
+
+  impl TestDirs {
+    fn new(name: &str) -> TestDirs {
+      let base = std::env::temp_dir().join(format!(
+        "periphery-files-{name}-{}",
+        std::process::id()
+      ));
+      let root = base.join("root");
+      let outside = base.join("outside");
+      std::fs::create_dir_all(root.join("dir")).unwrap();
+      std::fs::create_dir_all(&outside).unwrap();
+      // The roots are canonicalized, same as file_roots
+      TestDirs {
+        root: std::fs::canonicalize(&root).unwrap(),
+        outside: std::fs::canonicalize(&outside).unwrap(),
+        base,
+      }
+    }
+
+    async fn resolve(
+      &self,
+      path: &Path,
+    ) -> anyhow::Result<(PathBuf, bool)> {
+      resolve_path_in(
+        path.to_str().unwrap(),
+        std::slice::from_ref(&self.root),
+      )
+      .await
+    }
+  }
+
+  impl Drop for TestDirs {
+    fn drop(&mut self) {
+      let _ = std::fs::remove_dir_all(&self.base);
+    }
+  }
+
+  #[tokio::test]
+  async fn paths_inside_root() {
+    let dirs = TestDirs::new("inside");
+    assert_eq!(
+      dirs.resolve(&dirs.root).await.unwrap(),
+      (dirs.root.clone(), true)
+    );
+    assert_eq!(
+      dirs.resolve(&dirs.root.join("dir")).await.unwrap(),
+      (dirs.root.join("dir"), false)
+    );
+    // Doesn't need to exist yet
+    assert_eq!(
+      dirs.resolve(&dirs.root.join("new/file.txt")).await.unwrap(),
+      (dirs.root.join("new/file.txt"), false)
+    );
+    assert!(dirs.resolve(&dirs.outside).await.is_err());
+    assert!(dirs.resolve(Path::new("relative/path")).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn parent_dir_rejected() {
+    let dirs = TestDirs::new("parent");
+    assert!(
+      dirs.resolve(&dirs.root.join("../outside")).await.is_err()
+    );
+    // Even when it would resolve back inside the root
+    assert!(
+      dirs.resolve(&dirs.root.join("dir/../dir")).await.is_err()
+    );
+  }
+
+  #[tokio::test]
+  async fn symlink_out_of_root_rejected() {
+    let dirs = TestDirs::new("symlink");
+    let link = dirs.root.join("link");
+    std::os::unix::fs::symlink(&dirs.outside, &link).unwrap();
+    assert!(dirs.resolve(&link).await.is_err());
+    assert!(dirs.resolve(&link.join("file.txt")).await.is_err());
+    // Symlinks which stay inside the root are fine
+    let inner = dirs.root.join("inner");
+    std::os::unix::fs::symlink(dirs.root.join("dir"), &inner)
+      .unwrap();
+    assert_eq!(
+      dirs.resolve(&inner).await.unwrap(),
+      (dirs.root.join("dir"), false)
+    );
+  }
+}
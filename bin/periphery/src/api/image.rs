@@ -11,14 +11,20 @@ use komodo_client::entities::{
 use periphery_client::api::image::*;
 use resolver_api::Resolve;
 
-use crate::docker::{docker_client, docker_login};
+use crate::{
+  docker::{
+    docker_cli, docker_client, docker_login, pull_image,
+    registry_credentials,
+  },
+  pull_progress::get_pull_progress,
+};
 
 //
 
 impl Resolve<super::Args> for InspectImage {
   #[instrument(name = "InspectImage", level = "debug")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Image> {
-    Ok(docker_client().inspect_image(&self.name).await?)
+    Ok(docker_client()?.inspect_image(&self.name).await?)
   }
 }
 
@@ -30,7 +36,7 @@ impl Resolve<super::Args> for ImageHistory {
     self,
     _: &super::Args,
   ) -> serror::Result<Vec<ImageHistoryResponseItem>> {
-    Ok(docker_client().image_history(&self.name).await?)
+    Ok(docker_client()?.image_history(&self.name).await?)
   }
 }
 
@@ -52,6 +58,7 @@ impl Resolve<super::Args> for PullImage {
       name,
       account,
       token,
+      progress_id,
     } = self;
     // Acquire the image lock
     let lock = pull_cache().get_lock(name.clone()).await;
@@ -67,19 +74,13 @@ impl Resolve<super::Args> for PullImage {
     }
 
     let res = async {
-      docker_login(
-        &extract_registry_domain(&name)?,
-        account.as_deref().unwrap_or_default(),
-        token.as_deref(),
-      )
-      .await?;
+      let domain = extract_registry_domain(&name)?;
+      let account = account.as_deref().unwrap_or_default();
+      docker_login(&domain, account, token.as_deref()).await?;
+      let credentials =
+        registry_credentials(&domain, account, token.as_deref())?;
       anyhow::Ok(
-        run_komodo_command(
-          "Docker Pull",
-          None,
-          format!("docker pull {name}"),
-        )
-        .await,
+        pull_image(&name, credentials, progress_id.as_deref()).await,
       )
     }
     .await;
@@ -94,10 +95,22 @@ impl Resolve<super::Args> for PullImage {
 
 //
 
+impl Resolve<super::Args> for GetPullProgress {
+  #[instrument(name = "GetPullProgress", level = "debug")]
+  async fn resolve(
+    self,
+    _: &super::Args,
+  ) -> serror::Result<Option<String>> {
+    Ok(get_pull_progress(&self.id))
+  }
+}
+
+//
+
 impl Resolve<super::Args> for DeleteImage {
   #[instrument(name = "DeleteImage")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = format!("docker image rm {}", self.name);
+    let command = format!("{} image rm {}", docker_cli(), self.name);
     Ok(run_komodo_command("Delete Image", None, command).await)
   }
 }
@@ -107,7 +120,7 @@ impl Resolve<super::Args> for DeleteImage {
 impl Resolve<super::Args> for PruneImages {
   #[instrument(name = "PruneImages")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = String::from("docker image prune -a -f");
+    let command = format!("{} image prune -a -f", docker_cli());
     Ok(run_komodo_command("Prune Images", None, command).await)
   }
 }
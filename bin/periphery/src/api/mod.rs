@@ -1,29 +1,45 @@
 use anyhow::Context;
+use axum::http::StatusCode;
 use command::run_komodo_command;
 use derive_variants::EnumVariants;
-use futures::TryFutureExt;
+use futures::{TryFutureExt, future::join_all};
 use komodo_client::entities::{
   SystemCommand,
   config::{DockerRegistry, GitProvider},
+  docker::{
+    container::ContainerListItem, disk_usage::DockerDiskUsage,
+  },
   update::Log,
 };
 use periphery_client::api::{
-  GetDockerLists, GetDockerListsResponse, GetHealth,
-  GetHealthResponse, GetVersion, GetVersionResponse,
-  ListDockerRegistries, ListGitProviders, ListSecrets, PruneSystem,
-  RunCommand, build::*, compose::*, container::*, git::*, image::*,
-  network::*, stats::*, volume::*,
+  GetDockerDiskUsage, GetDockerLists, GetDockerListsResponse,
+  GetHealth, GetHealthResponse, GetVersion, GetVersionResponse,
+  ListDockerEndpoints, ListDockerRegistries, ListGitProviders,
+  ListSecrets, PruneSystem, RotatePasskey, RotateSslCert,
+  RotateSslCertResponse, RunCommand, build::*, compose::*,
+  container::*, files::*, git::*, image::*, network::*, stats::*,
+  volume::*,
 };
 use resolver_api::Resolve;
 use response::Response;
 use serde::{Deserialize, Serialize};
-
-use crate::{config::periphery_config, docker::docker_client};
+use serror::AddStatusCode;
+
+use crate::{
+  capabilities,
+  config::periphery_config,
+  docker::{
+    docker_cli, docker_client, docker_endpoints,
+    endpoint_docker_client,
+  },
+  passkeys, ssl,
+};
 
 mod build;
 mod compose;
 mod container;
 mod deploy;
+mod files;
 mod git;
 mod image;
 mod network;
@@ -48,8 +64,13 @@ pub enum PeripheryRequest {
   // Config (Read)
   ListGitProviders(ListGitProviders),
   ListDockerRegistries(ListDockerRegistries),
+  ListDockerEndpoints(ListDockerEndpoints),
   ListSecrets(ListSecrets),
 
+  // Auth (Write)
+  RotatePasskey(RotatePasskey),
+  RotateSslCert(RotateSslCert),
+
   // Stats / Info (Read)
   GetSystemInformation(GetSystemInformation),
   GetSystemStats(GetSystemStats),
@@ -82,12 +103,21 @@ pub enum PeripheryRequest {
   ComposeUp(ComposeUp),
   ComposeExecution(ComposeExecution),
 
+  // Files (Read)
+  ListFiles(ListFiles),
+  ReadFile(ReadFile),
+
+  // Files (Write)
+  WriteFile(WriteFile),
+  DeleteFile(DeleteFile),
+
   // Container (Read)
   InspectContainer(InspectContainer),
   GetContainerLog(GetContainerLog),
   GetContainerLogSearch(GetContainerLogSearch),
   GetContainerStats(GetContainerStats),
   GetContainerStatsList(GetContainerStatsList),
+  GetContainerStatsRecords(GetContainerStatsRecords),
 
   // Container (Write)
   Deploy(Deploy),
@@ -116,6 +146,7 @@ pub enum PeripheryRequest {
   // Image (Read)
   InspectImage(InspectImage),
   ImageHistory(ImageHistory),
+  GetPullProgress(GetPullProgress),
 
   // Image (Write)
   PullImage(PullImage),
@@ -124,13 +155,17 @@ pub enum PeripheryRequest {
 
   // Volume (Read)
   InspectVolume(InspectVolume),
+  ListVolumeBackups(ListVolumeBackups),
 
   // Volume (Write)
   DeleteVolume(DeleteVolume),
   PruneVolumes(PruneVolumes),
+  BackupVolume(BackupVolume),
+  RestoreVolume(RestoreVolume),
 
   // All in one (Read)
   GetDockerLists(GetDockerLists),
+  GetDockerDiskUsage(GetDockerDiskUsage),
 
   // All in one (Write)
   PruneSystem(PruneSystem),
@@ -203,15 +238,52 @@ impl Resolve<Args> for ListSecrets {
   }
 }
 
+//
+
+impl Resolve<Args> for RotatePasskey {
+  #[instrument(name = "RotatePasskey", skip_all)]
+  async fn resolve(self, _: &Args) -> serror::Result<Log> {
+    passkeys::rotate(self.passkey)?;
+    Ok(Log::simple(
+      "Rotate passkey",
+      String::from(
+        "New passkey accepted. Previous passkeys are accepted until the rollover completes.",
+      ),
+    ))
+  }
+}
+
+//
+
+impl Resolve<Args> for RotateSslCert {
+  #[instrument(name = "RotateSslCert", skip_all)]
+  async fn resolve(
+    self,
+    _: &Args,
+  ) -> serror::Result<RotateSslCertResponse> {
+    let fingerprint = ssl::rotate_certs().await?;
+    Ok(RotateSslCertResponse { fingerprint })
+  }
+}
+
+//
+
 impl Resolve<Args> for GetDockerLists {
   #[instrument(name = "GetDockerLists", level = "debug", skip_all)]
   async fn resolve(
     self,
     _: &Args,
   ) -> serror::Result<GetDockerListsResponse> {
-    let docker = docker_client();
+    let docker = docker_client()?;
     let containers =
       docker.list_containers().await.map_err(Into::into);
+    let containers = match containers {
+      Ok(mut containers) => {
+        containers.extend(list_endpoint_containers().await);
+        Ok(containers)
+      }
+      Err(e) => Err(e),
+    };
     // Should still try to retrieve other docker lists, but "in_use" will be false for images, networks, volumes
     let _containers = match &containers {
       Ok(containers) => containers.as_slice(),
@@ -235,9 +307,75 @@ impl Resolve<Args> for GetDockerLists {
   }
 }
 
+/// Lists the containers on the non-default docker endpoints.
+/// Endpoints which fail to list are skipped with a warning,
+/// so one unreachable engine doesn't hide the others.
+async fn list_endpoint_containers() -> Vec<ContainerListItem> {
+  let lists = join_all(docker_endpoints().into_iter().map(
+    |endpoint| async move {
+      let containers = async {
+        endpoint_docker_client(Some(endpoint))?
+          .list_containers()
+          .await
+      }
+      .await;
+      (endpoint, containers)
+    },
+  ))
+  .await;
+  let mut res = Vec::new();
+  for (endpoint, containers) in lists {
+    match containers {
+      Ok(containers) => {
+        res.extend(containers.into_iter().map(|mut container| {
+          container.docker_endpoint = endpoint.name.clone();
+          container
+        }))
+      }
+      Err(e) => warn!(
+        "Failed to list containers on docker endpoint {} | {e:#}",
+        endpoint.name
+      ),
+    }
+  }
+  res
+}
+
+impl Resolve<Args> for ListDockerEndpoints {
+  #[instrument(
+    name = "ListDockerEndpoints",
+    level = "debug",
+    skip_all
+  )]
+  async fn resolve(self, _: &Args) -> serror::Result<Vec<String>> {
+    Ok(
+      docker_endpoints()
+        .into_iter()
+        .map(|endpoint| endpoint.name.clone())
+        .collect(),
+    )
+  }
+}
+
+impl Resolve<Args> for GetDockerDiskUsage {
+  #[instrument(
+    name = "GetDockerDiskUsage",
+    level = "debug",
+    skip_all
+  )]
+  async fn resolve(
+    self,
+    _: &Args,
+  ) -> serror::Result<DockerDiskUsage> {
+    Ok(docker_client()?.disk_usage().await?)
+  }
+}
+
 impl Resolve<Args> for RunCommand {
   #[instrument(name = "RunCommand")]
   async fn resolve(self, _: &Args) -> serror::Result<Log> {
+    capabilities::check_command(&self.command)
+      .status_code(StatusCode::FORBIDDEN)?;
     let RunCommand {
       command: SystemCommand { path, command },
     } = self;
@@ -258,7 +396,8 @@ impl Resolve<Args> for RunCommand {
 impl Resolve<Args> for PruneSystem {
   #[instrument(name = "PruneSystem", skip_all)]
   async fn resolve(self, _: &Args) -> serror::Result<Log> {
-    let command = String::from("docker system prune -a -f --volumes");
+    let command =
+      format!("{} system prune -a -f --volumes", docker_cli());
     Ok(run_komodo_command("Prune System", None, command).await)
   }
 }
@@ -5,14 +5,14 @@ use komodo_client::entities::{
 use periphery_client::api::network::*;
 use resolver_api::Resolve;
 
-use crate::docker::docker_client;
+use crate::docker::{docker_cli, docker_client};
 
 //
 
 impl Resolve<super::Args> for InspectNetwork {
   #[instrument(name = "InspectNetwork", level = "debug")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Network> {
-    Ok(docker_client().inspect_network(&self.name).await?)
+    Ok(docker_client()?.inspect_network(&self.name).await?)
   }
 }
 
@@ -26,7 +26,8 @@ impl Resolve<super::Args> for CreateNetwork {
       Some(driver) => format!(" -d {driver}"),
       None => String::new(),
     };
-    let command = format!("docker network create{driver} {name}");
+    let command =
+      format!("{} network create{driver} {name}", docker_cli());
     Ok(run_komodo_command("Create Network", None, command).await)
   }
 }
@@ -36,7 +37,8 @@ impl Resolve<super::Args> for CreateNetwork {
 impl Resolve<super::Args> for DeleteNetwork {
   #[instrument(name = "DeleteNetwork", skip(self))]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = format!("docker network rm {}", self.name);
+    let command =
+      format!("{} network rm {}", docker_cli(), self.name);
     Ok(run_komodo_command("Delete Network", None, command).await)
   }
 }
@@ -46,7 +48,7 @@ impl Resolve<super::Args> for DeleteNetwork {
 impl Resolve<super::Args> for PruneNetworks {
   #[instrument(name = "PruneNetworks", skip(self))]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = String::from("docker network prune -f");
+    let command = format!("{} network prune -f", docker_cli());
     Ok(run_komodo_command("Prune Networks", None, command).await)
   }
 }
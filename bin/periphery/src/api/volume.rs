@@ -1,16 +1,33 @@
+use anyhow::{Context, anyhow};
 use command::run_komodo_command;
-use komodo_client::entities::{docker::volume::Volume, update::Log};
+use formatting::format_serror;
+use komodo_client::entities::{
+  config::periphery::ContainerEngine,
+  docker::{
+    container::ContainerStateStatusEnum,
+    volume::{Volume, VolumeBackup},
+  },
+  komodo_timestamp,
+  update::Log,
+};
 use periphery_client::api::volume::*;
 use resolver_api::Resolve;
 
-use crate::docker::docker_client;
+use crate::{
+  backup::{
+    BACKUP_EXTENSION, BackupStorage, backup_storage,
+    volume_backup_dir,
+  },
+  config::periphery_config,
+  docker::{docker_cli, docker_client},
+};
 
 //
 
 impl Resolve<super::Args> for InspectVolume {
   #[instrument(name = "InspectVolume", level = "debug")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Volume> {
-    Ok(docker_client().inspect_volume(&self.name).await?)
+    Ok(docker_client()?.inspect_volume(&self.name).await?)
   }
 }
 
@@ -19,7 +36,7 @@ impl Resolve<super::Args> for InspectVolume {
 impl Resolve<super::Args> for DeleteVolume {
   #[instrument(name = "DeleteVolume")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = format!("docker volume rm {}", self.name);
+    let command = format!("{} volume rm {}", docker_cli(), self.name);
     Ok(run_komodo_command("Delete Volume", None, command).await)
   }
 }
@@ -29,7 +46,165 @@ impl Resolve<super::Args> for DeleteVolume {
 impl Resolve<super::Args> for PruneVolumes {
   #[instrument(name = "PruneVolumes")]
   async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
-    let command = String::from("docker volume prune -a -f");
+    // Podman prunes all unused volumes, and doesn't take `-a`
+    let args = match periphery_config().container_engine {
+      ContainerEngine::Docker => "-a -f",
+      ContainerEngine::Podman => "-f",
+    };
+    let command = format!("{} volume prune {args}", docker_cli());
     Ok(run_komodo_command("Prune Volumes", None, command).await)
   }
 }
+
+//
+
+impl Resolve<super::Args> for ListVolumeBackups {
+  #[instrument(name = "ListVolumeBackups", level = "debug")]
+  async fn resolve(
+    self,
+    _: &super::Args,
+  ) -> serror::Result<Vec<VolumeBackup>> {
+    Ok(backup_storage()?.list(&self.name).await?)
+  }
+}
+
+//
+
+impl Resolve<super::Args> for BackupVolume {
+  #[instrument(name = "BackupVolume")]
+  async fn resolve(
+    self,
+    _: &super::Args,
+  ) -> serror::Result<Vec<Log>> {
+    let BackupVolume { name, keep } = self;
+    let dir = volume_backup_dir(&name)?;
+    let storage = backup_storage()?;
+    // Fail early if the volume doesn't exist,
+    // rather than docker creating an empty one.
+    docker_client()?.inspect_volume(&name).await?;
+    tokio::fs::create_dir_all(&dir).await.with_context(|| {
+      format!("Failed to create backup directory {dir:?}")
+    })?;
+    let file = format!("{}{BACKUP_EXTENSION}", komodo_timestamp());
+    let command = format!(
+      "{} run --rm -v {name}:/volume:ro -v {}:/backup {} tar -czf /backup/{file} -C /volume .",
+      docker_cli(),
+      dir.display(),
+      periphery_config().backup_image,
+    );
+    let log =
+      run_komodo_command("Backup Volume", None, command).await;
+    if !log.success {
+      // Don't leave partial archives around to be restored
+      let _ = tokio::fs::remove_file(dir.join(&file)).await;
+      return Ok(vec![log]);
+    }
+    let mut logs = vec![log];
+    if let Some(log) = storage.store(&name, &file).await {
+      let success = log.success;
+      logs.push(log);
+      if !success {
+        // Don't leave the archive staged on the host
+        let _ = tokio::fs::remove_file(dir.join(&file)).await;
+        return Ok(logs);
+      }
+    }
+    if let Some(keep) = keep {
+      logs.push(prune_volume_backups(storage, &name, keep).await);
+    }
+    Ok(logs)
+  }
+}
+
+//
+
+impl Resolve<super::Args> for RestoreVolume {
+  #[instrument(name = "RestoreVolume")]
+  async fn resolve(self, _: &super::Args) -> serror::Result<Log> {
+    let RestoreVolume { name, backup } = self;
+    let dir = volume_backup_dir(&name)?;
+    let storage = backup_storage()?;
+    let backups = storage.list(&name).await?;
+    // Only restore files from the list, which are known to be archives
+    let backup = match backup {
+      Some(backup) => backups
+        .into_iter()
+        .find(|b| b.name == backup)
+        .with_context(|| {
+          format!("No backup {backup} found for volume {name}")
+        })?,
+      None => backups.into_iter().next().with_context(|| {
+        format!("No backups found for volume {name}")
+      })?,
+    };
+    let running = docker_client()?
+      .list_containers()
+      .await?
+      .into_iter()
+      .filter(|container| {
+        matches!(
+          container.state,
+          ContainerStateStatusEnum::Running
+            | ContainerStateStatusEnum::Paused
+            | ContainerStateStatusEnum::Restarting
+        ) && container.volumes.contains(&name)
+      })
+      .map(|container| container.name)
+      .collect::<Vec<_>>();
+    if !running.is_empty() {
+      return Err(
+        anyhow!(
+          "Volume {name} is in use by running containers: {}. Stop them before restoring.",
+          running.join(", ")
+        )
+        .into(),
+      );
+    }
+    storage.stage(&name, &backup.name).await?;
+    let command = format!(
+      "{} run --rm -v {name}:/volume -v {}:/backup:ro {} sh -c 'find /volume -mindepth 1 -delete && tar -xzf /backup/{} -C /volume'",
+      docker_cli(),
+      dir.display(),
+      periphery_config().backup_image,
+      backup.name,
+    );
+    let log =
+      run_komodo_command("Restore Volume", None, command).await;
+    storage.unstage(&name, &backup.name).await;
+    Ok(log)
+  }
+}
+
+/// Removes all but the latest `keep` backups.
+/// Always keeps at least the latest backup.
+async fn prune_volume_backups(
+  storage: &BackupStorage,
+  volume: &str,
+  keep: u32,
+) -> Log {
+  let backups = match storage.list(volume).await {
+    Ok(backups) => backups,
+    Err(e) => {
+      return Log::error("Prune Backups", format_serror(&e.into()));
+    }
+  };
+  let mut removed = Vec::new();
+  let mut errors = Vec::new();
+  for backup in backups.into_iter().skip(keep.max(1) as usize) {
+    match storage.remove(volume, &backup.name).await {
+      Ok(_) => removed.push(backup.name),
+      Err(e) => errors.push(format!("{e:#}")),
+    }
+  }
+  let stdout = if removed.is_empty() {
+    String::from("No backups to remove")
+  } else {
+    format!("Removed backups: {}", removed.join(", "))
+  };
+  let mut log = Log::simple("Prune Backups", stdout);
+  if !errors.is_empty() {
+    log.stderr = errors.join("\n");
+    log.success = false;
+  }
+  log
+}
@@ -0,0 +1,433 @@
+use std::{
+  path::{Path, PathBuf},
+  sync::OnceLock,
+};
+
+use anyhow::{Context, anyhow};
+use aws_sdk_s3::{
+  Client,
+  config::{BehaviorVersion, Credentials, Region},
+  error::DisplayErrorContext,
+  primitives::ByteStream,
+};
+use formatting::format_serror;
+use komodo_client::entities::{
+  config::periphery::BackupS3Config, docker::volume::VolumeBackup,
+  update::Log,
+};
+
+use crate::{
+  config::periphery_config, docker::current_docker_endpoint,
+};
+
+pub const BACKUP_EXTENSION: &str = ".tar.gz";
+
+/// The backup directory name of the default docker endpoint.
+/// Can't clash with a configured endpoint,
+/// as those must start with an alphanumeric.
+const DEFAULT_ENDPOINT_DIR: &str = "_default";
+
+/// Where the volume backups are stored.
+///
+/// The backup container always writes / reads the archive
+/// in the volume folder of `backup_dir`, so other storage
+/// only needs to move the archive in and out of it.
+pub enum BackupStorage {
+  /// Kept in `backup_dir` on the host.
+  Local,
+  /// Uploaded to the S3 compatible bucket,
+  /// and only staged in `backup_dir`.
+  S3 {
+    client: Client,
+    config: &'static BackupS3Config,
+  },
+}
+
+pub fn backup_storage() -> anyhow::Result<&'static BackupStorage> {
+  static BACKUP_STORAGE: OnceLock<BackupStorage> = OnceLock::new();
+  let config = &periphery_config().backup_s3;
+  // Without a prefix, every Server sharing the bucket
+  // would read and prune the same backups.
+  if !config.bucket.is_empty() && config.prefix.is_empty() {
+    return Err(anyhow!(
+      "backup_s3.prefix must be configured to store backups in a bucket. Use a different prefix for each Server sharing the bucket."
+    ));
+  }
+  Ok(BACKUP_STORAGE.get_or_init(|| {
+    if config.bucket.is_empty() {
+      BackupStorage::Local
+    } else {
+      BackupStorage::S3 {
+        client: s3_client(config),
+        config,
+      }
+    }
+  }))
+}
+
+fn s3_client(config: &BackupS3Config) -> Client {
+  let region = if config.region.is_empty() {
+    String::from("us-east-1")
+  } else {
+    config.region.clone()
+  };
+  let mut builder = aws_sdk_s3::Config::builder()
+    .behavior_version(BehaviorVersion::latest())
+    .region(Region::new(region))
+    .credentials_provider(Credentials::new(
+      &config.access_key_id,
+      &config.secret_access_key,
+      None,
+      None,
+      "periphery-config",
+    ));
+  if !config.endpoint.is_empty() {
+    // MinIO and most self hosted stores
+    // don't support virtual hosted buckets.
+    builder = builder
+      .endpoint_url(&config.endpoint)
+      .force_path_style(true);
+  }
+  Client::from_conf(builder.build())
+}
+
+/// The directory the backup container reads / writes
+/// the archives of the volume in.
+pub fn volume_backup_dir(volume: &str) -> anyhow::Result<PathBuf> {
+  Ok(
+    periphery_config()
+      .backup_dir
+      .join(current_volume_backup_path(volume)?),
+  )
+}
+
+/// The [volume_backup_path] on the docker endpoint of the request.
+fn current_volume_backup_path(
+  volume: &str,
+) -> anyhow::Result<String> {
+  volume_backup_path(
+    current_docker_endpoint().map(|endpoint| endpoint.name.as_str()),
+    volume,
+  )
+}
+
+/// `{endpoint}/{volume}`, relative to `backup_dir` / `prefix`.
+/// Volumes with the same name on different docker endpoints
+/// are kept apart.
+fn volume_backup_path(
+  endpoint: Option<&str>,
+  volume: &str,
+) -> anyhow::Result<String> {
+  if !valid_path_name(volume) {
+    return Err(anyhow!("Invalid volume name: {volume}"));
+  }
+  let endpoint = match endpoint {
+    Some(endpoint) if valid_path_name(endpoint) => endpoint,
+    Some(endpoint) => {
+      return Err(anyhow!(
+        "Docker endpoint name can't be used in backup path: {endpoint}"
+      ));
+    }
+    None => DEFAULT_ENDPOINT_DIR,
+  };
+  Ok(format!("{endpoint}/{volume}"))
+}
+
+/// Same as the names docker allows for volumes
+fn valid_path_name(name: &str) -> bool {
+  name
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphanumeric())
+    && name.chars().all(|c| {
+      c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+    })
+}
+
+impl BackupStorage {
+  /// Latest first.
+  pub async fn list(
+    &self,
+    volume: &str,
+  ) -> anyhow::Result<Vec<VolumeBackup>> {
+    let dir = volume_backup_dir(volume)?;
+    let mut backups = match self {
+      BackupStorage::Local => list_local_backups(&dir).await?,
+      BackupStorage::S3 { client, config } => {
+        list_s3_backups(client, config, volume).await?
+      }
+    };
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+  }
+
+  /// Stores the archive written to the volume backup dir.
+  /// None when the archive is already in place.
+  pub async fn store(&self, volume: &str, file: &str) -> Option<Log> {
+    let BackupStorage::S3 { client, config } = self else {
+      return None;
+    };
+    let key = match current_volume_backup_path(volume) {
+      Ok(path) => s3_key(config, &path, file),
+      Err(e) => {
+        return Some(Log::error(
+          "Upload Backup",
+          format_serror(&e.into()),
+        ));
+      }
+    };
+    let res = async {
+      let path = volume_backup_dir(volume)?.join(file);
+      let body = ByteStream::from_path(&path)
+        .await
+        .with_context(|| format!("Failed to read backup {path:?}"))?;
+      client
+        .put_object()
+        .bucket(&config.bucket)
+        .key(&key)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| anyhow!("{}", DisplayErrorContext(e)))
+        .with_context(|| {
+          format!("Failed to upload backup to {}", config.bucket)
+        })?;
+      // Only the bucket keeps the backup.
+      let _ = tokio::fs::remove_file(&path).await;
+      anyhow::Ok(())
+    }
+    .await;
+    let log = match res {
+      Ok(_) => Log::simple(
+        "Upload Backup",
+        format!("Uploaded backup to s3://{}/{key}", config.bucket),
+      ),
+      Err(e) => Log::error("Upload Backup", format_serror(&e.into())),
+    };
+    Some(log)
+  }
+
+  /// Makes the backup available in the volume backup dir to restore.
+  pub async fn stage(
+    &self,
+    volume: &str,
+    file: &str,
+  ) -> anyhow::Result<()> {
+    let BackupStorage::S3 { client, config } = self else {
+      return Ok(());
+    };
+    let dir = volume_backup_dir(volume)?;
+    tokio::fs::create_dir_all(&dir).await.with_context(|| {
+      format!("Failed to create backup directory {dir:?}")
+    })?;
+    let res = client
+      .get_object()
+      .bucket(&config.bucket)
+      .key(s3_key(config, &current_volume_backup_path(volume)?, file))
+      .send()
+      .await
+      .map_err(|e| anyhow!("{}", DisplayErrorContext(e)))
+      .with_context(|| {
+        format!("Failed to download backup from {}", config.bucket)
+      })?;
+    let path = dir.join(file);
+    let mut staged = tokio::fs::File::create(&path)
+      .await
+      .with_context(|| format!("Failed to create {path:?}"))?;
+    let res =
+      tokio::io::copy(&mut res.body.into_async_read(), &mut staged)
+        .await
+        .with_context(|| {
+          format!("Failed to write backup to {path:?}")
+        });
+    if res.is_err() {
+      let _ = tokio::fs::remove_file(&path).await;
+    }
+    res.map(|_| ())
+  }
+
+  /// Removes the archive staged by [BackupStorage::stage].
+  pub async fn unstage(&self, volume: &str, file: &str) {
+    if let BackupStorage::Local = self {
+      return;
+    }
+    if let Ok(dir) = volume_backup_dir(volume) {
+      let _ = tokio::fs::remove_file(dir.join(file)).await;
+    }
+  }
+
+  pub async fn remove(
+    &self,
+    volume: &str,
+    file: &str,
+  ) -> anyhow::Result<()> {
+    match self {
+      BackupStorage::Local => {
+        let path = volume_backup_dir(volume)?.join(file);
+        tokio::fs::remove_file(&path)
+          .await
+          .with_context(|| format!("Failed to remove {path:?}"))
+      }
+      BackupStorage::S3 { client, config } => client
+        .delete_object()
+        .bucket(&config.bucket)
+        .key(s3_key(
+          config,
+          &current_volume_backup_path(volume)?,
+          file,
+        ))
+        .send()
+        .await
+        .map(|_| ())
+        .map_err(|e| anyhow!("{}", DisplayErrorContext(e)))
+        .with_context(|| {
+          format!("Failed to remove {file} from {}", config.bucket)
+        }),
+    }
+  }
+}
+
+/// `{prefix}{endpoint}/{volume}/{file}`
+fn s3_key(config: &BackupS3Config, path: &str, file: &str) -> String {
+  format!("{}{path}/{file}", config.prefix)
+}
+
+/// Only files named like the backup archives are listed,
+/// so other files can't be restored.
+fn parse_backup(name: &str) -> Option<VolumeBackup> {
+  let created_at =
+    name.strip_suffix(BACKUP_EXTENSION)?.parse().ok()?;
+  Some(VolumeBackup {
+    name: name.to_string(),
+    size: 0,
+    created_at,
+  })
+}
+
+async fn list_local_backups(
+  dir: &Path,
+) -> anyhow::Result<Vec<VolumeBackup>> {
+  let mut entries = match tokio::fs::read_dir(dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      return Ok(Vec::new());
+    }
+    Err(e) => {
+      return Err(e).with_context(|| {
+        format!("Failed to read backup directory {dir:?}")
+      });
+    }
+  };
+  let mut backups = Vec::new();
+  while let Some(entry) = entries
+    .next_entry()
+    .await
+    .context("Failed to read backup directory entry")?
+  {
+    let name = entry.file_name().to_string_lossy().to_string();
+    let Some(backup) = parse_backup(&name) else {
+      continue;
+    };
+    let metadata = entry.metadata().await.with_context(|| {
+      format!("Failed to read metadata of backup {name}")
+    })?;
+    if !metadata.is_file() {
+      continue;
+    }
+    backups.push(VolumeBackup {
+      size: metadata.len(),
+      ..backup
+    });
+  }
+  Ok(backups)
+}
+
+async fn list_s3_backups(
+  client: &Client,
+  config: &BackupS3Config,
+  volume: &str,
+) -> anyhow::Result<Vec<VolumeBackup>> {
+  let prefix =
+    s3_key(config, &current_volume_backup_path(volume)?, "");
+  let mut backups = Vec::new();
+  let mut continuation_token = None;
+  loop {
+    let res = client
+      .list_objects_v2()
+      .bucket(&config.bucket)
+      .prefix(&prefix)
+      .set_continuation_token(continuation_token)
+      .send()
+      .await
+      .map_err(|e| anyhow!("{}", DisplayErrorContext(e)))
+      .with_context(|| {
+        format!("Failed to list backups in {}", config.bucket)
+      })?;
+    backups.extend(res.contents().iter().filter_map(|object| {
+      let name = object.key()?.strip_prefix(&prefix)?;
+      Some(VolumeBackup {
+        size: object.size().unwrap_or_default() as u64,
+        ..parse_backup(name)?
+      })
+    }));
+    continuation_token =
+      res.next_continuation_token().map(String::from);
+    if continuation_token.is_none() {
+      break;
+    }
+  }
+  Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_backup_names() {
+    assert_eq!(
+      parse_backup("1729300000000.tar.gz"),
+      Some(VolumeBackup {
+        name: String::from("1729300000000.tar.gz"),
+        size: 0,
+        created_at: 1729300000000,
+      })
+    );
+    assert!(parse_backup("notes.txt").is_none());
+    assert!(parse_backup("latest.tar.gz").is_none());
+    assert!(parse_backup("nested/1729300000000.tar.gz").is_none());
+  }
+
+  #[test]
+  fn s3_keys_by_volume() {
+    let config = BackupS3Config {
+      prefix: String::from("server-1/"),
+      ..Default::default()
+    };
+    assert_eq!(
+      s3_key(&config, "_default/data", "1729300000000.tar.gz"),
+      "server-1/_default/data/1729300000000.tar.gz"
+    );
+    assert_eq!(
+      s3_key(&config, "_default/data", ""),
+      "server-1/_default/data/"
+    );
+  }
+
+  #[test]
+  fn backup_paths_by_endpoint() {
+    assert_eq!(
+      volume_backup_path(None, "data").unwrap(),
+      "_default/data"
+    );
+    assert_eq!(
+      volume_backup_path(Some("rootless"), "data").unwrap(),
+      "rootless/data"
+    );
+    // Can't clash with the default endpoint
+    assert!(volume_backup_path(Some("_default"), "data").is_err());
+    assert!(volume_backup_path(Some("../other"), "data").is_err());
+    assert!(volume_backup_path(None, "../data").is_err());
+    assert!(volume_backup_path(None, "").is_err());
+  }
+}
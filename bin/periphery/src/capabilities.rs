@@ -0,0 +1,342 @@
+use anyhow::{Context, anyhow};
+use komodo_client::entities::{
+  SystemCommand, config::periphery::PeripheryCapability,
+};
+
+use crate::{api::PeripheryRequest, config::periphery_config};
+
+use PeripheryCapability::*;
+
+/// What a request needs to be allowed.
+pub struct Requirements {
+  /// Whether the request changes the host,
+  /// rejected in `read_only` mode.
+  pub write: bool,
+  /// Rejected if any of these are in `disabled_capabilities`.
+  pub capabilities: &'static [PeripheryCapability],
+}
+
+impl Requirements {
+  const fn read(
+    capabilities: &'static [PeripheryCapability],
+  ) -> Self {
+    Self {
+      write: false,
+      capabilities,
+    }
+  }
+
+  const fn write(
+    capabilities: &'static [PeripheryCapability],
+  ) -> Self {
+    Self {
+      write: true,
+      capabilities,
+    }
+  }
+}
+
+/// Rejects the request if it is not allowed by the
+/// `read_only` / `disabled_capabilities` config.
+pub fn check(
+  name: &str,
+  Requirements {
+    write,
+    capabilities,
+  }: Requirements,
+) -> anyhow::Result<()> {
+  let config = periphery_config();
+  if write && config.read_only {
+    return Err(anyhow!(
+      "{name} is not allowed, Periphery is in read only mode"
+    ));
+  }
+  if let Some(disabled) = capabilities.iter().find(|capability| {
+    config.disabled_capabilities.contains(capability)
+  }) {
+    return Err(anyhow!(
+      "{name} is not allowed, the {disabled:?} capability is disabled on this Periphery"
+    ));
+  }
+  Ok(())
+}
+
+/// When `allowed_commands` is configured, the command must match
+/// one of the patterns, and can't chain other commands with shell operators.
+pub fn check_command(command: &SystemCommand) -> anyhow::Result<()> {
+  check_allowed_command(&periphery_config().allowed_commands, command)
+}
+
+fn check_allowed_command(
+  allowed: &[String],
+  SystemCommand { path, command }: &SystemCommand,
+) -> anyhow::Result<()> {
+  if allowed.is_empty() {
+    return Ok(());
+  }
+  if let Some(operator) = [path, command].into_iter().find_map(|s| {
+    SHELL_OPERATORS
+      .iter()
+      .find(|operator| s.contains(*operator))
+  }) {
+    return Err(anyhow!(
+      "Command contains {operator:?}, which is not allowed when allowed_commands is configured"
+    ));
+  }
+  let command = command.trim();
+  for pattern in allowed {
+    let wildcard = wildcard::Wildcard::new(pattern.as_bytes())
+      .with_context(|| {
+        format!("Invalid allowed_commands pattern: {pattern}")
+      })?;
+    if wildcard.is_match(command.as_bytes()) {
+      return Ok(());
+    }
+  }
+  Err(anyhow!("Command is not in allowed_commands: {command}"))
+}
+
+const SHELL_OPERATORS: &[&str] =
+  &[";", "&", "|", "`", "$(", ">", "<", "\n", "\r"];
+
+impl PeripheryRequest {
+  pub fn requirements(&self) -> Requirements {
+    match self {
+      PeripheryRequest::GetVersion(_)
+      | PeripheryRequest::GetHealth(_)
+      | PeripheryRequest::ListGitProviders(_)
+      | PeripheryRequest::ListDockerRegistries(_)
+      | PeripheryRequest::ListDockerEndpoints(_)
+      | PeripheryRequest::ListSecrets(_)
+      | PeripheryRequest::GetSystemInformation(_)
+      | PeripheryRequest::GetSystemStats(_)
+      | PeripheryRequest::GetSystemProcesses(_)
+      | PeripheryRequest::GetComposeLog(_)
+      | PeripheryRequest::GetComposeLogSearch(_)
+      | PeripheryRequest::InspectContainer(_)
+      | PeripheryRequest::GetContainerLog(_)
+      | PeripheryRequest::GetContainerLogSearch(_)
+      | PeripheryRequest::GetContainerStats(_)
+      | PeripheryRequest::GetContainerStatsList(_)
+      | PeripheryRequest::GetContainerStatsRecords(_)
+      | PeripheryRequest::InspectNetwork(_)
+      | PeripheryRequest::InspectImage(_)
+      | PeripheryRequest::ImageHistory(_)
+      | PeripheryRequest::GetPullProgress(_)
+      | PeripheryRequest::InspectVolume(_)
+      | PeripheryRequest::GetDockerLists(_)
+      | PeripheryRequest::GetDockerDiskUsage(_) => {
+        Requirements::read(&[])
+      }
+
+      // Core needs to be able to rotate credentials
+      // even on read only Periphery.
+      PeripheryRequest::RotatePasskey(_)
+      | PeripheryRequest::RotateSslCert(_) => Requirements::read(&[]),
+
+      PeripheryRequest::GetLatestCommit(_) => {
+        Requirements::read(&[Git])
+      }
+      PeripheryRequest::GetComposeContentsOnHost(_) => {
+        Requirements::read(&[Compose])
+      }
+      PeripheryRequest::ListFiles(_)
+      | PeripheryRequest::ReadFile(_) => Requirements::read(&[Files]),
+      PeripheryRequest::ListVolumeBackups(_) => {
+        Requirements::read(&[Container])
+      }
+
+      PeripheryRequest::RunCommand(_) => {
+        Requirements::write(&[RunCommand])
+      }
+
+      PeripheryRequest::CloneRepo(_)
+      | PeripheryRequest::PullRepo(_)
+        if self.runs_commands() =>
+      {
+        Requirements::write(&[Git, RunCommand])
+      }
+      PeripheryRequest::CloneRepo(_)
+      | PeripheryRequest::PullRepo(_)
+      | PeripheryRequest::RenameRepo(_) => {
+        Requirements::write(&[Git])
+      }
+      PeripheryRequest::DeleteRepo(_) => {
+        Requirements::write(&[Git, Destructive])
+      }
+
+      PeripheryRequest::Build(_) => Requirements::write(&[Build]),
+      PeripheryRequest::PruneBuilders(_)
+      | PeripheryRequest::PruneBuildx(_) => {
+        Requirements::write(&[Build, Destructive])
+      }
+
+      PeripheryRequest::ComposeUp(_) if self.runs_commands() => {
+        Requirements::write(&[Compose, RunCommand])
+      }
+      PeripheryRequest::WriteComposeContentsToHost(_)
+      | PeripheryRequest::ComposePull(_)
+      | PeripheryRequest::ComposeUp(_) => {
+        Requirements::write(&[Compose])
+      }
+      PeripheryRequest::WriteCommitComposeContents(_) => {
+        Requirements::write(&[Compose, Git])
+      }
+      PeripheryRequest::ComposeExecution(execution) => {
+        let command = execution.command.trim_start();
+        if command.starts_with("down") || command.starts_with("rm") {
+          Requirements::write(&[Compose, Destructive])
+        } else {
+          Requirements::write(&[Compose])
+        }
+      }
+
+      PeripheryRequest::WriteFile(_) => Requirements::write(&[Files]),
+      PeripheryRequest::DeleteFile(_) => {
+        Requirements::write(&[Files, Destructive])
+      }
+
+      PeripheryRequest::Deploy(_)
+      | PeripheryRequest::StartContainer(_)
+      | PeripheryRequest::RestartContainer(_)
+      | PeripheryRequest::PauseContainer(_)
+      | PeripheryRequest::UnpauseContainer(_)
+      | PeripheryRequest::StopContainer(_)
+      | PeripheryRequest::StartAllContainers(_)
+      | PeripheryRequest::RestartAllContainers(_)
+      | PeripheryRequest::PauseAllContainers(_)
+      | PeripheryRequest::UnpauseAllContainers(_)
+      | PeripheryRequest::StopAllContainers(_)
+      | PeripheryRequest::RenameContainer(_)
+      | PeripheryRequest::CreateNetwork(_)
+      | PeripheryRequest::PullImage(_)
+      | PeripheryRequest::BackupVolume(_) => {
+        Requirements::write(&[Container])
+      }
+      PeripheryRequest::RemoveContainer(_)
+      | PeripheryRequest::PruneContainers(_)
+      | PeripheryRequest::DeleteNetwork(_)
+      | PeripheryRequest::PruneNetworks(_)
+      | PeripheryRequest::DeleteImage(_)
+      | PeripheryRequest::PruneImages(_)
+      | PeripheryRequest::DeleteVolume(_)
+      | PeripheryRequest::PruneVolumes(_)
+      | PeripheryRequest::RestoreVolume(_)
+      | PeripheryRequest::PruneSystem(_) => {
+        Requirements::write(&[Container, Destructive])
+      }
+    }
+  }
+
+  /// The commands configured on the resource which the request
+  /// runs on the host, eg. the stack `pre_deploy`.
+  /// The build `pre_build` is sent as the `on_clone` of CloneRepo.
+  fn commands(&self) -> Vec<&SystemCommand> {
+    let commands: Vec<&SystemCommand> = match self {
+      PeripheryRequest::CloneRepo(req) => {
+        [&req.args.on_clone, &req.args.on_pull]
+          .into_iter()
+          .flatten()
+          .collect()
+      }
+      PeripheryRequest::PullRepo(req) => {
+        [&req.args.on_clone, &req.args.on_pull]
+          .into_iter()
+          .flatten()
+          .collect()
+      }
+      PeripheryRequest::ComposeUp(req) => vec![
+        &req.stack.config.pre_deploy,
+        &req.stack.config.post_deploy,
+      ],
+      _ => Vec::new(),
+    };
+    commands
+      .into_iter()
+      .filter(|command| !command.is_none())
+      .collect()
+  }
+
+  fn runs_commands(&self) -> bool {
+    !self.commands().is_empty()
+  }
+
+  /// Applies `allowed_commands` to the commands the request runs,
+  /// the same as for `RunCommand`.
+  pub fn check_commands(&self) -> anyhow::Result<()> {
+    self.commands().into_iter().try_for_each(check_command)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn check(allowed: &[&str], command: &str) -> anyhow::Result<()> {
+    check_path(allowed, "", command)
+  }
+
+  fn check_path(
+    allowed: &[&str],
+    path: &str,
+    command: &str,
+  ) -> anyhow::Result<()> {
+    let allowed =
+      allowed.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    check_allowed_command(
+      &allowed,
+      &SystemCommand {
+        path: path.to_string(),
+        command: command.to_string(),
+      },
+    )
+  }
+
+  #[test]
+  fn any_command_without_allowed_commands() {
+    assert!(check(&[], "rm -rf / && echo done").is_ok());
+  }
+
+  #[test]
+  fn shell_operators_rejected() {
+    let allowed = &["*"];
+    for command in [
+      "docker ps; rm -rf /",
+      "docker ps && rm -rf /",
+      "docker ps & rm -rf /",
+      "docker ps | sh",
+      "docker ps || sh",
+      "echo `rm -rf /`",
+      "echo $(rm -rf /)",
+      "docker ps > /etc/passwd",
+      "sh < script.sh",
+      "docker ps\nrm -rf /",
+      "docker ps\rrm -rf /",
+    ] {
+      assert!(check(allowed, command).is_err(), "{command:?}");
+    }
+    // The path is run in the same shell
+    assert!(check_path(allowed, "/tmp; rm -rf /", "ls").is_err());
+    // Variables aren't operators
+    assert!(check(allowed, "echo $HOME").is_ok());
+  }
+
+  #[test]
+  fn wildcard_patterns() {
+    let allowed =
+      &["docker ps", "docker compose * up -d", "git ?ull"];
+    assert!(check(allowed, "docker ps").is_ok());
+    assert!(check(allowed, "  docker ps  ").is_ok());
+    // Patterns match the whole command
+    assert!(check(allowed, "docker ps -a").is_err());
+    assert!(check(allowed, "sudo docker ps").is_err());
+    assert!(check(allowed, "docker compose -p app up -d").is_ok());
+    assert!(check(allowed, "docker compose -p app down").is_err());
+    // `*` can match nothing, `?` exactly one character
+    assert!(check(allowed, "docker compose  up -d").is_ok());
+    assert!(check(allowed, "git pull").is_ok());
+    assert!(check(allowed, "git ull").is_err());
+    assert!(check(allowed, "git ppull").is_err());
+    assert!(check(allowed, "").is_err());
+  }
+}
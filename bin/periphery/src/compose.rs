@@ -1,6 +1,11 @@
-use std::{fmt::Write, path::PathBuf};
+use std::{
+  collections::{BTreeSet, HashMap},
+  fmt::Write,
+  path::{Path, PathBuf},
+};
 
 use anyhow::{Context, anyhow};
+use bollard::auth::DockerCredentials;
 use command::{
   run_komodo_command, run_komodo_command_multiline,
   run_komodo_command_with_interpolation,
@@ -9,6 +14,7 @@ use formatting::format_serror;
 use git::environment;
 use komodo_client::entities::{
   CloneArgs, FileContents, all_logs_success,
+  config::periphery::ContainerEngine,
   environment_vars_from_str,
   stack::{
     ComposeFile, ComposeService, ComposeServiceDeploy, Stack,
@@ -22,21 +28,156 @@ use periphery_client::api::{
   git::{CloneRepo, PullOrCloneRepo, RepoActionResponse},
 };
 use resolver_api::Resolve;
+use serde::Deserialize;
 use tokio::fs;
 
 use crate::{
-  config::periphery_config, docker::docker_login,
+  config::periphery_config,
+  docker::{
+    container_engine_cli, current_docker_endpoint, docker_cli,
+    docker_host_args, docker_login, registry_credentials,
+  },
   helpers::parse_extra_args,
+  pull_progress::{run_engine_pull, run_pull_command},
 };
 
-pub fn docker_compose() -> &'static str {
-  if periphery_config().legacy_compose_cli {
-    "docker-compose"
-  } else {
-    "docker compose"
+/// The compose cli, targeting the docker endpoint of the request.
+pub fn docker_compose() -> String {
+  let config = periphery_config();
+  let host = current_docker_endpoint().map(|endpoint| &endpoint.host);
+  match (config.container_engine, config.legacy_compose_cli, host) {
+    (_, false, _) => format!("{} compose", docker_cli()),
+    (ContainerEngine::Docker, true, None) => {
+      String::from("docker-compose")
+    }
+    (ContainerEngine::Docker, true, Some(host)) => {
+      format!("DOCKER_HOST={host} docker-compose")
+    }
+    (ContainerEngine::Podman, true, None) => {
+      String::from("podman-compose")
+    }
+    (ContainerEngine::Podman, true, Some(host)) => {
+      format!("podman-compose --podman-args '--url {host}'")
+    }
   }
 }
 
+/// The compose cli, targeting the docker endpoint of the request,
+/// to run without a shell.
+pub fn docker_compose_command() -> tokio::process::Command {
+  let config = periphery_config();
+  let host = current_docker_endpoint().map(|endpoint| &endpoint.host);
+  match (config.container_engine, config.legacy_compose_cli) {
+    (_, false) => {
+      let mut command =
+        tokio::process::Command::new(container_engine_cli());
+      command.args(docker_host_args()).arg("compose");
+      command
+    }
+    (ContainerEngine::Docker, true) => {
+      let mut command =
+        tokio::process::Command::new("docker-compose");
+      if let Some(host) = host {
+        command.env("DOCKER_HOST", host);
+      }
+      command
+    }
+    (ContainerEngine::Podman, true) => {
+      let mut command =
+        tokio::process::Command::new("podman-compose");
+      if let Some(host) = host {
+        command.args(["--podman-args", &format!("--url {host}")]);
+      }
+      command
+    }
+  }
+}
+
+/// Pulls the images of the services, or all services if empty.
+/// `compose_args` are the project, file and env file args.
+///
+/// Docker compose reports the byte progress of each layer
+/// without a TTY. Podman compose pulls with `podman pull`,
+/// which does not, so the images are pulled through
+/// the engine api instead. The engine api doesn't read the
+/// cli credentials, so without configured credentials
+/// Podman compose pulls the images as well.
+pub async fn compose_pull(
+  run_directory: &Path,
+  compose_args: &str,
+  services: &[String],
+  credentials: Option<DockerCredentials>,
+  progress_id: Option<&str>,
+) -> Log {
+  let docker_compose = docker_compose();
+  if periphery_config().container_engine == ContainerEngine::Docker
+    || credentials.is_none()
+  {
+    let service_args = if services.is_empty() {
+      String::new()
+    } else {
+      format!(" {}", services.join(" "))
+    };
+    return run_pull_command(
+      "Compose Pull",
+      run_directory,
+      format!("{docker_compose} {compose_args} pull{service_args}"),
+      progress_id,
+    )
+    .await;
+  }
+  let config_log = run_komodo_command(
+    "Compose Config",
+    run_directory,
+    format!("{docker_compose} {compose_args} config"),
+  )
+  .await;
+  if !config_log.success {
+    return config_log;
+  }
+  let config =
+    match serde_yaml::from_str::<PullConfig>(&config_log.stdout) {
+      Ok(config) => config,
+      Err(e) => {
+        return Log::error(
+          "Compose Pull",
+          format_serror(
+            &anyhow::Error::from(e)
+              .context("Failed to parse compose contents")
+              .into(),
+          ),
+        );
+      }
+    };
+  // Like compose pull, services which are built are skipped.
+  let images = config
+    .services
+    .into_iter()
+    .filter(|(name, service)| {
+      (services.is_empty() || services.contains(name))
+        && service.build.is_none()
+    })
+    .filter_map(|(_, service)| service.image)
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect::<Vec<_>>();
+  run_engine_pull("Compose Pull", &images, credentials, progress_id)
+    .await
+}
+
+/// The parts of the compose config needed to pull the images.
+#[derive(Deserialize)]
+struct PullConfig {
+  #[serde(default)]
+  services: HashMap<String, PullService>,
+}
+
+#[derive(Deserialize)]
+struct PullService {
+  image: Option<String>,
+  build: Option<serde_yaml::Value>,
+}
+
 /// If this fn returns Err, the caller of `compose_up` has to write result to the log before return.
 pub async fn compose_up(
   stack: Stack,
@@ -45,6 +186,7 @@ pub async fn compose_up(
   registry_token: Option<String>,
   res: &mut ComposeUpResponse,
   core_replacers: Vec<(String, String)>,
+  progress_id: Option<&str>,
 ) -> anyhow::Result<()> {
   // Write the stack to local disk. For repos, will first delete any existing folder to ensure fresh deploy.
   // Will also set additional fields on the reponse.
@@ -158,6 +300,11 @@ pub async fn compose_up(
     })
     .context("failed to login to image registry")?;
   }
+  let credentials = registry_credentials(
+    &stack.config.registry_provider,
+    &stack.config.registry_account,
+    registry_token.as_deref(),
+  )?;
 
   let env_file = env_file_path
     .map(|path| format!(" --env-file {path}"))
@@ -273,12 +420,14 @@ pub async fn compose_up(
   if stack.config.auto_pull {
     // Pull images before destroying to minimize downtime.
     // If this fails, do not continue.
-    let log = run_komodo_command(
-      "Compose Pull",
-      run_directory.as_ref(),
-      format!(
-        "{docker_compose} -p {project_name} -f {file_args}{env_file}{additional_env_files} pull{service_args}",
+    let log = compose_pull(
+      &run_directory,
+      &format!(
+        "-p {project_name} -f {file_args}{env_file}{additional_env_files}"
       ),
+      &services,
+      credentials,
+      progress_id,
     )
     .await;
 
@@ -1,9 +1,13 @@
 use std::sync::OnceLock;
 
 use clap::Parser;
-use environment_file::maybe_read_list_from_file;
+use environment_file::{
+  maybe_read_item_from_file, maybe_read_list_from_file,
+};
 use komodo_client::entities::{
-  config::periphery::{CliArgs, Env, PeripheryConfig},
+  config::periphery::{
+    BackupS3Config, CliArgs, Env, PeripheryConfig,
+  },
   logger::{LogConfig, LogLevel},
 };
 use merge_config_files::parse_config_paths;
@@ -37,12 +41,55 @@ pub fn periphery_config() -> &'static PeripheryConfig {
       port: env.periphery_port.unwrap_or(config.port),
       repo_dir: env.periphery_repo_dir.unwrap_or(config.repo_dir),
       stack_dir: env.periphery_stack_dir.unwrap_or(config.stack_dir),
+      backup_dir: env
+        .periphery_backup_dir
+        .unwrap_or(config.backup_dir),
+      backup_image: env
+        .periphery_backup_image
+        .unwrap_or(config.backup_image),
+      backup_s3: BackupS3Config {
+        endpoint: env
+          .periphery_backup_s3_endpoint
+          .unwrap_or(config.backup_s3.endpoint),
+        region: env
+          .periphery_backup_s3_region
+          .unwrap_or(config.backup_s3.region),
+        bucket: env
+          .periphery_backup_s3_bucket
+          .unwrap_or(config.backup_s3.bucket),
+        prefix: env
+          .periphery_backup_s3_prefix
+          .unwrap_or(config.backup_s3.prefix),
+        access_key_id: maybe_read_item_from_file(
+          env.periphery_backup_s3_access_key_id_file,
+          env.periphery_backup_s3_access_key_id,
+        )
+        .unwrap_or(config.backup_s3.access_key_id),
+        secret_access_key: maybe_read_item_from_file(
+          env.periphery_backup_s3_secret_access_key_file,
+          env.periphery_backup_s3_secret_access_key,
+        )
+        .unwrap_or(config.backup_s3.secret_access_key),
+      },
+      file_roots: env
+        .periphery_file_roots
+        .unwrap_or(config.file_roots),
+      max_upload_size_mb: env
+        .periphery_max_upload_size_mb
+        .unwrap_or(config.max_upload_size_mb),
       stats_polling_rate: env
         .periphery_stats_polling_rate
         .unwrap_or(config.stats_polling_rate),
       legacy_compose_cli: env
         .periphery_legacy_compose_cli
         .unwrap_or(config.legacy_compose_cli),
+      container_engine: env
+        .periphery_container_engine
+        .unwrap_or(config.container_engine),
+      container_socket: env
+        .periphery_container_socket
+        .or(config.container_socket),
+      docker_endpoints: config.docker_endpoints,
       logging: LogConfig {
         level: args
           .log_level
@@ -67,12 +114,36 @@ pub fn periphery_config() -> &'static PeripheryConfig {
         env.periphery_passkeys,
       )
       .unwrap_or(config.passkeys),
+      allow_unsigned_requests: env
+        .periphery_allow_unsigned_requests
+        .unwrap_or(config.allow_unsigned_requests),
+      rotated_passkey_file: env
+        .periphery_rotated_passkey_file
+        .unwrap_or(config.rotated_passkey_file),
+      read_only: env.periphery_read_only.unwrap_or(config.read_only),
+      disabled_capabilities: env
+        .periphery_disabled_capabilities
+        .unwrap_or(config.disabled_capabilities),
+      allowed_commands: env
+        .periphery_allowed_commands
+        .unwrap_or(config.allowed_commands),
       include_disk_mounts: env
         .periphery_include_disk_mounts
         .unwrap_or(config.include_disk_mounts),
       exclude_disk_mounts: env
         .periphery_exclude_disk_mounts
         .unwrap_or(config.exclude_disk_mounts),
+      core_address: env
+        .periphery_core_address
+        .unwrap_or(config.core_address),
+      core_server: env
+        .periphery_core_server
+        .unwrap_or(config.core_server),
+      core_passkey: maybe_read_item_from_file(
+        env.periphery_core_passkey_file,
+        env.periphery_core_passkey,
+      )
+      .unwrap_or(config.core_passkey),
       ssl_enabled: env
         .periphery_ssl_enabled
         .unwrap_or(config.ssl_enabled),
@@ -82,6 +153,12 @@ pub fn periphery_config() -> &'static PeripheryConfig {
       ssl_cert_file: env
         .periphery_ssl_cert_file
         .unwrap_or(config.ssl_cert_file),
+      ssl_client_ca_file: env
+        .periphery_ssl_client_ca_file
+        .or(config.ssl_client_ca_file),
+      ssl_client_fingerprints: env
+        .periphery_ssl_client_fingerprints
+        .unwrap_or(config.ssl_client_fingerprints),
       secrets: config.secrets,
       git_providers: config.git_providers,
       docker_registries: config.docker_registries,
@@ -0,0 +1,100 @@
+use anyhow::Context;
+use axum::{
+  body::Body,
+  extract::Query,
+  http::{StatusCode, header::CONTENT_TYPE},
+  response::Response,
+};
+use futures::{StreamExt, stream};
+use komodo_client::entities::config::periphery::PeripheryCapability;
+use periphery_client::api::{
+  DockerEndpointQuery, container::ContainerCopyQuery,
+};
+use serror::AddStatusCode;
+
+use crate::{
+  capabilities::{self, Requirements},
+  config::periphery_config,
+  docker::{endpoint_docker_client, get_docker_endpoint},
+};
+
+pub async fn download_from_container(
+  Query(ContainerCopyQuery { container, path }): Query<
+    ContainerCopyQuery,
+  >,
+  Query(DockerEndpointQuery { docker_endpoint }): Query<
+    DockerEndpointQuery,
+  >,
+) -> serror::Result<Response> {
+  capabilities::check(
+    "DownloadFromContainer",
+    Requirements {
+      write: false,
+      capabilities: &[PeripheryCapability::Container],
+    },
+  )
+  .status_code(StatusCode::FORBIDDEN)?;
+  let docker_endpoint =
+    get_docker_endpoint(docker_endpoint.as_deref())
+      .status_code(StatusCode::BAD_REQUEST)?;
+  let docker = endpoint_docker_client(docker_endpoint)
+    .status_code(StatusCode::BAD_REQUEST)?;
+  let mut archive =
+    Box::pin(docker.download_from_container(&container, &path));
+  // Surface missing container / path as an error response,
+  // rather than failing the stream after the response is sent.
+  let first = archive
+    .next()
+    .await
+    .transpose()
+    .with_context(|| {
+      format!("Failed to download {path} from container {container}")
+    })
+    .status_code(StatusCode::BAD_REQUEST)?;
+  let body =
+    Body::from_stream(stream::iter(first.map(Ok)).chain(archive));
+  let res = Response::builder()
+    .header(CONTENT_TYPE, "application/x-tar")
+    .body(body)
+    .context("failed to build response")?;
+  Ok(res)
+}
+
+pub async fn upload_to_container(
+  Query(ContainerCopyQuery { container, path }): Query<
+    ContainerCopyQuery,
+  >,
+  Query(DockerEndpointQuery { docker_endpoint }): Query<
+    DockerEndpointQuery,
+  >,
+  body: Body,
+) -> serror::Result<()> {
+  capabilities::check(
+    "UploadToContainer",
+    Requirements {
+      write: true,
+      capabilities: &[PeripheryCapability::Container],
+    },
+  )
+  .status_code(StatusCode::FORBIDDEN)?;
+  let docker_endpoint =
+    get_docker_endpoint(docker_endpoint.as_deref())
+      .status_code(StatusCode::BAD_REQUEST)?;
+  // Read the Body directly, as archives are usually
+  // larger than the default limit on the Bytes extractor.
+  let tar = axum::body::to_bytes(body, max_upload_size())
+    .await
+    .context("failed to read tar archive")
+    .status_code(StatusCode::PAYLOAD_TOO_LARGE)?;
+  endpoint_docker_client(docker_endpoint)
+    .status_code(StatusCode::BAD_REQUEST)?
+    .upload_to_container(&container, &path, tar)
+    .await
+    .status_code(StatusCode::BAD_REQUEST)?;
+  Ok(())
+}
+
+/// The limit on the archives uploaded with [upload_to_container].
+pub fn max_upload_size() -> usize {
+  periphery_config().max_upload_size_mb as usize * 1024 * 1024
+}
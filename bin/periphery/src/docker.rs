@@ -1,26 +1,190 @@
-use std::{collections::HashMap, sync::OnceLock};
+use std::{
+  collections::{HashMap, HashSet},
+  path::PathBuf,
+  sync::OnceLock,
+};
 
 use anyhow::{Context, anyhow};
+use axum::body::Bytes;
 use bollard::{
   Docker,
-  container::{InspectContainerOptions, ListContainersOptions},
+  auth::DockerCredentials,
+  container::{
+    BlkioStats, CPUStats, Config, CreateContainerOptions,
+    DownloadFromContainerOptions, InspectContainerOptions,
+    ListContainersOptions, MemoryStatsStats, StartContainerOptions,
+    Stats, StatsOptions, UploadToContainerOptions,
+  },
+  image::CreateImageOptions,
   network::InspectNetworkOptions,
+  secret::{CreateImageInfo, EventMessageTypeEnum},
+  system::EventsOptions,
 };
-use command::run_komodo_command;
+use futures::{Stream, StreamExt, future::join_all};
 use komodo_client::entities::{
   TerminationSignal,
+  config::periphery::{ContainerEngine, DockerEndpoint},
   docker::{
     ContainerConfig, GraphDriverData, HealthConfig, PortBinding,
-    container::*, image::*, network::*, volume::*,
+    container::*, disk_usage::*, image::*, network::*, volume::*,
   },
+  stats::ContainerStatsRecord,
   to_komodo_name,
   update::Log,
 };
+use periphery_client::api::events::{DockerEvent, DockerEventType};
 use run_command::async_run_command;
 
-pub fn docker_client() -> &'static DockerClient {
+use crate::{
+  config::periphery_config,
+  pull_progress::{run_engine_pull, run_pull_command},
+};
+
+tokio::task_local! {
+  /// The docker endpoint targeted by the request being handled.
+  /// None for the default endpoint.
+  static DOCKER_ENDPOINT: Option<&'static DockerEndpoint>;
+}
+
+/// Finds the docker endpoint with the name,
+/// see `docker_endpoints` in the periphery config.
+/// Empty / None is the default endpoint.
+pub fn get_docker_endpoint(
+  name: Option<&str>,
+) -> anyhow::Result<Option<&'static DockerEndpoint>> {
+  let Some(name) = name.filter(|name| !name.is_empty()) else {
+    return Ok(None);
+  };
+  periphery_config()
+    .docker_endpoints
+    .iter()
+    .find(|endpoint| endpoint.name == name)
+    .map(Some)
+    .with_context(|| {
+      format!("No docker endpoint named '{name}' is configured")
+    })
+}
+
+/// Runs the future against the docker endpoint.
+pub async fn with_docker_endpoint<F: Future>(
+  endpoint: Option<&'static DockerEndpoint>,
+  fut: F,
+) -> F::Output {
+  DOCKER_ENDPOINT.scope(endpoint, fut).await
+}
+
+/// The docker endpoint targeted by the request being handled,
+/// or None for the default endpoint.
+pub fn current_docker_endpoint() -> Option<&'static DockerEndpoint> {
+  DOCKER_ENDPOINT
+    .try_with(|endpoint| *endpoint)
+    .ok()
+    .flatten()
+}
+
+/// The container engine cli, `docker` or `podman`,
+/// targeting the docker endpoint of the request.
+pub fn docker_cli() -> String {
+  std::iter::once(container_engine_cli())
+    .chain(docker_host_args())
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// The container engine cli, without targeting the docker endpoint.
+pub fn container_engine_cli() -> &'static str {
+  match periphery_config().container_engine {
+    ContainerEngine::Docker => "docker",
+    ContainerEngine::Podman => "podman",
+  }
+}
+
+/// The cli flags which target the docker endpoint of the request.
+/// Empty for the default endpoint.
+pub fn docker_host_args() -> Vec<&'static str> {
+  let Some(endpoint) = current_docker_endpoint() else {
+    return Vec::new();
+  };
+  match periphery_config().container_engine {
+    ContainerEngine::Docker => vec!["-H", &endpoint.host],
+    ContainerEngine::Podman => vec!["--url", &endpoint.host],
+  }
+}
+
+/// The client for the docker endpoint of the request.
+pub fn docker_client() -> anyhow::Result<&'static DockerClient> {
+  endpoint_docker_client(current_docker_endpoint())
+}
+
+/// The client for the docker endpoint,
+/// or the default endpoint for None.
+pub fn endpoint_docker_client(
+  endpoint: Option<&'static DockerEndpoint>,
+) -> anyhow::Result<&'static DockerClient> {
   static DOCKER_CLIENT: OnceLock<DockerClient> = OnceLock::new();
-  DOCKER_CLIENT.get_or_init(Default::default)
+  let Some(endpoint) = endpoint else {
+    return Ok(DOCKER_CLIENT.get_or_init(Default::default));
+  };
+  endpoint_clients()
+    .get(&endpoint.name)
+    .with_context(|| {
+      format!(
+        "No docker endpoint named '{}' is configured",
+        endpoint.name
+      )
+    })?
+    .as_ref()
+    .map_err(|e| {
+      anyhow!("Docker endpoint '{}' is invalid | {e}", endpoint.name)
+    })
+}
+
+/// The clients for the `docker_endpoints` in the periphery config,
+/// or the reason the endpoint is invalid.
+fn endpoint_clients()
+-> &'static HashMap<String, Result<DockerClient, String>> {
+  static ENDPOINT_CLIENTS: OnceLock<
+    HashMap<String, Result<DockerClient, String>>,
+  > = OnceLock::new();
+  ENDPOINT_CLIENTS.get_or_init(|| {
+    let mut clients = HashMap::new();
+    for endpoint in &periphery_config().docker_endpoints {
+      if clients.contains_key(&endpoint.name) {
+        error!(
+          "Docker endpoint '{}' is configured more than once, only the first is used",
+          endpoint.name
+        );
+        continue;
+      }
+      let client = DockerClient::connect(endpoint)
+        .map_err(|e| format!("{e:#}"));
+      clients.insert(endpoint.name.clone(), client);
+    }
+    clients
+  })
+}
+
+/// The `docker_endpoints` in the periphery config,
+/// skipping any repeated names.
+pub fn docker_endpoints() -> Vec<&'static DockerEndpoint> {
+  let mut names = HashSet::new();
+  periphery_config()
+    .docker_endpoints
+    .iter()
+    .filter(|endpoint| names.insert(endpoint.name.as_str()))
+    .collect()
+}
+
+/// Validates the `docker_endpoints` in the periphery config,
+/// logging any which are invalid. Requests to an invalid
+/// endpoint fail, while the other endpoints keep working.
+pub fn init_docker_endpoints() {
+  for (name, client) in endpoint_clients() {
+    match client {
+      Ok(_) => info!("Docker endpoint '{name}' configured"),
+      Err(e) => error!("Docker endpoint '{name}' is invalid | {e}"),
+    }
+  }
 }
 
 pub struct DockerClient {
@@ -29,14 +193,220 @@ pub struct DockerClient {
 
 impl Default for DockerClient {
   fn default() -> DockerClient {
+    let config = periphery_config();
+    let socket =
+      match (&config.container_socket, config.container_engine) {
+        (Some(socket), _) => Some(socket.clone()),
+        (None, ContainerEngine::Podman) => {
+          Some(PathBuf::from(PODMAN_SOCKET))
+        }
+        (None, ContainerEngine::Docker) => None,
+      };
+    let docker = match socket {
+      Some(socket) => Docker::connect_with_socket(
+        &socket.to_string_lossy(),
+        120,
+        bollard::API_DEFAULT_VERSION,
+      ),
+      None => Docker::connect_with_defaults(),
+    };
     DockerClient {
-      docker: Docker::connect_with_defaults()
-        .expect("failed to connect to docker daemon"),
+      docker: docker.expect("failed to connect to container engine"),
     }
   }
 }
 
+/// The rootful Podman socket.
+const PODMAN_SOCKET: &str = "/run/podman/podman.sock";
+
 impl DockerClient {
+  /// Connects to a unix socket (`unix://` or a path)
+  /// or tcp (`tcp://` / `http://`) docker endpoint.
+  fn connect(
+    DockerEndpoint { name, host }: &DockerEndpoint,
+  ) -> anyhow::Result<Self> {
+    if name.is_empty() {
+      return Err(anyhow!("Docker endpoint name cannot be empty"));
+    }
+    let docker = if host.starts_with("unix://") || host.starts_with('/')
+    {
+      Docker::connect_with_socket(
+        host,
+        120,
+        bollard::API_DEFAULT_VERSION,
+      )
+    } else if host.starts_with("tcp://") || host.starts_with("http://")
+    {
+      Docker::connect_with_http(host, 120, bollard::API_DEFAULT_VERSION)
+    } else {
+      return Err(anyhow!(
+        "Docker endpoint host must start with 'unix://', '/', 'tcp://' or 'http://', got '{host}'"
+      ));
+    }
+    .with_context(|| {
+      format!("Failed to connect to docker endpoint at {host}")
+    })?;
+    Ok(DockerClient { docker })
+  }
+
+  /// Creates and starts the container through the Engine API.
+  /// Returns any warnings docker gives when creating the container.
+  pub async fn create_and_start_container(
+    &self,
+    name: &str,
+    config: Config<String>,
+  ) -> anyhow::Result<Vec<String>> {
+    let created = self
+      .docker
+      .create_container(
+        Some(CreateContainerOptions {
+          name,
+          platform: None,
+        }),
+        config,
+      )
+      .await
+      .map_err(engine_error)
+      .with_context(|| {
+        format!("Failed to create container {name}")
+      })?;
+    self
+      .docker
+      .start_container(name, None::<StartContainerOptions<String>>)
+      .await
+      .map_err(engine_error)
+      .with_context(|| format!("Failed to start container {name}"))?;
+    Ok(created.warnings)
+  }
+
+  /// Streams a tar archive of the path in the container.
+  pub fn download_from_container(
+    &self,
+    container: &str,
+    path: &str,
+  ) -> impl Stream<Item = anyhow::Result<Bytes>> + use<> {
+    self
+      .docker
+      .download_from_container(
+        container,
+        Some(DownloadFromContainerOptions {
+          path: path.to_string(),
+        }),
+      )
+      .map(|chunk| chunk.map_err(engine_error))
+  }
+
+  /// Pulls the image, streaming the progress messages.
+  /// Pulls the `latest` tag if the image has no tag,
+  /// rather than every tag.
+  pub fn pull_image(
+    &self,
+    image: &str,
+    credentials: Option<DockerCredentials>,
+  ) -> impl Stream<Item = anyhow::Result<CreateImageInfo>> + use<> {
+    let tag = if image_has_tag(image) {
+      String::new()
+    } else {
+      String::from("latest")
+    };
+    self
+      .docker
+      .create_image(
+        Some(CreateImageOptions {
+          from_image: image.to_string(),
+          tag,
+          ..Default::default()
+        }),
+        None,
+        credentials,
+      )
+      .map(|info| {
+        let info = info.map_err(engine_error)?;
+        if let Some(error) = &info.error {
+          return Err(anyhow!("{error}"));
+        }
+        Ok(info)
+      })
+  }
+
+  /// Extracts the tar archive into the path in the container.
+  pub async fn upload_to_container(
+    &self,
+    container: &str,
+    path: &str,
+    tar: Bytes,
+  ) -> anyhow::Result<()> {
+    self
+      .docker
+      .upload_to_container(
+        container,
+        Some(UploadToContainerOptions {
+          path,
+          ..Default::default()
+        }),
+        tar,
+      )
+      .await
+      .map_err(engine_error)
+      .with_context(|| {
+        format!("Failed to upload to {path} in container {container}")
+      })
+  }
+
+  /// Streams container / image / network / volume events
+  /// from now on, until the stream is dropped.
+  pub fn events(
+    &self,
+  ) -> impl Stream<Item = anyhow::Result<DockerEvent>> {
+    let filters = HashMap::from([(
+      "type",
+      vec!["container", "image", "network", "volume"],
+    )]);
+    self
+      .docker
+      .events(Some(EventsOptions {
+        filters,
+        ..Default::default()
+      }))
+      .filter_map(|event| async move {
+        let event = match event {
+          Ok(event) => event,
+          Err(e) => {
+            return Some(Err(
+              anyhow::Error::from(e).context("docker events error"),
+            ));
+          }
+        };
+        let ty = match event.typ? {
+          EventMessageTypeEnum::CONTAINER => {
+            DockerEventType::Container
+          }
+          EventMessageTypeEnum::IMAGE => DockerEventType::Image,
+          EventMessageTypeEnum::NETWORK => DockerEventType::Network,
+          EventMessageTypeEnum::VOLUME => DockerEventType::Volume,
+          _ => return None,
+        };
+        let actor = event.actor.unwrap_or_default();
+        let name = match ty {
+          DockerEventType::Container => actor
+            .attributes
+            .and_then(|mut attributes| attributes.remove("name")),
+          _ => None,
+        };
+        Some(Ok(DockerEvent {
+          ty,
+          action: event.action.unwrap_or_default(),
+          id: actor.id.unwrap_or_default(),
+          name,
+          ts: event
+            .time_nano
+            .map(|nanos| nanos / 1_000_000)
+            .or(event.time.map(|secs| secs * 1000))
+            .unwrap_or_default(),
+        }))
+      })
+  }
+
   pub async fn list_containers(
     &self,
   ) -> anyhow::Result<Vec<ContainerListItem>> {
@@ -51,6 +421,7 @@ impl DockerClient {
       .flat_map(|container| {
         anyhow::Ok(ContainerListItem {
           server_id: None,
+          docker_endpoint: String::new(),
           name: container
             .names
             .context("no names on container")?
@@ -63,11 +434,11 @@ impl DockerClient {
           created: container.created,
           size_rw: container.size_rw,
           size_root_fs: container.size_root_fs,
-          state: container
-            .state
-            .context("no container state")?
-            .parse()
-            .context("failed to parse container state")?,
+          state: normalize_container_state(
+            &container.state.context("no container state")?,
+          )
+          .parse()
+          .context("failed to parse container state")?,
           status: container.status,
           network_mode: container
             .host_config
@@ -117,6 +488,51 @@ impl DockerClient {
     Ok(containers)
   }
 
+  /// The stats of the running containers.
+  /// The engine samples the cpu twice for each container,
+  /// so the containers are read concurrently.
+  pub async fn container_stats_records(
+    &self,
+  ) -> anyhow::Result<Vec<ContainerStatsRecord>> {
+    let names = self
+      .docker
+      .list_containers(Some(ListContainersOptions::<String> {
+        all: false,
+        ..Default::default()
+      }))
+      .await
+      .map_err(engine_error)?
+      .into_iter()
+      .filter_map(|container| {
+        Some(container.names?.pop()?.replace('/', ""))
+      })
+      .collect::<Vec<_>>();
+    let records =
+      join_all(names.into_iter().map(|name| async move {
+        let options = StatsOptions {
+          stream: false,
+          one_shot: false,
+        };
+        let stats =
+          std::pin::pin!(self.docker.stats(&name, Some(options)))
+            .next()
+            .await?
+            // The container may have stopped since it was listed
+            .inspect_err(|e| {
+              debug!(
+                "failed to get stats for container {name} | {e:?}"
+              )
+            })
+            .ok()?;
+        Some(container_stats_record(name, &stats))
+      }))
+      .await
+      .into_iter()
+      .flatten()
+      .collect();
+    Ok(records)
+  }
+
   pub async fn inspect_container(
     &self,
     container_name: &str,
@@ -927,6 +1343,175 @@ impl DockerClient {
       }),
     })
   }
+
+  /// The equivalent of `docker system df -v`.
+  /// Reclaimable space is calculated the same as the docker cli.
+  pub async fn disk_usage(&self) -> anyhow::Result<DockerDiskUsage> {
+    let df = self
+      .docker
+      .df()
+      .await
+      .context("Failed to get docker disk usage")?;
+
+    let image_usage = df
+      .images
+      .unwrap_or_default()
+      .into_iter()
+      .map(|image| ImageDiskUsage {
+        name: image
+          .repo_tags
+          .into_iter()
+          .next()
+          .unwrap_or_else(|| image.id.clone()),
+        id: image.id,
+        created: image.created,
+        size: image.size,
+        shared_size: image.shared_size,
+        unique_size: image.size - image.shared_size.max(0),
+        containers: image.containers,
+      })
+      .collect::<Vec<_>>();
+    let used = image_usage
+      .iter()
+      .filter(|image| image.containers > 0)
+      .map(|image| image.size)
+      .sum::<i64>();
+    let size = df.layers_size.unwrap_or_else(|| {
+      image_usage.iter().map(|image| image.unique_size).sum()
+    });
+    let images = DiskUsageSummary {
+      total: image_usage.len() as i64,
+      active: image_usage
+        .iter()
+        .filter(|image| image.containers > 0)
+        .count() as i64,
+      size,
+      reclaimable: (size - used).max(0),
+    };
+
+    let container_usage = df
+      .containers
+      .unwrap_or_default()
+      .into_iter()
+      .map(|container| ContainerDiskUsage {
+        name: container
+          .names
+          .and_then(|mut names| names.pop())
+          .unwrap_or_default()
+          .replace('/', ""),
+        id: container.id,
+        image: container.image,
+        state: container
+          .state
+          .and_then(|state| {
+            normalize_container_state(&state).parse().ok()
+          })
+          .unwrap_or_default(),
+        size_rw: container.size_rw.unwrap_or_default(),
+        size_root_fs: container.size_root_fs.unwrap_or_default(),
+      })
+      .collect::<Vec<_>>();
+    let container_active = |container: &&ContainerDiskUsage| {
+      matches!(
+        container.state,
+        ContainerStateStatusEnum::Running
+          | ContainerStateStatusEnum::Paused
+      )
+    };
+    let containers = DiskUsageSummary {
+      total: container_usage.len() as i64,
+      active: container_usage.iter().filter(container_active).count()
+        as i64,
+      size: container_usage
+        .iter()
+        .map(|container| container.size_rw)
+        .sum(),
+      reclaimable: container_usage
+        .iter()
+        .filter(|container| !container_active(container))
+        .map(|container| container.size_rw)
+        .sum(),
+    };
+
+    let volume_usage = df
+      .volumes
+      .unwrap_or_default()
+      .into_iter()
+      .map(|volume| {
+        let (size, ref_count) = volume
+          .usage_data
+          .map(|data| (data.size, data.ref_count))
+          .unwrap_or((-1, -1));
+        VolumeDiskUsage {
+          name: volume.name,
+          driver: volume.driver,
+          size,
+          ref_count,
+        }
+      })
+      .collect::<Vec<_>>();
+    let volumes = DiskUsageSummary {
+      total: volume_usage.len() as i64,
+      active: volume_usage
+        .iter()
+        .filter(|volume| volume.ref_count > 0)
+        .count() as i64,
+      size: volume_usage
+        .iter()
+        .map(|volume| volume.size.max(0))
+        .sum(),
+      reclaimable: volume_usage
+        .iter()
+        .filter(|volume| volume.ref_count == 0)
+        .map(|volume| volume.size.max(0))
+        .sum(),
+    };
+
+    let build_cache_usage = df
+      .build_cache
+      .unwrap_or_default()
+      .into_iter()
+      .map(|cache| BuildCacheDiskUsage {
+        id: cache.id.unwrap_or_default(),
+        typ: cache.typ.map(|typ| typ.to_string()).unwrap_or_default(),
+        description: cache.description.unwrap_or_default(),
+        in_use: cache.in_use.unwrap_or_default(),
+        shared: cache.shared.unwrap_or_default(),
+        size: cache.size.unwrap_or_default(),
+        last_used_at: cache.last_used_at,
+        usage_count: cache.usage_count.unwrap_or_default(),
+      })
+      .collect::<Vec<_>>();
+    // Shared records are counted in the records they are shared with
+    let build_cache = DiskUsageSummary {
+      total: build_cache_usage.len() as i64,
+      active: build_cache_usage
+        .iter()
+        .filter(|cache| cache.in_use)
+        .count() as i64,
+      size: build_cache_usage
+        .iter()
+        .filter(|cache| !cache.shared)
+        .map(|cache| cache.size)
+        .sum(),
+      reclaimable: build_cache_usage
+        .iter()
+        .filter(|cache| !cache.shared && !cache.in_use)
+        .map(|cache| cache.size)
+        .sum(),
+    };
+
+    Ok(DockerDiskUsage {
+      images,
+      containers,
+      volumes,
+      build_cache,
+      image_usage,
+      container_usage,
+      volume_usage,
+      build_cache_usage,
+    })
+  }
 }
 
 /// Returns whether build result should be pushed after build
@@ -945,7 +1530,8 @@ pub async fn docker_login(
     None => crate::helpers::registry_token(domain, account)?,
   };
   let log = async_run_command(&format!(
-    "echo {registry_token} | docker login {domain} --username {account} --password-stdin",
+    "echo {registry_token} | {} login {domain} --username {account} --password-stdin",
+    docker_cli(),
   ))
   .await;
   if log.success() {
@@ -966,10 +1552,183 @@ pub async fn docker_login(
   }
 }
 
-#[instrument]
-pub async fn pull_image(image: &str) -> Log {
-  let command = format!("docker pull {image}");
-  run_komodo_command("Docker Pull", None, command).await
+/// The credentials to pull from the registry through the engine api,
+/// resolved the same way as [docker_login].
+/// None if the domain / account are not set.
+pub fn registry_credentials(
+  domain: &str,
+  account: &str,
+  // For local token override from core.
+  registry_token: Option<&str>,
+) -> anyhow::Result<Option<DockerCredentials>> {
+  if domain.is_empty() || account.is_empty() {
+    return Ok(None);
+  }
+  let registry_token = match registry_token {
+    Some(token) => token,
+    None => crate::helpers::registry_token(domain, account)?,
+  };
+  Ok(Some(DockerCredentials {
+    username: Some(account.to_string()),
+    password: Some(registry_token.to_string()),
+    serveraddress: Some(domain.to_string()),
+    ..Default::default()
+  }))
+}
+
+/// Calculated the same as `docker stats`.
+fn container_stats_record(
+  container: String,
+  stats: &Stats,
+) -> ContainerStatsRecord {
+  let inactive_file = match stats.memory_stats.stats {
+    Some(MemoryStatsStats::V1(stats)) => stats.total_inactive_file,
+    Some(MemoryStatsStats::V2(stats)) => stats.inactive_file,
+    None => 0,
+  };
+  let mem_used_bytes = stats
+    .memory_stats
+    .usage
+    .unwrap_or_default()
+    .saturating_sub(inactive_file) as f64;
+  let mem_limit_bytes =
+    stats.memory_stats.limit.unwrap_or_default() as f64;
+  let (network_ingress_bytes, network_egress_bytes) = stats
+    .networks
+    .iter()
+    .flat_map(|networks| networks.values())
+    .fold((0.0, 0.0), |(rx, tx), network| {
+      (rx + network.rx_bytes as f64, tx + network.tx_bytes as f64)
+    });
+  let (block_read_bytes, block_write_bytes) =
+    block_io_bytes(&stats.blkio_stats);
+  ContainerStatsRecord {
+    container,
+    cpu_perc: cpu_percent(&stats.cpu_stats, &stats.precpu_stats),
+    mem_perc: if mem_limit_bytes > 0.0 {
+      mem_used_bytes / mem_limit_bytes * 100.0
+    } else {
+      0.0
+    },
+    mem_used_bytes,
+    mem_limit_bytes,
+    network_ingress_bytes,
+    network_egress_bytes,
+    block_read_bytes,
+    block_write_bytes,
+    pids: stats.pids_stats.current.unwrap_or_default() as i64,
+    ..Default::default()
+  }
+}
+
+/// The share of the host cpus used between the two samples,
+/// where 100% is one full cpu.
+fn cpu_percent(cpu: &CPUStats, precpu: &CPUStats) -> f64 {
+  let cpu_delta = cpu
+    .cpu_usage
+    .total_usage
+    .saturating_sub(precpu.cpu_usage.total_usage)
+    as f64;
+  let system_delta = cpu
+    .system_cpu_usage
+    .unwrap_or_default()
+    .saturating_sub(precpu.system_cpu_usage.unwrap_or_default())
+    as f64;
+  if cpu_delta <= 0.0 || system_delta <= 0.0 {
+    return 0.0;
+  }
+  let online_cpus = cpu.online_cpus.unwrap_or_else(|| {
+    cpu
+      .cpu_usage
+      .percpu_usage
+      .as_ref()
+      .map(|usage| usage.len() as u64)
+      .unwrap_or(1)
+  }) as f64;
+  cpu_delta / system_delta * online_cpus * 100.0
+}
+
+/// The total bytes (read, written) to the block devices.
+fn block_io_bytes(stats: &BlkioStats) -> (f64, f64) {
+  stats.io_service_bytes_recursive.iter().flatten().fold(
+    (0.0, 0.0),
+    |(read, write), entry| match entry
+      .op
+      .to_ascii_lowercase()
+      .as_str()
+    {
+      "read" => (read + entry.value as f64, write),
+      "write" => (read, write + entry.value as f64),
+      _ => (read, write),
+    },
+  )
+}
+
+/// Whether the image reference includes a tag or digest.
+/// The registry domain may include a port, so only
+/// the last path segment is checked for the tag.
+fn image_has_tag(image: &str) -> bool {
+  image.contains('@')
+    || image.rsplit('/').next().unwrap_or(image).contains(':')
+}
+
+/// Podman has some additional container states,
+/// which are mapped to the closest Docker state.
+fn normalize_container_state(state: &str) -> &str {
+  match state {
+    "configured" | "initialized" => "created",
+    "stopping" => "running",
+    "stopped" => "exited",
+    "unknown" => "",
+    state => state,
+  }
+}
+
+/// Surfaces the message from the Docker Engine,
+/// rather than the generic bollard error.
+fn engine_error(e: bollard::errors::Error) -> anyhow::Error {
+  match e {
+    bollard::errors::Error::DockerResponseServerError {
+      status_code,
+      message,
+    } => anyhow!("{message}")
+      .context(format!("Docker Engine responded with {status_code}")),
+    e => e.into(),
+  }
+}
+
+/// Pulls through the engine api rather than the cli,
+/// which gives no byte progress without a TTY.
+///
+/// The engine api doesn't read the cli credentials
+/// (`~/.docker/config.json` and credential helpers),
+/// so without configured credentials the cli pulls the image.
+#[instrument(skip(credentials))]
+pub async fn pull_image(
+  image: &str,
+  credentials: Option<DockerCredentials>,
+  progress_id: Option<&str>,
+) -> Log {
+  match credentials {
+    Some(credentials) => {
+      run_engine_pull(
+        "Docker Pull",
+        &[image.to_string()],
+        Some(credentials),
+        progress_id,
+      )
+      .await
+    }
+    None => {
+      run_pull_command(
+        "Docker Pull",
+        None,
+        format!("{} pull {image}", docker_cli()),
+        progress_id,
+      )
+      .await
+    }
+  }
 }
 
 pub fn stop_container_command(
@@ -978,25 +1737,52 @@ pub fn stop_container_command(
   time: Option<i32>,
 ) -> String {
   let container_name = to_komodo_name(container_name);
-  let signal = signal
-    .map(|signal| format!(" --signal {signal}"))
-    .unwrap_or_default();
-  let time = time
-    .map(|time| format!(" --time {time}"))
-    .unwrap_or_default();
-  format!("docker stop{signal}{time} {container_name}")
+  let cli = docker_cli();
+  match (periphery_config().container_engine, signal) {
+    (ContainerEngine::Docker, signal) => {
+      let signal = signal
+        .map(|signal| format!(" --signal {signal}"))
+        .unwrap_or_default();
+      let time = time
+        .map(|time| format!(" --time {time}"))
+        .unwrap_or_default();
+      format!("{cli} stop{signal}{time} {container_name}")
+    }
+    // `podman stop` doesn't take a signal, so send it directly,
+    // and kill the container if it hasn't exited after the timeout.
+    (ContainerEngine::Podman, Some(signal)) => {
+      let time = time.unwrap_or(10);
+      format!(
+        "{cli} kill --signal {signal} {container_name} && timeout {time} {cli} wait {container_name} > /dev/null; {cli} stop --time 0 {container_name}"
+      )
+    }
+    (ContainerEngine::Podman, None) => {
+      let time = time
+        .map(|time| format!(" --time {time}"))
+        .unwrap_or_default();
+      format!("{cli} stop{time} {container_name}")
+    }
+  }
 }
 
 pub async fn container_stats(
   container_name: Option<String>,
 ) -> anyhow::Result<Vec<ContainerStats>> {
-  let format = "--format \"{{ json . }}\"";
+  let format = match periphery_config().container_engine {
+    ContainerEngine::Docker => "--format \"{{ json . }}\"",
+    // Podman's json uses different field names
+    ContainerEngine::Podman => {
+      r#"--format '{"Name":"{{.Name}}","CPUPerc":"{{.CPUPerc}}","MemPerc":"{{.MemPerc}}","MemUsage":"{{.MemUsage}}","NetIO":"{{.NetIO}}","BlockIO":"{{.BlockIO}}","PIDs":"{{.PIDS}}"}'"#
+    }
+  };
   let container_name = match container_name {
     Some(name) => format!(" {name}"),
     None => "".to_string(),
   };
-  let command =
-    format!("docker stats{container_name} --no-stream {format}");
+  let command = format!(
+    "{} stats{container_name} --no-stream {format}",
+    docker_cli()
+  );
   let output = async_run_command(&command).await;
   if output.success() {
     output
@@ -1013,3 +1799,77 @@ pub async fn container_stats(
     Err(anyhow!("{}", output.stderr.replace('\n', "")))
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use bollard::container::{
+    BlkioStatsEntry, CPUUsage, ThrottlingData,
+  };
+
+  use super::*;
+
+  #[test]
+  fn image_tag_detection() {
+    assert!(image_has_tag("nginx:1.27"));
+    assert!(image_has_tag("ghcr.io/moghtech/komodo:latest"));
+    assert!(image_has_tag("nginx@sha256:abc"));
+    assert!(!image_has_tag("nginx"));
+    assert!(!image_has_tag("registry.local:5000/app"));
+  }
+
+  fn cpu_stats(total_usage: u64, system_cpu_usage: u64) -> CPUStats {
+    CPUStats {
+      cpu_usage: CPUUsage {
+        percpu_usage: None,
+        usage_in_usermode: 0,
+        total_usage,
+        usage_in_kernelmode: 0,
+      },
+      system_cpu_usage: Some(system_cpu_usage),
+      online_cpus: Some(4),
+      throttling_data: ThrottlingData {
+        periods: 0,
+        throttled_periods: 0,
+        throttled_time: 0,
+      },
+    }
+  }
+
+  #[test]
+  fn cpu_percent_of_one_cpu() {
+    // Used half of one of the 4 cpus
+    let precpu = cpu_stats(1_000, 10_000);
+    let cpu = cpu_stats(1_500, 14_000);
+    assert_eq!(cpu_percent(&cpu, &precpu), 50.0);
+    // Since the container started
+    let since_start = cpu_percent(&cpu, &cpu_stats(0, 0));
+    assert!((since_start - 42.857).abs() < 0.001);
+    assert_eq!(cpu_percent(&cpu, &cpu), 0.0);
+  }
+
+  #[test]
+  fn block_io_by_op() {
+    let entry = |op: &str, value| BlkioStatsEntry {
+      major: 8,
+      minor: 0,
+      op: op.to_string(),
+      value,
+    };
+    let stats = BlkioStats {
+      io_service_bytes_recursive: Some(vec![
+        entry("read", 100),
+        entry("Write", 20),
+        entry("Read", 1),
+        entry("sync", 500),
+      ]),
+      io_serviced_recursive: None,
+      io_queue_recursive: None,
+      io_service_time_recursive: None,
+      io_wait_time_recursive: None,
+      io_merged_recursive: None,
+      io_time_recursive: None,
+      sectors_recursive: None,
+    };
+    assert_eq!(block_io_bytes(&stats), (101.0, 20.0));
+  }
+}
@@ -0,0 +1,79 @@
+use axum::{
+  extract::{
+    Query, WebSocketUpgrade,
+    ws::{Message, WebSocket},
+  },
+  http::StatusCode,
+  response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use periphery_client::api::DockerEndpointQuery;
+use serror::AddStatusCode;
+
+use crate::docker::{
+  DockerClient, endpoint_docker_client, get_docker_endpoint,
+};
+
+/// Passes Docker events through to Core as they happen,
+/// so it doesn't have to wait for the next poll to see state changes.
+pub async fn docker_events(
+  Query(DockerEndpointQuery { docker_endpoint }): Query<
+    DockerEndpointQuery,
+  >,
+  ws: WebSocketUpgrade,
+) -> serror::Result<Response> {
+  let docker = get_docker_endpoint(docker_endpoint.as_deref())
+    .and_then(endpoint_docker_client)
+    .status_code(StatusCode::BAD_REQUEST)?;
+  Ok(
+    ws.on_upgrade(move |socket| handle_docker_events(socket, docker)),
+  )
+}
+
+async fn handle_docker_events(
+  socket: WebSocket,
+  docker: &'static DockerClient,
+) {
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+
+  let mut events = Box::pin(docker.events());
+
+  // docker -> ws
+  let forward = async {
+    while let Some(event) = events.next().await {
+      let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+          warn!("{e:#}");
+          break;
+        }
+      };
+      let event = match serde_json::to_string(&event) {
+        Ok(event) => event,
+        Err(e) => {
+          warn!("failed to serialize docker event | {e:?}");
+          continue;
+        }
+      };
+      if ws_sender.send(Message::text(event)).await.is_err() {
+        break;
+      }
+    }
+  };
+
+  // Only need to watch for the client closing
+  let closed = async {
+    while let Some(Ok(msg)) = ws_receiver.next().await {
+      if let Message::Close(_) = msg {
+        break;
+      }
+    }
+  };
+
+  tokio::select! {
+    _ = forward => {},
+    _ = closed => {},
+  }
+
+  let _ = ws_sender.close().await;
+}
@@ -0,0 +1,357 @@
+use std::{future::ready, pin::pin, process::Stdio};
+
+use anyhow::{Context, anyhow};
+use axum::{
+  extract::{
+    Query, WebSocketUpgrade,
+    ws::{Message, WebSocket},
+  },
+  http::StatusCode,
+  response::Response,
+};
+use futures::{SinkExt, Stream, StreamExt};
+use komodo_client::entities::SearchCombinator;
+use periphery_client::api::{
+  DockerEndpointQuery, compose::FollowComposeLog,
+  container::FollowContainerLog,
+};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serror::AddStatusCode;
+use tokio::{
+  io::{AsyncBufReadExt, AsyncRead, BufReader},
+  process::{Child, Command},
+};
+
+use crate::{
+  capabilities::{self, Requirements},
+  compose::docker_compose_command,
+  docker::{
+    container_engine_cli, docker_host_args, get_docker_endpoint,
+    with_docker_endpoint,
+  },
+};
+
+pub async fn follow_container_log(
+  Query(DockerEndpointQuery { docker_endpoint }): Query<
+    DockerEndpointQuery,
+  >,
+  ws: WebSocketUpgrade,
+) -> serror::Result<Response> {
+  // Same as GetContainerLog
+  capabilities::check(
+    "FollowContainerLog",
+    Requirements {
+      write: false,
+      capabilities: &[],
+    },
+  )
+  .status_code(StatusCode::FORBIDDEN)?;
+  let docker_endpoint =
+    get_docker_endpoint(docker_endpoint.as_deref())
+      .status_code(StatusCode::BAD_REQUEST)?;
+  Ok(ws.on_upgrade(move |socket| {
+    with_docker_endpoint(
+      docker_endpoint,
+      handle_follow_log(socket, |request: FollowContainerLog| {
+        let FollowContainerLog {
+          name,
+          tail,
+          terms,
+          combinator,
+          invert,
+          timestamps,
+        } = request;
+        check_arg("Container name", &name)?;
+        let mut command = Command::new(container_engine_cli());
+        command.args(docker_host_args()).args([
+          "logs",
+          "-f",
+          "--tail",
+          &tail.to_string(),
+        ]);
+        if timestamps {
+          command.arg("--timestamps");
+        }
+        command.arg(name);
+        Ok(FollowLog {
+          command,
+          filter: LogFilter::new(&terms, combinator, invert)?,
+        })
+      }),
+    )
+  }))
+}
+
+pub async fn follow_compose_log(
+  Query(DockerEndpointQuery { docker_endpoint }): Query<
+    DockerEndpointQuery,
+  >,
+  ws: WebSocketUpgrade,
+) -> serror::Result<Response> {
+  // Same as GetComposeLog
+  capabilities::check(
+    "FollowComposeLog",
+    Requirements {
+      write: false,
+      capabilities: &[],
+    },
+  )
+  .status_code(StatusCode::FORBIDDEN)?;
+  let docker_endpoint =
+    get_docker_endpoint(docker_endpoint.as_deref())
+      .status_code(StatusCode::BAD_REQUEST)?;
+  Ok(ws.on_upgrade(move |socket| {
+    with_docker_endpoint(
+      docker_endpoint,
+      handle_follow_log(socket, |request: FollowComposeLog| {
+        let FollowComposeLog {
+          project,
+          services,
+          tail,
+          terms,
+          combinator,
+          invert,
+          timestamps,
+        } = request;
+        check_arg("Project name", &project)?;
+        for service in &services {
+          check_arg("Service name", service)?;
+        }
+        let mut command = docker_compose_command();
+        command.args([
+          "-p",
+          &project,
+          "logs",
+          "-f",
+          "--tail",
+          &tail.to_string(),
+        ]);
+        if timestamps {
+          command.arg("--timestamps");
+        }
+        command.args(services);
+        Ok(FollowLog {
+          command,
+          filter: LogFilter::new(&terms, combinator, invert)?,
+        })
+      }),
+    )
+  }))
+}
+
+/// The args are passed to the cli positionally,
+/// so they can't be allowed to be read as flags.
+fn check_arg(kind: &str, arg: &str) -> anyhow::Result<()> {
+  if arg.is_empty() || arg.starts_with('-') {
+    return Err(anyhow!("{kind} is invalid: {arg:?}"));
+  }
+  Ok(())
+}
+
+struct FollowLog {
+  command: Command,
+  filter: LogFilter,
+}
+
+/// Applies the search terms to the lines,
+/// the same as the `grep` of the log searches.
+struct LogFilter {
+  terms: Vec<Regex>,
+  combinator: SearchCombinator,
+  invert: bool,
+}
+
+impl LogFilter {
+  fn new(
+    terms: &[String],
+    combinator: SearchCombinator,
+    invert: bool,
+  ) -> anyhow::Result<LogFilter> {
+    let terms = terms
+      .iter()
+      .map(|term| {
+        Regex::new(term)
+          .with_context(|| format!("invalid search term: {term}"))
+      })
+      .collect::<anyhow::Result<_>>()?;
+    Ok(LogFilter {
+      terms,
+      combinator,
+      invert,
+    })
+  }
+
+  fn matches(&self, line: &str) -> bool {
+    if self.terms.is_empty() {
+      return true;
+    }
+    let matches = match self.combinator {
+      SearchCombinator::Or => {
+        self.terms.iter().any(|term| term.is_match(line))
+      }
+      SearchCombinator::And => {
+        self.terms.iter().all(|term| term.is_match(line))
+      }
+    };
+    matches != self.invert
+  }
+}
+
+/// Waits for the request as the first message,
+/// then passes each line of the command output as a Text message
+/// until either side closes.
+async fn handle_follow_log<R: DeserializeOwned>(
+  socket: WebSocket,
+  follow_log: impl FnOnce(R) -> anyhow::Result<FollowLog>,
+) {
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+
+  let res = async {
+    let request = match ws_receiver.next().await {
+      Some(Ok(Message::Text(text))) => {
+        serde_json::from_str::<R>(text.as_str())
+          .context("failed to parse follow log request")?
+      }
+      _ => return Err(anyhow!("did not receive follow log request")),
+    };
+    let FollowLog { command, filter } = follow_log(request)?;
+    Ok((spawn_follow_log(command)?, filter))
+  };
+
+  let (mut child, filter) = match res.await {
+    Ok(res) => res,
+    Err(e) => {
+      warn!("failed to follow log | {e:#}");
+      let _ = ws_sender
+        .send(Message::text(format!("failed to follow log | {e:#}")))
+        .await;
+      let _ = ws_sender.close().await;
+      return;
+    }
+  };
+
+  let (Some(stdout), Some(stderr)) =
+    (child.stdout.take(), child.stderr.take())
+  else {
+    let _ = child.kill().await;
+    let _ = ws_sender.close().await;
+    return;
+  };
+  // The container stderr is logged on stderr.
+  let mut lines = pin!(
+    futures::stream::select(
+      output_lines(stdout),
+      output_lines(stderr)
+    )
+    .filter(|line| ready(filter.matches(line)))
+  );
+
+  // command -> ws
+  let stdout = async {
+    while let Some(line) = lines.next().await {
+      if ws_sender.send(Message::text(line)).await.is_err() {
+        break;
+      }
+    }
+  };
+
+  // Only need to watch for the client closing
+  let closed = async {
+    while let Some(Ok(msg)) = ws_receiver.next().await {
+      if let Message::Close(_) = msg {
+        break;
+      }
+    }
+  };
+
+  tokio::select! {
+    _ = stdout => {},
+    _ = closed => {},
+  }
+
+  let _ = child.kill().await;
+  let _ = ws_sender.close().await;
+}
+
+fn output_lines(
+  output: impl AsyncRead + Unpin,
+) -> impl Stream<Item = String> {
+  futures::stream::unfold(
+    BufReader::new(output).lines(),
+    |mut lines| async move {
+      let line = lines.next_line().await.ok().flatten()?;
+      Some((line, lines))
+    },
+  )
+}
+
+fn spawn_follow_log(mut command: Command) -> anyhow::Result<Child> {
+  command
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .context("failed to spawn log command")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn filter(
+    terms: &[&str],
+    combinator: SearchCombinator,
+    invert: bool,
+  ) -> LogFilter {
+    let terms = terms
+      .iter()
+      .map(|term| term.to_string())
+      .collect::<Vec<_>>();
+    LogFilter::new(&terms, combinator, invert).unwrap()
+  }
+
+  #[test]
+  fn log_filter_combinators() {
+    let or = filter(&["error", "warn"], SearchCombinator::Or, false);
+    assert!(or.matches("WARN: warn disk"));
+    assert!(or.matches("an error"));
+    assert!(!or.matches("info"));
+
+    let and = filter(&["error", "db"], SearchCombinator::And, false);
+    assert!(and.matches("db error"));
+    assert!(!and.matches("an error"));
+
+    let invert = filter(&["debug"], SearchCombinator::Or, true);
+    assert!(invert.matches("info"));
+    assert!(!invert.matches("debug: ..."));
+
+    let none = filter(&[], SearchCombinator::Or, true);
+    assert!(none.matches("anything"));
+  }
+
+  #[test]
+  fn log_filter_terms_are_regex() {
+    let filter =
+      filter(&["^GET /api/[0-9]+"], SearchCombinator::Or, false);
+    assert!(filter.matches("GET /api/12 200"));
+    assert!(!filter.matches("POST /api/12 200"));
+    assert!(
+      LogFilter::new(
+        &[String::from("(unclosed")],
+        SearchCombinator::Or,
+        false
+      )
+      .is_err()
+    );
+  }
+
+  #[test]
+  fn args_cant_be_flags() {
+    assert!(check_arg("Container name", "komodo-core").is_ok());
+    assert!(check_arg("Container name", "--help").is_err());
+    assert!(check_arg("Container name", "-f").is_err());
+    assert!(check_arg("Container name", "").is_err());
+  }
+}
@@ -5,16 +5,24 @@ extern crate tracing;
 use std::{net::SocketAddr, str::FromStr};
 
 use anyhow::Context;
-use axum_server::tls_rustls::RustlsConfig;
 
 mod api;
+mod backup;
+mod capabilities;
 mod compose;
 mod config;
+mod copy;
 mod docker;
+mod events;
 mod helpers;
+mod logs;
+mod passkeys;
+mod pull_progress;
 mod router;
 mod ssl;
 mod stats;
+mod terminal;
+mod tunnel;
 
 async fn app() -> anyhow::Result<()> {
   dotenvy::dotenv().ok();
@@ -24,7 +32,14 @@ async fn app() -> anyhow::Result<()> {
   info!("Komodo Periphery version: v{}", env!("CARGO_PKG_VERSION"));
   info!("{:?}", config.sanitized());
 
+  // Used by both the ssl server and the outbound connection to core
+  rustls::crypto::ring::default_provider()
+    .install_default()
+    .expect("failed to install default rustls CryptoProvider");
+
+  docker::init_docker_endpoints();
   stats::spawn_system_stats_polling_thread();
+  tunnel::spawn_core_connection();
 
   let socket_addr =
     SocketAddr::from_str(&format!("0.0.0.0:{}", config.port))
@@ -35,17 +50,9 @@ async fn app() -> anyhow::Result<()> {
 
   if config.ssl_enabled {
     info!("🔒 Periphery SSL Enabled");
-    rustls::crypto::ring::default_provider()
-      .install_default()
-      .expect("failed to install default rustls CryptoProvider");
     ssl::ensure_certs().await;
     info!("Komodo Periphery starting on https://{}", socket_addr);
-    let ssl_config = RustlsConfig::from_pem_file(
-      &config.ssl_cert_file,
-      &config.ssl_key_file,
-    )
-    .await
-    .context("Invalid ssl cert / key")?;
+    let ssl_config = ssl::rustls_config()?;
     axum_server::bind_rustls(socket_addr, ssl_config)
       .serve(app)
       .await?
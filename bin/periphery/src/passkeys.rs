@@ -0,0 +1,190 @@
+use std::{
+  io::Write,
+  os::unix::fs::OpenOptionsExt,
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::{Context, anyhow};
+use komodo_client::entities::komodo_timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::config::periphery_config;
+
+/// Once Core signs with the new passkey, the previous passkeys
+/// are still accepted for this long, for requests already in flight.
+const ROLLOVER_GRACE_MS: i64 = 60 * 1000;
+
+/// The passkey rotated by Core, replacing the configured `passkeys`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RotatedPasskey {
+  passkey: String,
+  /// The passkeys accepted before the rotation,
+  /// still accepted until the rollover completes.
+  previous: Vec<String>,
+  /// Set on the first request signed with `passkey`.
+  rolled_over_at: Option<i64>,
+}
+
+fn rotated_passkey() -> &'static Mutex<Option<RotatedPasskey>> {
+  static ROTATED_PASSKEY: OnceLock<Mutex<Option<RotatedPasskey>>> =
+    OnceLock::new();
+  ROTATED_PASSKEY.get_or_init(|| {
+    let path = &periphery_config().rotated_passkey_file;
+    let rotated = match std::fs::read_to_string(path) {
+      Ok(contents) => serde_json::from_str(&contents)
+        .inspect_err(|e| {
+          error!("Invalid rotated passkey file at {path:?} | {e:?}")
+        })
+        .ok(),
+      Err(_) => None,
+    };
+    Mutex::new(rotated)
+  })
+}
+
+impl RotatedPasskey {
+  fn accepted_passkeys(&self, now: i64) -> Vec<String> {
+    let mut passkeys = vec![self.passkey.clone()];
+    let in_rollover =
+      self.rolled_over_at.is_none_or(|rolled_over_at| {
+        now - rolled_over_at < ROLLOVER_GRACE_MS
+      });
+    if in_rollover {
+      passkeys.extend(self.previous.iter().cloned());
+    }
+    passkeys
+  }
+
+  /// Returns true if this completes the rollover.
+  fn signed_with(&mut self, passkey: &str, now: i64) -> bool {
+    if self.rolled_over_at.is_some() || self.passkey != passkey {
+      return false;
+    }
+    self.rolled_over_at = Some(now);
+    true
+  }
+}
+
+/// The passkeys requests can be signed with.
+/// If empty, requests don't need to be signed.
+pub fn accepted_passkeys() -> Vec<String> {
+  let rotated = rotated_passkey().lock().unwrap();
+  match rotated.as_ref() {
+    Some(rotated) => rotated.accepted_passkeys(komodo_timestamp()),
+    None => periphery_config().passkeys.clone(),
+  }
+}
+
+/// The passkey to sign the outbound connection to Core with.
+/// Core assigns the Server a passkey of its own on the first
+/// connection, which replaces `core_passkey`.
+pub fn core_passkey() -> String {
+  rotated_passkey()
+    .lock()
+    .unwrap()
+    .as_ref()
+    .map(|rotated| rotated.passkey.clone())
+    .unwrap_or_else(|| periphery_config().core_passkey.clone())
+}
+
+/// Called with the passkey a request was signed with.
+/// The first request signed with the rotated passkey
+/// completes the rollover.
+pub fn signed_with(passkey: &str) {
+  let mut rotated = rotated_passkey().lock().unwrap();
+  let Some(rotated) = rotated.as_mut() else {
+    return;
+  };
+  if !rotated.signed_with(passkey, komodo_timestamp()) {
+    return;
+  }
+  if let Err(e) = write_rotated_passkey(rotated) {
+    warn!("{e:#}");
+  }
+  info!("Passkey rollover complete");
+}
+
+/// Accepts the new passkey along with the currently accepted passkeys,
+/// until Core signs a request with the new passkey.
+pub fn rotate(passkey: String) -> anyhow::Result<()> {
+  if passkey.is_empty() {
+    return Err(anyhow!("Passkey cannot be empty"));
+  }
+  let previous = accepted_passkeys();
+  let new = RotatedPasskey {
+    passkey,
+    previous,
+    rolled_over_at: None,
+  };
+  write_rotated_passkey(&new)?;
+  *rotated_passkey().lock().unwrap() = Some(new);
+  info!("Passkey rotated, waiting for rollover");
+  Ok(())
+}
+
+fn write_rotated_passkey(
+  rotated: &RotatedPasskey,
+) -> anyhow::Result<()> {
+  let path = &periphery_config().rotated_passkey_file;
+  if let Some(parent) = path.parent() {
+    let _ = std::fs::create_dir_all(parent);
+  }
+  let contents = serde_json::to_string_pretty(rotated)
+    .context("Failed to serialize rotated passkey")?;
+  std::fs::OpenOptions::new()
+    .write(true)
+    .create(true)
+    .truncate(true)
+    .mode(0o600)
+    .open(path)
+    .and_then(|mut file| file.write_all(contents.as_bytes()))
+    .with_context(|| {
+      format!("Failed to write rotated passkey to {path:?}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rotated() -> RotatedPasskey {
+    RotatedPasskey {
+      passkey: String::from("new"),
+      previous: vec![String::from("old")],
+      rolled_over_at: None,
+    }
+  }
+
+  #[test]
+  fn previous_passkeys_accepted_until_rollover() {
+    let rotated = rotated();
+    assert_eq!(rotated.accepted_passkeys(0), ["new", "old"]);
+    assert_eq!(rotated.accepted_passkeys(i64::MAX), ["new", "old"]);
+  }
+
+  #[test]
+  fn rollover_on_new_passkey_only() {
+    let mut rotated = rotated();
+    assert!(!rotated.signed_with("old", 1_000));
+    assert_eq!(rotated.rolled_over_at, None);
+    assert!(rotated.signed_with("new", 1_000));
+    assert_eq!(rotated.rolled_over_at, Some(1_000));
+    // Only the first request completes the rollover
+    assert!(!rotated.signed_with("new", 2_000));
+    assert_eq!(rotated.rolled_over_at, Some(1_000));
+  }
+
+  #[test]
+  fn previous_passkeys_dropped_after_grace() {
+    let mut rotated = rotated();
+    rotated.signed_with("new", 1_000);
+    assert_eq!(
+      rotated.accepted_passkeys(1_000 + ROLLOVER_GRACE_MS - 1),
+      ["new", "old"]
+    );
+    assert_eq!(
+      rotated.accepted_passkeys(1_000 + ROLLOVER_GRACE_MS),
+      ["new"]
+    );
+  }
+}
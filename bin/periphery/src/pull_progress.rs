@@ -0,0 +1,403 @@
+use std::{
+  collections::HashMap,
+  path::Path,
+  process::Stdio,
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::Context;
+use bollard::{
+  auth::DockerCredentials,
+  secret::{CreateImageInfo, ProgressDetail},
+};
+use command::run_komodo_command;
+use formatting::format_serror;
+use futures::StreamExt;
+use komodo_client::entities::{
+  deployment::extract_registry_domain, komodo_timestamp, update::Log,
+};
+use tokio::{
+  io::{AsyncBufReadExt, AsyncRead, BufReader},
+  process::Command,
+};
+
+use crate::docker::{container_engine_cli, docker_client};
+
+/// The latest line for each layer of the pulls in progress,
+/// by the progress id Core sends with the request.
+fn pull_progress() -> &'static Mutex<HashMap<String, PullProgress>> {
+  static PULL_PROGRESS: OnceLock<
+    Mutex<HashMap<String, PullProgress>>,
+  > = OnceLock::new();
+  PULL_PROGRESS.get_or_init(Default::default)
+}
+
+#[derive(Default)]
+struct PullProgress {
+  /// Line keys in the order they were first seen
+  order: Vec<String>,
+  latest: HashMap<String, String>,
+}
+
+impl PullProgress {
+  fn push_line(&mut self, line: &str) {
+    let line = line.trim();
+    if line.is_empty() {
+      return;
+    }
+    let key = line_key(line);
+    if !self.latest.contains_key(&key) {
+      self.order.push(key.clone());
+    }
+    self.latest.insert(key, line.to_string());
+  }
+
+  fn render(&self) -> String {
+    self
+      .order
+      .iter()
+      .filter_map(|key| self.latest.get(key))
+      .map(String::as_str)
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+}
+
+/// Formats the engine api pull message like the cli output,
+/// with the byte progress of the layer when it is known.
+fn info_line(info: &CreateImageInfo) -> Option<String> {
+  let status = info.status.as_deref()?;
+  let mut line = match &info.id {
+    Some(id) => format!("{id}: {status}"),
+    None => status.to_string(),
+  };
+  if let Some(ProgressDetail {
+    current: Some(current),
+    total: Some(total),
+  }) = &info.progress_detail
+  {
+    if *total > 0 {
+      line.push_str(&format!(
+        " {} / {} ({}%)",
+        format_bytes(*current),
+        format_bytes(*total),
+        current * 100 / total
+      ));
+    }
+  }
+  Some(line)
+}
+
+/// Formats the byte count like the docker cli, eg `12.3MB`.
+fn format_bytes(bytes: i64) -> String {
+  if bytes < 1000 {
+    return format!("{bytes}B");
+  }
+  let mut size = bytes as f64;
+  let mut unit = "B";
+  for next in ["kB", "MB", "GB", "TB"] {
+    if size < 1000.0 {
+      break;
+    }
+    size /= 1000.0;
+    unit = next;
+  }
+  format!("{size:.1}{unit}")
+}
+
+/// Groups the pull output lines by the layer / service they refer to:
+/// - docker pull: `<layer>: Downloading ...`
+/// - podman pull: `Copying blob <layer> ...`
+/// - docker compose pull: `<layer or service> Downloading ...`
+fn line_key(line: &str) -> String {
+  if let Some((key, _)) = line
+    .split_once(": ")
+    .filter(|(key, _)| !key.contains(char::is_whitespace))
+  {
+    return key.to_string();
+  }
+  if line.starts_with("Copying ") {
+    return line
+      .split_whitespace()
+      .take(3)
+      .collect::<Vec<_>>()
+      .join(" ");
+  }
+  line
+    .split_whitespace()
+    .next()
+    .unwrap_or_default()
+    .to_string()
+}
+
+/// The current progress of the pull, if one is running with the id.
+pub fn get_pull_progress(id: &str) -> Option<String> {
+  pull_progress()
+    .lock()
+    .unwrap()
+    .get(id)
+    .map(PullProgress::render)
+}
+
+/// Runs the pull command, tracking the pull progress
+/// under the `progress_id` until it finishes.
+/// Without `progress_id`, this is the same as [run_komodo_command].
+pub async fn run_pull_command(
+  stage: &str,
+  path: impl Into<Option<&Path>>,
+  command: impl AsRef<str>,
+  progress_id: Option<&str>,
+) -> Log {
+  let Some(id) = progress_id else {
+    return run_komodo_command(stage, path, command).await;
+  };
+  let command = if let Some(path) = path.into() {
+    format!("cd {} && {}", path.display(), command.as_ref())
+  } else {
+    command.as_ref().to_string()
+  };
+  let start_ts = komodo_timestamp();
+  pull_progress()
+    .lock()
+    .unwrap()
+    .insert(id.to_string(), Default::default());
+  let res = run_with_progress(&command, id).await;
+  pull_progress().lock().unwrap().remove(id);
+  match res {
+    Ok((success, stdout, stderr)) => Log {
+      stage: stage.to_string(),
+      command,
+      stdout,
+      stderr,
+      success,
+      start_ts,
+      end_ts: komodo_timestamp(),
+    },
+    Err(e) => Log {
+      stage: stage.to_string(),
+      command,
+      stdout: String::new(),
+      stderr: format_serror(&e.into()),
+      success: false,
+      start_ts,
+      end_ts: komodo_timestamp(),
+    },
+  }
+}
+
+/// Pulls the images through the engine api, tracking the
+/// byte progress of each layer under the `progress_id`.
+/// The cli only reports when each layer is done without a TTY.
+pub async fn run_engine_pull(
+  stage: &str,
+  images: &[String],
+  credentials: Option<DockerCredentials>,
+  progress_id: Option<&str>,
+) -> Log {
+  let command = images
+    .iter()
+    .map(|image| format!("{} pull {image}", container_engine_cli()))
+    .collect::<Vec<_>>()
+    .join(" && ");
+  let start_ts = komodo_timestamp();
+  if let Some(id) = progress_id {
+    pull_progress()
+      .lock()
+      .unwrap()
+      .insert(id.to_string(), Default::default());
+  }
+  // The final line of each layer makes up the log.
+  let mut output = PullProgress::default();
+  let res = async {
+    let client = docker_client()?;
+    for image in images {
+      let mut stream = std::pin::pin!(client.pull_image(
+        image,
+        image_credentials(credentials.as_ref(), image),
+      ));
+      while let Some(info) = stream.next().await {
+        let info =
+          info.with_context(|| format!("Failed to pull {image}"))?;
+        let Some(line) = info_line(&info) else {
+          continue;
+        };
+        output.push_line(&line);
+        let Some(id) = progress_id else {
+          continue;
+        };
+        if let Some(progress) =
+          pull_progress().lock().unwrap().get_mut(id)
+        {
+          progress.push_line(&line);
+        }
+      }
+    }
+    anyhow::Ok(())
+  }
+  .await;
+  if let Some(id) = progress_id {
+    pull_progress().lock().unwrap().remove(id);
+  }
+  let (success, stderr) = match res {
+    Ok(()) => (true, String::new()),
+    Err(e) => (false, format_serror(&e.into())),
+  };
+  Log {
+    stage: stage.to_string(),
+    command,
+    stdout: output.render(),
+    stderr,
+    success,
+    start_ts,
+    end_ts: komodo_timestamp(),
+  }
+}
+
+/// The credentials are only sent to the registry they are for,
+/// as the compose services may pull from other registries.
+fn image_credentials(
+  credentials: Option<&DockerCredentials>,
+  image: &str,
+) -> Option<DockerCredentials> {
+  let credentials = credentials?;
+  let domain = extract_registry_domain(image).ok()?;
+  (credentials.serveraddress.as_deref() == Some(domain.as_str()))
+    .then(|| credentials.clone())
+}
+
+async fn run_with_progress(
+  command: &str,
+  id: &str,
+) -> anyhow::Result<(bool, String, String)> {
+  let mut child = Command::new("sh")
+    .arg("-c")
+    .arg(command)
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true)
+    .spawn()
+    .context("Failed to spawn pull command")?;
+  let stdout = child.stdout.take().context("Missing stdout")?;
+  let stderr = child.stderr.take().context("Missing stderr")?;
+  // docker pull writes progress to stdout, docker compose to stderr.
+  let (stdout, stderr) =
+    tokio::join!(read_lines(stdout, id), read_lines(stderr, id));
+  let status = child
+    .wait()
+    .await
+    .context("Failed to wait for pull command")?;
+  Ok((status.success(), stdout, stderr))
+}
+
+/// Reads the output to the end, updating the progress with each line.
+async fn read_lines(
+  output: impl AsyncRead + Unpin,
+  id: &str,
+) -> String {
+  let mut lines = BufReader::new(output).lines();
+  let mut res = String::new();
+  while let Ok(Some(line)) = lines.next_line().await {
+    if let Some(progress) =
+      pull_progress().lock().unwrap().get_mut(id)
+    {
+      progress.push_line(&line);
+    }
+    if !res.is_empty() {
+      res.push('\n');
+    }
+    res.push_str(&line);
+  }
+  res
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn info(
+    id: &str,
+    status: &str,
+    progress: Option<(i64, i64)>,
+  ) -> CreateImageInfo {
+    CreateImageInfo {
+      id: Some(id.to_string()),
+      status: Some(status.to_string()),
+      progress_detail: progress.map(|(current, total)| {
+        ProgressDetail {
+          current: Some(current),
+          total: Some(total),
+        }
+      }),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn credentials_only_for_their_registry() {
+    let credentials = DockerCredentials {
+      username: Some(String::from("user")),
+      password: Some(String::from("token")),
+      serveraddress: Some(String::from("ghcr.io")),
+      ..Default::default()
+    };
+    assert!(
+      image_credentials(Some(&credentials), "ghcr.io/org/app:1.0")
+        .is_some()
+    );
+    assert!(
+      image_credentials(Some(&credentials), "postgres:17").is_none()
+    );
+    assert!(
+      image_credentials(Some(&credentials), "quay.io/org/app")
+        .is_none()
+    );
+    assert!(image_credentials(None, "ghcr.io/org/app").is_none());
+  }
+
+  #[test]
+  fn info_line_byte_progress() {
+    assert_eq!(
+      info_line(&info(
+        "a2abf6c4d29d",
+        "Downloading",
+        Some((1_234_567, 31_400_000))
+      ))
+      .unwrap(),
+      "a2abf6c4d29d: Downloading 1.2MB / 31.4MB (3%)"
+    );
+    assert_eq!(
+      info_line(&info("a2abf6c4d29d", "Pull complete", None))
+        .unwrap(),
+      "a2abf6c4d29d: Pull complete"
+    );
+    assert!(info_line(&CreateImageInfo::default()).is_none());
+  }
+
+  #[test]
+  fn format_bytes_units() {
+    assert_eq!(format_bytes(999), "999B");
+    assert_eq!(format_bytes(1_500), "1.5kB");
+    assert_eq!(format_bytes(31_400_000), "31.4MB");
+    assert_eq!(format_bytes(2_000_000_000), "2.0GB");
+  }
+
+  #[test]
+  fn progress_keeps_latest_line_per_layer() {
+    let mut progress = PullProgress::default();
+    for line in [
+      "latest: Pulling from library/nginx",
+      "a2abf6c4d29d: Downloading 1.2MB / 31.4MB (3%)",
+      "b4df32aa5a72: Waiting",
+      "a2abf6c4d29d: Downloading 15.7MB / 31.4MB (50%)",
+    ] {
+      progress.push_line(line);
+    }
+    assert_eq!(
+      progress.render(),
+      "latest: Pulling from library/nginx\n\
+       a2abf6c4d29d: Downloading 15.7MB / 31.4MB (50%)\n\
+       b4df32aa5a72: Waiting"
+    );
+  }
+}
@@ -8,30 +8,87 @@ use axum::{
   http::{Request, StatusCode},
   middleware::{self, Next},
   response::Response,
-  routing::post,
+  routing::{get, post},
 };
 use derive_variants::ExtractVariant;
+use komodo_client::entities::komodo_timestamp;
+use periphery_client::auth::{
+  NONCE_HEADER, REPLAY_WINDOW_MS, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+  check_nonce, verify_signature,
+};
 use resolver_api::Resolve;
 use serror::{AddStatusCode, AddStatusCodeError, Json};
 use uuid::Uuid;
 
-use crate::config::periphery_config;
+use crate::{
+  capabilities,
+  config::periphery_config,
+  docker::{get_docker_endpoint, with_docker_endpoint},
+  passkeys,
+};
+
+/// Matches the default limit of the axum body extractors.
+const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
 
 pub fn router() -> Router {
   Router::new()
     .route("/", post(handler))
+    .route(
+      "/terminal/container",
+      get(crate::terminal::container_exec),
+    )
+    .route("/log/container", get(crate::logs::follow_container_log))
+    .route("/log/compose", get(crate::logs::follow_compose_log))
+    .route("/events", get(crate::events::docker_events))
+    .route(
+      "/copy/container",
+      get(crate::copy::download_from_container)
+        .post(crate::copy::upload_to_container),
+    )
     .layer(middleware::from_fn(guard_request_by_ip))
     .layer(middleware::from_fn(guard_request_by_passkey))
 }
 
 async fn handler(
-  Json(request): Json<crate::api::PeripheryRequest>,
+  Json(body): Json<serde_json::Value>,
+) -> serror::Result<axum::response::Response> {
+  let (request, docker_endpoint) =
+    parse_request(body).status_code(StatusCode::BAD_REQUEST)?;
+  handle_request(request, docker_endpoint).await
+}
+
+/// Splits the `docker_endpoint` the request targets from the request body.
+pub fn parse_request(
+  mut body: serde_json::Value,
+) -> anyhow::Result<(crate::api::PeripheryRequest, Option<String>)> {
+  let docker_endpoint = body
+    .as_object_mut()
+    .and_then(|body| body.remove("docker_endpoint"))
+    .map(serde_json::from_value::<String>)
+    .transpose()
+    .context("docker_endpoint must be a string")?;
+  let request = serde_json::from_value(body)
+    .context("failed to parse request")?;
+  Ok((request, docker_endpoint))
+}
+
+/// Also used for requests received over the outbound connection to core.
+pub async fn handle_request(
+  request: crate::api::PeripheryRequest,
+  docker_endpoint: Option<String>,
 ) -> serror::Result<axum::response::Response> {
   let req_id = Uuid::new_v4();
 
-  let res = tokio::spawn(task(req_id, request))
-    .await
-    .context("task handler spawn error");
+  let docker_endpoint =
+    get_docker_endpoint(docker_endpoint.as_deref())
+      .status_code(StatusCode::BAD_REQUEST)?;
+
+  let res = tokio::spawn(with_docker_endpoint(
+    docker_endpoint,
+    task(req_id, request),
+  ))
+  .await
+  .context("task handler spawn error");
 
   if let Err(e) = &res {
     warn!("request {req_id} spawn error: {e:#}");
@@ -46,7 +103,18 @@ async fn task(
 ) -> serror::Result<axum::response::Response> {
   let variant = request.extract_variant();
 
-  let res = request.resolve(&crate::api::Args).await.map(|res| res.0);
+  let res = match capabilities::check(
+    &format!("{variant:?}"),
+    request.requirements(),
+  )
+  .and_then(|_| request.check_commands())
+  .status_code(StatusCode::FORBIDDEN)
+  {
+    Ok(_) => {
+      request.resolve(&crate::api::Args).await.map(|res| res.0)
+    }
+    Err(e) => Err(e),
+  };
 
   if let Err(e) = &res {
     warn!(
@@ -62,30 +130,111 @@ async fn guard_request_by_passkey(
   req: Request<Body>,
   next: Next,
 ) -> serror::Result<Response> {
-  if periphery_config().passkeys.is_empty() {
+  let passkeys = passkeys::accepted_passkeys();
+  if passkeys.is_empty() {
     return Ok(next.run(req).await);
   }
-  let Some(req_passkey) = req.headers().get("authorization") else {
-    return Err(
-      anyhow!("request was not sent with passkey")
-        .status_code(StatusCode::UNAUTHORIZED),
-    );
+  let req = verify_request(req, &passkeys)
+    .await
+    .status_code(StatusCode::UNAUTHORIZED)?;
+  Ok(next.run(req).await)
+}
+
+/// Checks the request signature against the accepted passkeys,
+/// and that it isn't a replay of an earlier request.
+async fn verify_request(
+  req: Request<Body>,
+  passkeys: &[String],
+) -> anyhow::Result<Request<Body>> {
+  let headers = req.headers();
+  let Some(signature) = headers.get(SIGNATURE_HEADER) else {
+    return verify_unsigned_request(req, passkeys);
   };
-  let req_passkey = req_passkey
+  let signature = signature
     .to_str()
-    .context("failed to convert passkey to str")
-    .status_code(StatusCode::UNAUTHORIZED)?;
-  if periphery_config()
-    .passkeys
+    .context("failed to convert signature to str")?
+    .to_string();
+  let timestamp = headers
+    .get(TIMESTAMP_HEADER)
+    .context("request was not sent with timestamp")?
+    .to_str()
+    .context("failed to convert timestamp to str")?
+    .parse::<i64>()
+    .context("request timestamp is not a number")?;
+  let nonce = headers
+    .get(NONCE_HEADER)
+    .context("request was not sent with nonce")?
+    .to_str()
+    .context("failed to convert nonce to str")?
+    .to_string();
+
+  if (komodo_timestamp() - timestamp).abs() > REPLAY_WINDOW_MS {
+    return Err(anyhow!(
+      "request timestamp is outside the allowed window, check the clocks of core and periphery are in sync"
+    ));
+  }
+
+  // The body is read before the signature is checked,
+  // so bound it by the limit of the route.
+  let (parts, body) = req.into_parts();
+  let limit = body_limit(parts.uri.path());
+  let body =
+    axum::body::to_bytes(body, limit).await.with_context(|| {
+      format!("failed to read request body | limit: {limit} bytes")
+    })?;
+  let method = parts.method.as_str();
+  let path = parts
+    .uri
+    .path_and_query()
+    .map(|path| path.as_str())
+    .unwrap_or("/");
+
+  let passkey = passkeys
     .iter()
-    .any(|passkey| passkey == req_passkey)
-  {
-    Ok(next.run(req).await)
+    .find(|passkey| {
+      verify_signature(
+        passkey, method, path, timestamp, &nonce, &body, &signature,
+      )
+    })
+    .context("request signature invalid")?;
+
+  // Only record the nonce once the request is verified
+  if !check_nonce(&nonce, timestamp) {
+    return Err(anyhow!("request nonce was already used"));
+  }
+
+  passkeys::signed_with(passkey);
+
+  Ok(Request::from_parts(parts, Body::from(body)))
+}
+
+/// Archive uploads are limited by `max_upload_size_mb`,
+/// everything else by the axum default for json requests.
+fn body_limit(path: &str) -> usize {
+  if path == "/copy/container" {
+    crate::copy::max_upload_size()
   } else {
-    Err(
-      anyhow!("request passkey invalid")
-        .status_code(StatusCode::UNAUTHORIZED),
-    )
+    DEFAULT_BODY_LIMIT
+  }
+}
+
+fn verify_unsigned_request(
+  req: Request<Body>,
+  passkeys: &[String],
+) -> anyhow::Result<Request<Body>> {
+  if !periphery_config().allow_unsigned_requests {
+    return Err(anyhow!("request was not signed"));
+  }
+  let req_passkey = req
+    .headers()
+    .get("authorization")
+    .context("request was not sent with passkey")?
+    .to_str()
+    .context("failed to convert passkey to str")?;
+  if passkeys.iter().any(|passkey| passkey == req_passkey) {
+    Ok(req)
+  } else {
+    Err(anyhow!("request passkey invalid"))
   }
 }
 
@@ -1,29 +1,246 @@
+use std::{
+  path::{Path, PathBuf},
+  sync::{Arc, OnceLock},
+};
+
+use anyhow::{Context, anyhow};
+use axum_server::tls_rustls::RustlsConfig;
+use periphery_client::tls::{
+  certificate_file_fingerprint, certificate_fingerprint,
+  normalize_fingerprint,
+};
+use rustls::{
+  DigitallySignedStruct, DistinguishedName, RootCertStore,
+  ServerConfig, SignatureScheme,
+  client::danger::HandshakeSignatureValid,
+  crypto::{
+    CryptoProvider, verify_tls12_signature, verify_tls13_signature,
+  },
+  pki_types::{
+    CertificateDer, PrivateKeyDer, UnixTime, pem::PemObject,
+  },
+  server::{
+    WebPkiClientVerifier,
+    danger::{ClientCertVerified, ClientCertVerifier},
+  },
+};
+
 use crate::config::periphery_config;
 
 pub async fn ensure_certs() {
   let config = periphery_config();
   if !config.ssl_cert_file.is_file() || !config.ssl_key_file.is_file()
   {
-    generate_self_signed_ssl_certs().await
+    info!("Generating certs...");
+    if let Err(e) = generate_self_signed_ssl_certs(
+      &config.ssl_key_file,
+      &config.ssl_cert_file,
+    )
+    .await
+    {
+      panic!("🚨 Failed to generate SSL Certs | {e:#}");
+    }
+    info!("✅ SSL Certs generated");
+  }
+}
+
+static RUSTLS_CONFIG: OnceLock<RustlsConfig> = OnceLock::new();
+
+/// The config for the https server. Kept to reload the cert on rotation.
+pub fn rustls_config() -> anyhow::Result<RustlsConfig> {
+  if let Some(config) = RUSTLS_CONFIG.get() {
+    return Ok(config.clone());
   }
+  let config = RustlsConfig::from_config(server_config()?);
+  Ok(RUSTLS_CONFIG.get_or_init(|| config).clone())
 }
 
+/// Replaces the configured cert / key with a newly generated
+/// self-signed cert, and serves it for new connections.
+/// Returns the fingerprint of the new cert.
 #[instrument]
-async fn generate_self_signed_ssl_certs() {
-  info!("Generating certs...");
+pub async fn rotate_certs() -> anyhow::Result<String> {
+  let config = periphery_config();
+  if !config.ssl_enabled {
+    return Err(anyhow!("Periphery is not using ssl"));
+  }
+  let rustls_config = RUSTLS_CONFIG
+    .get()
+    .context("Periphery ssl config is not initialized")?;
+
+  // Generate alongside the existing files,
+  // so they are only replaced once generation succeeds.
+  let key_file = with_extension_suffix(&config.ssl_key_file, "new");
+  let cert_file = with_extension_suffix(&config.ssl_cert_file, "new");
+  generate_self_signed_ssl_certs(&key_file, &cert_file).await?;
+  let fingerprint = certificate_file_fingerprint(&cert_file)?;
+
+  tokio::fs::rename(&key_file, &config.ssl_key_file)
+    .await
+    .context("Failed to replace ssl key file")?;
+  tokio::fs::rename(&cert_file, &config.ssl_cert_file)
+    .await
+    .context("Failed to replace ssl cert file")?;
+
+  rustls_config.reload_from_config(server_config()?);
+
+  info!("🔒 SSL cert rotated | fingerprint: {fingerprint}");
+
+  Ok(fingerprint)
+}
+
+fn server_config() -> anyhow::Result<Arc<ServerConfig>> {
+  let config = periphery_config();
+  let certs = CertificateDer::pem_file_iter(&config.ssl_cert_file)
+    .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+    .context("Invalid ssl cert")?;
+  let key = PrivateKeyDer::from_pem_file(&config.ssl_key_file)
+    .context("Invalid ssl key")?;
+  let builder = ServerConfig::builder();
+  let mut server_config = match client_cert_verifier()? {
+    Some(verifier) => builder.with_client_cert_verifier(verifier),
+    None => builder.with_no_client_auth(),
+  }
+  .with_single_cert(certs, key)
+  .context("Invalid ssl cert / key")?;
+  server_config.alpn_protocols =
+    vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+  Ok(Arc::new(server_config))
+}
 
+/// Only configured if Core is required to present a client cert.
+fn client_cert_verifier()
+-> anyhow::Result<Option<Arc<dyn ClientCertVerifier>>> {
   let config = periphery_config();
+  if config.ssl_client_ca_file.is_none()
+    && config.ssl_client_fingerprints.is_empty()
+  {
+    return Ok(None);
+  }
+  let provider = CryptoProvider::get_default()
+    .cloned()
+    .context("No default rustls CryptoProvider installed")?;
+  let ca = match &config.ssl_client_ca_file {
+    Some(ca_file) => {
+      let mut roots = RootCertStore::empty();
+      for cert in CertificateDer::pem_file_iter(ca_file)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| {
+          format!("Invalid ssl client ca file {ca_file:?}")
+        })?
+      {
+        roots.add(cert).with_context(|| {
+          format!("Invalid ssl client ca in {ca_file:?}")
+        })?;
+      }
+      let verifier = WebPkiClientVerifier::builder_with_provider(
+        Arc::new(roots),
+        provider.clone(),
+      )
+      .build()
+      .context("Failed to build ssl client verifier")?;
+      Some(verifier)
+    }
+    None => None,
+  };
+  Ok(Some(Arc::new(CoreCertVerifier {
+    ca,
+    fingerprints: config
+      .ssl_client_fingerprints
+      .iter()
+      .map(|fingerprint| normalize_fingerprint(fingerprint))
+      .collect(),
+    provider,
+  })))
+}
+
+/// Accepts client certs matching a pinned fingerprint,
+/// or signed by the configured CA.
+#[derive(Debug)]
+struct CoreCertVerifier {
+  ca: Option<Arc<dyn ClientCertVerifier>>,
+  fingerprints: Vec<String>,
+  provider: Arc<CryptoProvider>,
+}
 
+impl ClientCertVerifier for CoreCertVerifier {
+  fn root_hint_subjects(&self) -> &[DistinguishedName] {
+    match &self.ca {
+      Some(ca) => ca.root_hint_subjects(),
+      None => &[],
+    }
+  }
+
+  fn verify_client_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    intermediates: &[CertificateDer<'_>],
+    now: UnixTime,
+  ) -> Result<ClientCertVerified, rustls::Error> {
+    let fingerprint = certificate_fingerprint(end_entity);
+    if self.fingerprints.contains(&fingerprint) {
+      return Ok(ClientCertVerified::assertion());
+    }
+    match &self.ca {
+      Some(ca) => {
+        ca.verify_client_cert(end_entity, intermediates, now)
+      }
+      None => Err(rustls::Error::General(format!(
+        "Client certificate fingerprint {fingerprint} is not trusted"
+      ))),
+    }
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    verify_tls12_signature(
+      message,
+      cert,
+      dss,
+      &self.provider.signature_verification_algorithms,
+    )
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    verify_tls13_signature(
+      message,
+      cert,
+      dss,
+      &self.provider.signature_verification_algorithms,
+    )
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self
+      .provider
+      .signature_verification_algorithms
+      .supported_schemes()
+  }
+}
+
+async fn generate_self_signed_ssl_certs(
+  key_file: &Path,
+  cert_file: &Path,
+) -> anyhow::Result<()> {
   // ensure cert folders exist
-  if let Some(parent) = config.ssl_key_file.parent() {
+  if let Some(parent) = key_file.parent() {
     let _ = std::fs::create_dir_all(parent);
   }
-  if let Some(parent) = config.ssl_cert_file.parent() {
+  if let Some(parent) = cert_file.parent() {
     let _ = std::fs::create_dir_all(parent);
   }
 
-  let key_path = &config.ssl_key_file.display();
-  let cert_path = &config.ssl_cert_file.display();
+  let key_path = key_file.display();
+  let cert_path = cert_file.display();
 
   let command = format!(
     "openssl req -x509 -newkey rsa:4096 -keyout {key_path} -out {cert_path} -sha256 -days 3650 -nodes -subj \"/C=XX/CN=periphery\""
@@ -31,11 +248,16 @@ async fn generate_self_signed_ssl_certs() {
   let log = run_command::async_run_command(&command).await;
 
   if log.success() {
-    info!("✅ SSL Certs generated");
+    Ok(())
   } else {
-    panic!(
-      "🚨 Failed to generate SSL Certs | stdout: {} | stderr: {}",
-      log.stdout, log.stderr
-    );
+    Err(anyhow!("stdout: {} | stderr: {}", log.stdout, log.stderr))
   }
 }
+
+/// eg. `cert.pem` -> `cert.pem.new`
+fn with_extension_suffix(path: &Path, suffix: &str) -> PathBuf {
+  let mut path = path.as_os_str().to_owned();
+  path.push(".");
+  path.push(suffix);
+  path.into()
+}
@@ -1,8 +1,15 @@
-use std::{cmp::Ordering, sync::OnceLock};
+use std::{
+  cmp::Ordering,
+  collections::HashMap,
+  path::Path,
+  sync::OnceLock,
+  time::{Duration, Instant},
+};
 
 use async_timing_util::wait_until_timelength;
 use komodo_client::entities::stats::{
-  SingleDiskUsage, SystemInformation, SystemProcess, SystemStats,
+  LoadAverage, SingleDiskUsage, SingleNetworkInterfaceUsage,
+  SystemInformation, SystemProcess, SystemStats,
 };
 use sysinfo::{ProcessesToUpdate, System};
 use tokio::sync::RwLock;
@@ -45,6 +52,14 @@ pub struct StatsClient {
   system: sysinfo::System,
   disks: sysinfo::Disks,
   networks: sysinfo::Networks,
+  /// Interface name -> (rx_dropped, tx_dropped) totals,
+  /// (previous poll, latest poll)
+  network_drops: HashMap<String, ((u64, u64), (u64, u64))>,
+  /// When the handles were last refreshed
+  refreshed_at: Instant,
+  /// The time between the last two refreshes,
+  /// used to turn the disk io into throughput
+  refresh_elapsed: Duration,
 }
 
 const BYTES_PER_GB: f64 = 1073741824.0;
@@ -65,6 +80,9 @@ impl Default for StatsClient {
       system,
       disks,
       networks,
+      network_drops: Default::default(),
+      refreshed_at: Instant::now(),
+      refresh_elapsed: Duration::ZERO,
       stats,
     }
   }
@@ -77,27 +95,79 @@ impl StatsClient {
     self.system.refresh_processes(ProcessesToUpdate::All, true);
     self.disks.refresh(true);
     self.networks.refresh(true);
+    self.refresh_network_drops();
+    let now = Instant::now();
+    self.refresh_elapsed = now - self.refreshed_at;
+    self.refreshed_at = now;
+  }
+
+  /// Dropped packets are not reported by sysinfo,
+  /// so read the totals from sysfs.
+  fn refresh_network_drops(&mut self) {
+    let mut drops = HashMap::new();
+    for (name, _) in self.networks.iter() {
+      let Some(latest) = read_interface_drops(name) else {
+        continue;
+      };
+      let previous = self
+        .network_drops
+        .get(name)
+        .map(|(_, previous)| *previous)
+        .unwrap_or(latest);
+      drops.insert(name.clone(), (previous, latest));
+    }
+    self.network_drops = drops;
   }
 
   pub fn get_system_stats(&self) -> SystemStats {
     let total_mem = self.system.total_memory();
     let available_mem = self.system.available_memory();
+    let load_average = System::load_average();
 
     let mut network_ingress_bytes: u64 = 0;
     let mut network_egress_bytes: u64 = 0;
+    let mut network_usage_interface = Vec::new();
 
-    for (_, network) in self.networks.iter() {
+    for (name, network) in self.networks.iter() {
       network_ingress_bytes += network.received();
       network_egress_bytes += network.transmitted();
+      let (ingress_dropped, egress_dropped) = self
+        .network_drops
+        .get(name)
+        .map(|(previous, latest)| {
+          (
+            latest.0.saturating_sub(previous.0),
+            latest.1.saturating_sub(previous.1),
+          )
+        })
+        .unwrap_or_default();
+      network_usage_interface.push(SingleNetworkInterfaceUsage {
+        name: name.clone(),
+        ingress_bytes: network.received() as f64,
+        egress_bytes: network.transmitted() as f64,
+        ingress_errors: network.errors_on_received() as f64,
+        egress_errors: network.errors_on_transmitted() as f64,
+        ingress_dropped: ingress_dropped as f64,
+        egress_dropped: egress_dropped as f64,
+      });
     }
+    network_usage_interface.sort_by(|a, b| a.name.cmp(&b.name));
 
     SystemStats {
       cpu_perc: self.system.global_cpu_usage(),
       mem_free_gb: self.system.free_memory() as f64 / BYTES_PER_GB,
       mem_used_gb: (total_mem - available_mem) as f64 / BYTES_PER_GB,
       mem_total_gb: total_mem as f64 / BYTES_PER_GB,
+      swap_used_gb: self.system.used_swap() as f64 / BYTES_PER_GB,
+      swap_total_gb: self.system.total_swap() as f64 / BYTES_PER_GB,
+      load_average: LoadAverage {
+        one: load_average.one,
+        five: load_average.five,
+        fifteen: load_average.fifteen,
+      },
       network_ingress_bytes: network_ingress_bytes as f64,
       network_egress_bytes: network_egress_bytes as f64,
+      network_usage_interface,
       disks: self.get_disks(),
       polling_rate: self.stats.polling_rate,
       refresh_ts: self.stats.refresh_ts,
@@ -107,6 +177,7 @@ impl StatsClient {
 
   fn get_disks(&self) -> Vec<SingleDiskUsage> {
     let config = periphery_config();
+    let elapsed_secs = self.refresh_elapsed.as_secs_f64();
     self
       .disks
       .list()
@@ -136,11 +207,25 @@ impl StatsClient {
           disk.file_system().to_string_lossy().to_string();
         let disk_total = disk.total_space() as f64 / BYTES_PER_GB;
         let disk_free = disk.available_space() as f64 / BYTES_PER_GB;
+        let (inodes_used, inodes_total) =
+          read_inodes(disk.mount_point()).unwrap_or_default();
+        let usage = disk.usage();
+        let per_sec = |bytes: u64| {
+          if elapsed_secs > 0.0 {
+            bytes as f64 / elapsed_secs
+          } else {
+            0.0
+          }
+        };
         SingleDiskUsage {
           mount: disk.mount_point().to_owned(),
           used_gb: disk_total - disk_free,
           total_gb: disk_total,
           file_system,
+          inodes_used: inodes_used as i64,
+          inodes_total: inodes_total as i64,
+          read_bytes_per_sec: per_sec(usage.read_bytes),
+          write_bytes_per_sec: per_sec(usage.written_bytes),
         }
       })
       .collect()
@@ -186,6 +271,29 @@ impl StatsClient {
   }
 }
 
+/// Reads the (rx_dropped, tx_dropped) totals for the interface.
+/// Only available on Linux.
+fn read_interface_drops(interface: &str) -> Option<(u64, u64)> {
+  let read = |stat: &str| {
+    std::fs::read_to_string(format!(
+      "/sys/class/net/{interface}/statistics/{stat}"
+    ))
+    .ok()?
+    .trim()
+    .parse::<u64>()
+    .ok()
+  };
+  Some((read("rx_dropped")?, read("tx_dropped")?))
+}
+
+/// Reads the (used, total) inodes of the file system at the mount.
+/// Inodes are not reported by sysinfo.
+fn read_inodes(mount: &Path) -> Option<(u64, u64)> {
+  let stat = rustix::fs::statvfs(mount).ok()?;
+  let (total, free) = (stat.f_files, stat.f_ffree);
+  Some((total.saturating_sub(free), total))
+}
+
 fn get_system_information(
   sys: &sysinfo::System,
 ) -> SystemInformation {
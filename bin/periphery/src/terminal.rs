@@ -0,0 +1,239 @@
+use std::{
+  os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+  process::Stdio,
+};
+
+use anyhow::{Context, anyhow};
+use axum::{
+  extract::{
+    Query, WebSocketUpgrade,
+    ws::{Message, WebSocket},
+  },
+  http::StatusCode,
+  response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use komodo_client::{
+  entities::config::periphery::PeripheryCapability,
+  ws::TerminalControlMessage,
+};
+use periphery_client::api::{
+  DockerEndpointQuery, terminal::ContainerExecQuery,
+};
+use serror::AddStatusCode;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  process::{Child, Command},
+};
+
+use crate::{
+  capabilities::{self, Requirements},
+  docker::{
+    container_engine_cli, docker_host_args, get_docker_endpoint,
+    with_docker_endpoint,
+  },
+};
+
+pub async fn container_exec(
+  Query(query): Query<ContainerExecQuery>,
+  Query(DockerEndpointQuery { docker_endpoint }): Query<
+    DockerEndpointQuery,
+  >,
+  ws: WebSocketUpgrade,
+) -> serror::Result<Response> {
+  capabilities::check(
+    "ContainerExec",
+    Requirements {
+      write: true,
+      capabilities: &[PeripheryCapability::Terminal],
+    },
+  )
+  .status_code(StatusCode::FORBIDDEN)?;
+  let docker_endpoint =
+    get_docker_endpoint(docker_endpoint.as_deref())
+      .status_code(StatusCode::BAD_REQUEST)?;
+  Ok(ws.on_upgrade(move |socket| {
+    with_docker_endpoint(
+      docker_endpoint,
+      handle_container_exec(socket, query),
+    )
+  }))
+}
+
+async fn handle_container_exec(
+  socket: WebSocket,
+  ContainerExecQuery { container, shell }: ContainerExecQuery,
+) {
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+
+  let (master, mut child) =
+    match spawn_container_exec(&container, &shell) {
+      Ok(res) => res,
+      Err(e) => {
+        warn!("failed to exec into container {container} | {e:#}");
+        let _ = ws_sender
+          .send(Message::text(format!(
+            "failed to exec into container | {e:#}"
+          )))
+          .await;
+        let _ = ws_sender.close().await;
+        return;
+      }
+    };
+
+  let files = master.try_clone().and_then(|reader| {
+    Ok((
+      tokio::fs::File::from_std(reader.into()),
+      tokio::fs::File::from_std(master.try_clone()?.into()),
+    ))
+  });
+  let (mut reader, mut writer) = match files {
+    Ok(files) => files,
+    Err(e) => {
+      warn!("failed to clone pty fd | {e:?}");
+      let _ = child.start_kill();
+      let _ = ws_sender.close().await;
+      return;
+    }
+  };
+
+  // pty -> ws
+  let stdout = async {
+    let mut buf = [0u8; 8192];
+    loop {
+      match reader.read(&mut buf).await {
+        // The pty errors once the process exits
+        Ok(0) | Err(_) => break,
+        Ok(n) => {
+          if ws_sender
+            .send(Message::binary(buf[..n].to_vec()))
+            .await
+            .is_err()
+          {
+            break;
+          }
+        }
+      }
+    }
+  };
+
+  // ws -> pty
+  let stdin = async {
+    while let Some(Ok(msg)) = ws_receiver.next().await {
+      match msg {
+        Message::Binary(bytes) => {
+          if writer.write_all(&bytes).await.is_err()
+            || writer.flush().await.is_err()
+          {
+            break;
+          }
+        }
+        Message::Text(text) => {
+          match serde_json::from_str::<TerminalControlMessage>(
+            text.as_str(),
+          ) {
+            Ok(TerminalControlMessage::Resize { rows, cols }) => {
+              resize_pty(master.as_raw_fd(), rows, cols)
+            }
+            Err(e) => {
+              warn!("invalid terminal control message | {e:?}")
+            }
+          }
+        }
+        Message::Close(_) => break,
+        _ => {}
+      }
+    }
+  };
+
+  tokio::select! {
+    _ = stdout => {},
+    _ = stdin => {},
+    _ = child.wait() => {},
+  }
+
+  let _ = child.start_kill();
+  let _ = ws_sender.close().await;
+}
+
+/// Runs `docker exec -it` attached to a new pty,
+/// returning the pty master.
+fn spawn_container_exec(
+  container: &str,
+  shell: &str,
+) -> anyhow::Result<(OwnedFd, Child)> {
+  if container.is_empty() || container.starts_with('-') {
+    return Err(anyhow!("invalid container name: {container}"));
+  }
+  let (master, slave) = open_pty()?;
+  let mut command = Command::new(container_engine_cli());
+  command
+    .args(docker_host_args())
+    .args(["exec", "-it", "-e", "TERM=xterm-256color"])
+    .arg(container)
+    .arg(shell)
+    .stdin(Stdio::from(slave.try_clone()?))
+    .stdout(Stdio::from(slave.try_clone()?))
+    .stderr(Stdio::from(slave))
+    .kill_on_drop(true);
+  // SAFETY: Only async signal safe libc calls are made after the fork.
+  unsafe {
+    command.pre_exec(|| {
+      // Make the pty the controlling terminal of a new session,
+      // so the shell gets job control and signals like ctrl-c.
+      if libc::setsid() == -1
+        || libc::ioctl(0, libc::TIOCSCTTY, 0) == -1
+      {
+        return Err(std::io::Error::last_os_error());
+      }
+      Ok(())
+    });
+  }
+  // The command holds the parent's copies of the slave fd,
+  // they are dropped with it so the master sees the process exit.
+  let child =
+    command.spawn().context("failed to spawn docker exec")?;
+  Ok((master, child))
+}
+
+/// Returns the (master, slave) fds.
+fn open_pty() -> anyhow::Result<(OwnedFd, OwnedFd)> {
+  let mut master: RawFd = -1;
+  let mut slave: RawFd = -1;
+  let mut size = libc::winsize {
+    ws_row: 24,
+    ws_col: 80,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  };
+  // SAFETY: The fds are only wrapped after openpty succeeds.
+  unsafe {
+    if libc::openpty(
+      &mut master,
+      &mut slave,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      &mut size,
+    ) != 0
+    {
+      return Err(std::io::Error::last_os_error())
+        .context("failed to open pty");
+    }
+    // Don't leak the master into the child process
+    libc::fcntl(master, libc::F_SETFD, libc::FD_CLOEXEC);
+    Ok((OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)))
+  }
+}
+
+fn resize_pty(fd: RawFd, rows: u16, cols: u16) {
+  let size = libc::winsize {
+    ws_row: rows,
+    ws_col: cols,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  };
+  // SAFETY: fd is the open pty master.
+  unsafe {
+    libc::ioctl(fd, libc::TIOCSWINSZ, &size);
+  }
+}
@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use axum::response::IntoResponse;
+use futures::{SinkExt, StreamExt};
+use komodo_client::entities::komodo_timestamp;
+use periphery_client::{
+  auth::{NONCE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER},
+  tunnel::{
+    TUNNEL_PATH, TUNNEL_SERVER_HEADER, TunnelRequest, TunnelResponse,
+    sign_tunnel_connection,
+  },
+};
+use serror::serialize_error;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{
+  connect_async,
+  tungstenite::{
+    Message,
+    client::IntoClientRequest,
+    http::{HeaderValue, StatusCode},
+  },
+};
+use uuid::Uuid;
+
+use crate::{
+  config::periphery_config,
+  passkeys,
+  router::{handle_request, parse_request},
+};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// If `core_address` is configured, keeps a connection open to core,
+/// reconnecting whenever it is lost.
+pub fn spawn_core_connection() {
+  let config = periphery_config();
+  if config.core_address.is_empty() {
+    return;
+  }
+  if config.core_server.is_empty() {
+    warn!(
+      "core_address is configured without core_server, not connecting to core"
+    );
+    return;
+  }
+  tokio::spawn(async move {
+    loop {
+      match handle_core_connection().await {
+        Ok(()) => warn!("connection to core closed, reconnecting"),
+        Err(e) => {
+          warn!("connection to core failed, reconnecting | {e:#}")
+        }
+      }
+      tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+  });
+}
+
+async fn handle_core_connection() -> anyhow::Result<()> {
+  let config = periphery_config();
+  let url = format!(
+    "{}{TUNNEL_PATH}",
+    config
+      .core_address
+      .trim_end_matches('/')
+      .replacen("http", "ws", 1)
+  );
+  let mut req =
+    url.into_client_request().context("invalid core_address")?;
+  let timestamp = komodo_timestamp();
+  let nonce = Uuid::new_v4().simple().to_string();
+  let signature = sign_tunnel_connection(
+    &passkeys::core_passkey(),
+    &config.core_server,
+    timestamp,
+    &nonce,
+  );
+  let headers = req.headers_mut();
+  headers.insert(
+    TUNNEL_SERVER_HEADER,
+    HeaderValue::from_str(&config.core_server)
+      .context("invalid core_server")?,
+  );
+  headers.insert(TIMESTAMP_HEADER, HeaderValue::from(timestamp));
+  headers.insert(
+    NONCE_HEADER,
+    HeaderValue::from_str(&nonce).context("invalid nonce")?,
+  );
+  headers.insert(
+    SIGNATURE_HEADER,
+    HeaderValue::from_str(&signature).context("invalid signature")?,
+  );
+
+  let (socket, _) = connect_async(req)
+    .await
+    .context("failed to connect to core")?;
+  info!("connected to core at {}", config.core_address);
+
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+  let (sender, mut receiver) = mpsc::channel::<TunnelResponse>(100);
+
+  let responses = async {
+    while let Some(response) = receiver.recv().await {
+      let response = match serde_json::to_string(&response) {
+        Ok(response) => response,
+        Err(e) => {
+          warn!("failed to serialize response for core | {e:?}");
+          continue;
+        }
+      };
+      if ws_sender.send(Message::text(response)).await.is_err() {
+        break;
+      }
+    }
+  };
+
+  let requests = async {
+    while let Some(msg) = ws_receiver.next().await {
+      match msg.context("failed to read message from core")? {
+        Message::Text(text) => {
+          let request = match serde_json::from_str::<TunnelRequest>(
+            text.as_str(),
+          ) {
+            Ok(request) => request,
+            Err(e) => {
+              warn!("invalid request from core | {e:?}");
+              continue;
+            }
+          };
+          let sender = sender.clone();
+          // Requests run concurrently, same as over http
+          tokio::spawn(async move {
+            let _ =
+              sender.send(handle_tunnel_request(request).await).await;
+          });
+        }
+        Message::Close(_) => break,
+        _ => {}
+      }
+    }
+    anyhow::Ok(())
+  };
+
+  tokio::select! {
+    res = requests => res,
+    _ = responses => Ok(()),
+  }
+}
+
+async fn handle_tunnel_request(
+  TunnelRequest { id, request }: TunnelRequest,
+) -> TunnelResponse {
+  let (request, docker_endpoint) = match parse_request(request) {
+    Ok(request) => request,
+    Err(e) => {
+      return TunnelResponse {
+        id,
+        status: StatusCode::BAD_REQUEST.as_u16(),
+        body: serialize_error(&e),
+      };
+    }
+  };
+  let res = handle_request(request, docker_endpoint)
+    .await
+    .into_response();
+  let status = res.status().as_u16();
+  match axum::body::to_bytes(res.into_body(), usize::MAX).await {
+    Ok(body) => TunnelResponse {
+      id,
+      status,
+      body: String::from_utf8_lossy(&body).to_string(),
+    },
+    Err(e) => TunnelResponse {
+      id,
+      status: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+      body: serialize_error(
+        &anyhow::Error::from(e)
+          .context("failed to read response body"),
+      ),
+    },
+  }
+}
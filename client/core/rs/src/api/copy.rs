@@ -0,0 +1,37 @@
+//! # Copy files to / from containers
+//!
+//! The `docker cp` equivalent, at `/copy/deployment` and `/copy/stack`.
+//! These use the standard authentication headers, but not JSON bodies:
+//! - `GET` responds with a tar archive of the path in the container.
+//! - `POST` extracts the tar archive in the request body into the path
+//!   in the container, which must be an existing directory.
+//!
+//! Requires Write permission on the Deployment / Stack.
+//! Not available for servers using an outbound connection.
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+/// Query params to copy files to / from the Deployment container,
+/// at `/copy/deployment`.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentCopyQuery {
+  /// Id or name
+  pub deployment: String,
+  /// The path inside the container
+  pub path: String,
+}
+
+/// Query params to copy files to / from a Stack service container,
+/// at `/copy/stack`.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackCopyQuery {
+  /// Id or name
+  pub stack: String,
+  /// The service name
+  pub service: String,
+  /// The path inside the container
+  pub path: String,
+}
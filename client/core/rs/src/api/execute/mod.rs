@@ -114,6 +114,8 @@ pub enum Execution {
   PruneImages(PruneImages),
   DeleteVolume(DeleteVolume),
   PruneVolumes(PruneVolumes),
+  BackupVolume(BackupVolume),
+  RestoreVolume(RestoreVolume),
   PruneDockerBuilders(PruneDockerBuilders),
   PruneBuildx(PruneBuildx),
   PruneSystem(PruneSystem),
@@ -34,6 +34,10 @@ pub struct StartContainer {
   pub server: String,
   /// The container name
   pub container: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -60,6 +64,10 @@ pub struct RestartContainer {
   pub server: String,
   /// The container name
   pub container: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -86,6 +94,10 @@ pub struct PauseContainer {
   pub server: String,
   /// The container name
   pub container: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -114,6 +126,10 @@ pub struct UnpauseContainer {
   pub server: String,
   /// The container name
   pub container: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -144,6 +160,10 @@ pub struct StopContainer {
   pub signal: Option<TerminationSignal>,
   /// Override the default termination max time.
   pub time: Option<i32>,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -175,6 +195,10 @@ pub struct DestroyContainer {
   pub signal: Option<TerminationSignal>,
   /// Override the default termination max time.
   pub time: Option<i32>,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -197,6 +221,10 @@ pub struct DestroyContainer {
 pub struct StartAllContainers {
   /// Name or id
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -219,6 +247,10 @@ pub struct StartAllContainers {
 pub struct RestartAllContainers {
   /// Name or id
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -241,6 +273,10 @@ pub struct RestartAllContainers {
 pub struct PauseAllContainers {
   /// Name or id
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -263,6 +299,10 @@ pub struct PauseAllContainers {
 pub struct UnpauseAllContainers {
   /// Name or id
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -285,6 +325,10 @@ pub struct UnpauseAllContainers {
 pub struct StopAllContainers {
   /// Name or id
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -309,6 +353,10 @@ pub struct StopAllContainers {
 pub struct PruneContainers {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 // ============================
@@ -336,6 +384,10 @@ pub struct DeleteNetwork {
   pub server: String,
   /// The name of the network to delete.
   pub name: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -360,6 +412,10 @@ pub struct DeleteNetwork {
 pub struct PruneNetworks {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -385,6 +441,10 @@ pub struct DeleteImage {
   pub server: String,
   /// The name of the image to delete.
   pub name: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -409,6 +469,10 @@ pub struct DeleteImage {
 pub struct PruneImages {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -434,6 +498,10 @@ pub struct DeleteVolume {
   pub server: String,
   /// The name of the volume to delete.
   pub name: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -458,6 +526,85 @@ pub struct DeleteVolume {
 pub struct PruneVolumes {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
+}
+
+//
+
+/// Backs up a docker volume into a compressed archive
+/// in the periphery `backup_dir`, or `backup_s3` bucket.
+/// Response: [Update].
+///
+/// 1. Runs `tar -czf` against the volume in a `backup_image` container.
+/// 2. Uploads the archive to the `backup_s3` bucket, if configured.
+/// 3. Removes all but the most recent `keep` backups of the volume, if given.
+///
+/// Run this in a scheduled Procedure to back up volumes regularly.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  PartialEq,
+  Resolve,
+  EmptyTraits,
+  Parser,
+)]
+#[empty_traits(KomodoExecuteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct BackupVolume {
+  /// Id or name
+  pub server: String,
+  /// The name of the volume to back up.
+  pub volume: String,
+  /// The number of backups of the volume to keep.
+  /// If not given, all backups are kept.
+  pub keep: Option<u32>,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
+}
+
+//
+
+/// Restores a docker volume from a backup made with [BackupVolume].
+/// The current volume contents are replaced. Response: [Update].
+///
+/// Containers using the volume must be stopped first.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  PartialEq,
+  Resolve,
+  EmptyTraits,
+  Parser,
+)]
+#[empty_traits(KomodoExecuteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct RestoreVolume {
+  /// Id or name
+  pub server: String,
+  /// The name of the volume to restore.
+  pub volume: String,
+  /// The backup file name, from [ListDockerVolumeBackups].
+  /// If not given, the latest backup is restored.
+  ///
+  /// [ListDockerVolumeBackups]: crate::api::read::ListDockerVolumeBackups
+  pub backup: Option<String>,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -482,6 +629,10 @@ pub struct PruneVolumes {
 pub struct PruneDockerBuilders {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -506,6 +657,10 @@ pub struct PruneDockerBuilders {
 pub struct PruneBuildx {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
 
 //
@@ -530,4 +685,8 @@ pub struct PruneBuildx {
 pub struct PruneSystem {
   /// Id or name
   pub server: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
 }
@@ -51,6 +51,7 @@
 //! - [read]: Read only requests which retrieve data from Komodo.
 //! - [execute]: Run actions on Komodo resources, eg [execute::RunBuild].
 //! - [mod@write]: Requests which alter data, like create / update / delete resources.
+//! - [copy]: Copy files to / from containers as tar archives.
 //!
 //! ## Errors
 //!
@@ -67,6 +68,7 @@
 //! ```
 
 pub mod auth;
+pub mod copy;
 pub mod execute;
 pub mod read;
 pub mod user;
@@ -4,20 +4,23 @@ use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
 use crate::entities::{
-  I64, ResourceTarget, SearchCombinator, Timelength, U64,
+  FileContents, I64, ResourceTarget, SearchCombinator, Timelength,
+  U64,
   docker::{
     container::{Container, ContainerListItem},
+    disk_usage::DockerDiskUsage,
     image::{Image, ImageHistoryResponseItem, ImageListItem},
     network::{Network, NetworkListItem},
-    volume::{Volume, VolumeListItem},
+    volume::{Volume, VolumeBackup, VolumeListItem},
   },
   server::{
-    Server, ServerActionState, ServerListItem, ServerQuery,
-    ServerState,
+    HostFileEntry, Server, ServerActionState, ServerListItem,
+    ServerQuery, ServerState,
   },
   stack::ComposeProject,
   stats::{
-    SystemInformation, SystemProcess, SystemStats, SystemStatsRecord,
+    ContainerStatsRecord, DiskForecast, SystemInformation,
+    SystemProcess, SystemStats, SystemStatsRecord, SystemStatsRollup,
   },
   update::Log,
 };
@@ -151,7 +154,49 @@ pub struct GetPeripheryVersionResponse {
 
 //
 
+/// List the periphery version of every server the user has access to,
+/// compared against the core version.
+/// Response: [ListPeripheryVersionsResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListPeripheryVersionsResponse)]
+#[error(serror::Error)]
+pub struct ListPeripheryVersions {}
+
+/// Response for [ListPeripheryVersions].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListPeripheryVersionsResponse {
+  /// The version of core.
+  pub core_version: String,
+  /// The periphery version of each server.
+  pub servers: Vec<PeripheryVersionItem>,
+}
+
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeripheryVersionItem {
+  /// The server id
+  pub server_id: String,
+  /// The server name
+  pub server_name: String,
+  /// The server state
+  pub state: ServerState,
+  /// The version of periphery, or 'unknown' if it can't be reached.
+  pub version: String,
+  /// Whether the periphery version matches the core version.
+  /// False if the version is unknown.
+  pub matches_core: bool,
+}
+
+//
+
 /// List the docker networks on the server. Response: [ListDockerNetworksResponse].
+///
+/// Only lists from the default docker endpoint of the server.
 #[typeshare]
 #[derive(
   Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
@@ -193,6 +238,8 @@ pub type InspectDockerNetworkResponse = Network;
 
 /// List the docker images locally cached on the target server.
 /// Response: [ListDockerImagesResponse].
+///
+/// Only lists from the default docker endpoint of the server.
 #[typeshare]
 #[derive(
   Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
@@ -256,6 +303,8 @@ pub type ListDockerImageHistoryResponse =
 
 /// List all docker containers on the target server.
 /// Response: [ListDockerContainersResponse].
+///
+/// Includes the containers on all the docker endpoints of the server.
 #[typeshare]
 #[derive(
   Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
@@ -416,6 +465,8 @@ pub struct GetResourceMatchingContainerResponse {
 
 /// List all docker volumes on the target server.
 /// Response: [ListDockerVolumesResponse].
+///
+/// Only lists from the default docker endpoint of the server.
 #[typeshare]
 #[derive(
   Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
@@ -455,8 +506,78 @@ pub type InspectDockerVolumeResponse = Volume;
 
 //
 
+/// List the backups of a docker volume on the server, latest first.
+/// Response: [ListDockerVolumeBackupsResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListDockerVolumeBackupsResponse)]
+#[error(serror::Error)]
+pub struct ListDockerVolumeBackups {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// The volume name
+  pub volume: String,
+  /// The docker endpoint to target,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If not given, the default endpoint is used.
+  pub docker_endpoint: Option<String>,
+}
+
+#[typeshare]
+pub type ListDockerVolumeBackupsResponse = Vec<VolumeBackup>;
+
+//
+
+/// Get the disk space used by docker on the server, per image,
+/// container writable layer, volume and build cache record,
+/// like `docker system df -v`. Response: [DockerDiskUsage].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetDockerDiskUsageResponse)]
+#[error(serror::Error)]
+pub struct GetDockerDiskUsage {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+}
+
+#[typeshare]
+pub type GetDockerDiskUsageResponse = DockerDiskUsage;
+
+//
+
+/// List the names of the docker endpoints configured on the server's
+/// periphery, in addition to the default endpoint.
+/// Response: [ListDockerEndpointsResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListDockerEndpointsResponse)]
+#[error(serror::Error)]
+pub struct ListDockerEndpoints {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+}
+
+#[typeshare]
+pub type ListDockerEndpointsResponse = Vec<String>;
+
+//
+
 /// List all docker compose projects on the target server.
 /// Response: [ListComposeProjectsResponse].
+///
+/// Only lists from the default docker endpoint of the server.
 #[typeshare]
 #[derive(
   Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
@@ -543,6 +664,8 @@ pub type ListSystemProcessesResponse = Vec<SystemProcess>;
 //
 
 /// Paginated endpoint serving historical (timeseries) server stats for graphing.
+/// Pages older than the raw stats retention are served
+/// from the 5 minute or hourly rollups, merged into the granularity.
 /// Response: [GetHistoricalServerStatsResponse].
 #[typeshare]
 #[derive(
@@ -568,13 +691,126 @@ pub struct GetHistoricalServerStats {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetHistoricalServerStatsResponse {
   /// The timeseries page of data.
+  /// If served from rollups, these are the averages.
   pub stats: Vec<SystemStatsRecord>,
+  /// The min / avg / max rollups, if the page is served from rollups.
+  #[serde(default)]
+  pub rollups: Vec<SystemStatsRollup>,
   /// If there is a next page of data, pass this to `page` to get it.
   pub next_page: Option<u32>,
 }
 
 //
 
+/// Paginated endpoint serving historical (timeseries) container stats
+/// for a Deployment or Stack service.
+/// Response: [GetHistoricalContainerStatsResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetHistoricalContainerStatsResponse)]
+#[error(serror::Error)]
+pub struct GetHistoricalContainerStats {
+  /// The Deployment or Stack to get container stats for.
+  pub target: ResourceTarget,
+  /// The Stack service. Required when the target is a Stack.
+  pub service: Option<String>,
+  /// The granularity of the data.
+  pub granularity: Timelength,
+  /// Page of historical data. Default is 0, which is the most recent data.
+  /// Use with the `next_page` field of the response.
+  #[serde(default)]
+  pub page: u32,
+}
+
+/// Response to [GetHistoricalContainerStats].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetHistoricalContainerStatsResponse {
+  /// The timeseries page of data.
+  pub stats: Vec<ContainerStatsRecord>,
+  /// If there is a next page of data, pass this to `page` to get it.
+  pub next_page: Option<u32>,
+}
+
+//
+
+/// Forecast when each of the server's disks will be full,
+/// computed from the growth in disk usage over the last day
+/// of historical stats.
+/// Response: [GetServerDiskForecastResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetServerDiskForecastResponse)]
+#[error(serror::Error)]
+pub struct GetServerDiskForecast {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+}
+
+#[typeshare]
+pub type GetServerDiskForecastResponse = Vec<DiskForecast>;
+
+//
+
+/// List the files in a directory on the server host.
+/// Only directories inside the periphery `stack_dir`, `repo_dir`
+/// and `file_roots` can be listed.
+/// Requires Write permission on the server.
+/// Response: [ListServerFilesResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListServerFilesResponse)]
+#[error(serror::Error)]
+pub struct ListServerFiles {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// Absolute path of the directory.
+  /// If empty, lists the file roots.
+  #[serde(default)]
+  pub path: String,
+}
+
+#[typeshare]
+pub type ListServerFilesResponse = Vec<HostFileEntry>;
+
+//
+
+/// Get the contents of a text file on the server host.
+/// Only files inside the periphery `stack_dir`, `repo_dir`
+/// and `file_roots` can be read.
+/// Requires Write permission on the server.
+/// Response: [FileContents].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetServerFileResponse)]
+#[error(serror::Error)]
+pub struct GetServerFile {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub server: String,
+  /// Absolute path of the file
+  pub path: String,
+}
+
+#[typeshare]
+pub type GetServerFileResponse = FileContents;
+
+//
+
 /// Gets a summary of data relating to all servers.
 /// Response: [GetServersSummaryResponse].
 #[typeshare]
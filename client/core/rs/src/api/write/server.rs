@@ -107,3 +107,123 @@ pub struct CreateNetwork {
   /// The name of the network to create.
   pub name: String,
 }
+
+//
+
+/// Write a file on the server host, creating it and
+/// any missing parent directories.
+/// Only files inside the periphery `stack_dir`, `repo_dir`
+/// and `file_roots` can be written.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct WriteServerFile {
+  /// Server Id or name
+  pub server: String,
+  /// Absolute path of the file
+  pub path: String,
+  /// The contents to write.
+  pub contents: String,
+}
+
+//
+
+/// Delete a file or directory on the server host.
+/// Only files inside the periphery `stack_dir`, `repo_dir`
+/// and `file_roots` can be deleted, and not the roots themselves.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct DeleteServerFile {
+  /// Server Id or name
+  pub server: String,
+  /// Absolute path of the file or directory
+  pub path: String,
+}
+
+//
+
+/// Has Periphery generate a new self-signed ssl cert,
+/// and pins the new cert fingerprint on the Server.
+/// The new fingerprint is received over the connection
+/// verified with the currently pinned cert.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct RotateServerCertificate {
+  /// Server Id or name
+  pub server: String,
+}
+
+//
+
+/// Clears the pinned Periphery cert fingerprint on the Server,
+/// so the cert presented on the next connection is pinned instead.
+/// Use when the Periphery cert was replaced outside of Komodo.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct ResetServerCertificate {
+  /// Server Id or name
+  pub server: String,
+}
+
+//
+
+/// Rotates the passkey Core signs requests to the Server with.
+/// Periphery accepts both the previous and new passkey
+/// until Core first signs a request with the new passkey,
+/// so there is no downtime. After rotating, the passkeys
+/// in the Periphery config are no longer accepted.
+/// Servers using an outbound connection connect to Core
+/// with the new passkey from then on.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct RotateServerPasskey {
+  /// Server Id or name
+  pub server: String,
+}
+
+//
+
+/// Clears the passkey rotated for the Server,
+/// so Core uses the Core `passkey` for it again.
+/// Use when the Periphery rotated passkey file was lost.
+/// Response: [Update]
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Resolve, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(Update)]
+#[error(serror::Error)]
+pub struct ResetServerPasskey {
+  /// Server Id or name
+  pub server: String,
+}
@@ -121,6 +121,20 @@ pub enum AlertData {
     total_gb: f64,
   },
 
+  /// A server has high swap usage.
+  ServerSwap {
+    /// The id of the server
+    id: String,
+    /// The name of the server
+    name: String,
+    /// The region of the server
+    region: Option<String>,
+    /// The used swap in GB
+    used_gb: f64,
+    /// The total swap in GB
+    total_gb: f64,
+  },
+
   /// A server has high disk usage.
   ServerDisk {
     /// The id of the server
@@ -137,6 +151,56 @@ pub enum AlertData {
     total_gb: f64,
   },
 
+  /// A server disk is running out of inodes.
+  ServerDiskInodes {
+    /// The id of the server
+    id: String,
+    /// The name of the server
+    name: String,
+    /// The region of the server
+    region: Option<String>,
+    /// The mount path of the disk
+    path: PathBuf,
+    /// The used inodes on the disk
+    used: I64,
+    /// The total inodes on the disk
+    total: I64,
+  },
+
+  /// A server disk is forecast to be full soon.
+  ServerDiskForecast {
+    /// The id of the server
+    id: String,
+    /// The name of the server
+    name: String,
+    /// The region of the server
+    region: Option<String>,
+    /// The mount path of the disk
+    path: PathBuf,
+    /// The used portion of the disk in GB
+    used_gb: f64,
+    /// The total size of the disk in GB
+    total_gb: f64,
+    /// The growth rate of the used portion in GB per day
+    growth_gb_per_day: f64,
+    /// The forecast number of days until the disk is full
+    days_until_full: f64,
+  },
+
+  /// A server's periphery version does not match the core version.
+  PeripheryVersionMismatch {
+    /// The id of the server
+    id: String,
+    /// The name of the server
+    name: String,
+    /// The region of the server
+    region: Option<String>,
+    /// The version of periphery on the server
+    periphery_version: String,
+    /// The version of core
+    core_version: String,
+  },
+
   /// A container's state has changed unexpectedly.
   ContainerStateChange {
     /// The id of the deployment
@@ -153,6 +217,42 @@ pub enum AlertData {
     to: DeploymentState,
   },
 
+  /// A container is restarting repeatedly.
+  ContainerRestartLoop {
+    /// The id of the deployment or stack
+    id: String,
+    /// The name of the deployment or stack
+    name: String,
+    /// The server id of server that the container is on
+    server_id: String,
+    /// The server name
+    server_name: String,
+    /// The stack service, if the container belongs to a stack
+    service: Option<String>,
+    /// The container name
+    container: String,
+    /// The number of restarts observed in the window
+    restarts: I64,
+    /// The window length in minutes
+    window_minutes: I64,
+  },
+
+  /// A container was killed after running out of memory.
+  ContainerOomKilled {
+    /// The id of the deployment or stack
+    id: String,
+    /// The name of the deployment or stack
+    name: String,
+    /// The server id of server that the container is on
+    server_id: String,
+    /// The server name
+    server_name: String,
+    /// The stack service, if the container belongs to a stack
+    service: Option<String>,
+    /// The container name
+    container: String,
+  },
+
   /// A Deployment has an image update available
   DeploymentImageUpdateAvailable {
     /// The id of the deployment
@@ -260,6 +360,54 @@ pub enum AlertData {
     /// The name of the repo
     name: String,
   },
+
+  /// A procedure has failed
+  ProcedureFailed {
+    /// The id of the procedure
+    id: String,
+    /// The name of the procedure
+    name: String,
+  },
+
+  /// An action has failed
+  ActionFailed {
+    /// The id of the action
+    id: String,
+    /// The name of the action
+    name: String,
+  },
+
+  /// A resource sync has failed
+  ResourceSyncFailed {
+    /// The id of the resource sync
+    id: String,
+    /// The name of the resource sync
+    name: String,
+  },
+
+  /// A deployment has failed to deploy
+  DeployFailed {
+    /// The id of the deployment
+    id: String,
+    /// The name of the deployment
+    name: String,
+    /// The server id of server that the deployment is on
+    server_id: String,
+    /// The server name
+    server_name: String,
+  },
+
+  /// A stack has failed to deploy
+  StackDeployFailed {
+    /// The id of the stack
+    id: String,
+    /// The name of the stack
+    name: String,
+    /// The server id of server that the stack is on
+    server_id: String,
+    /// The server name
+    server_name: String,
+  },
 }
 
 impl Default for AlertData {
@@ -52,6 +52,8 @@ pub struct Env {
   pub komodo_first_server: Option<String>,
   /// Override `frontend_path`
   pub komodo_frontend_path: Option<String>,
+  /// Override `max_upload_size_mb`
+  pub komodo_max_upload_size_mb: Option<u64>,
   /// Override `jwt_secret`
   pub komodo_jwt_secret: Option<String>,
   /// Override `jwt_secret` from file
@@ -70,8 +72,30 @@ pub struct Env {
   pub komodo_monitoring_interval: Option<Timelength>,
   /// Override `keep_stats_for_days`
   pub komodo_keep_stats_for_days: Option<u64>,
+  /// Override `keep_five_minute_stats_for_days`
+  pub komodo_keep_five_minute_stats_for_days: Option<u64>,
+  /// Override `keep_hourly_stats_for_days`
+  pub komodo_keep_hourly_stats_for_days: Option<u64>,
   /// Override `keep_alerts_for_days`
   pub komodo_keep_alerts_for_days: Option<u64>,
+  /// Override `container_restart_alert_threshold`
+  pub komodo_container_restart_alert_threshold: Option<u64>,
+  /// Override `container_restart_alert_window`
+  pub komodo_container_restart_alert_window: Option<Timelength>,
+  /// Override `metrics_enabled`
+  pub komodo_metrics_enabled: Option<bool>,
+  /// Override `metrics_token`
+  pub komodo_metrics_token: Option<String>,
+  /// Override `metrics_token` with file
+  pub komodo_metrics_token_file: Option<PathBuf>,
+  /// Override `stats_export_format`
+  pub komodo_stats_export_format: Option<StatsExportFormat>,
+  /// Override `stats_export_url`
+  pub komodo_stats_export_url: Option<String>,
+  /// Override `stats_export_token`
+  pub komodo_stats_export_token: Option<String>,
+  /// Override `stats_export_token` with file
+  pub komodo_stats_export_token_file: Option<PathBuf>,
   /// Override `webhook_secret`
   pub komodo_webhook_secret: Option<String>,
   /// Override `webhook_secret` with file
@@ -216,6 +240,10 @@ pub struct Env {
   pub komodo_ssl_key_file: Option<PathBuf>,
   /// Override `ssl_cert_file`
   pub komodo_ssl_cert_file: Option<PathBuf>,
+  /// Override `periphery_client_cert_file`
+  pub komodo_periphery_client_cert_file: Option<PathBuf>,
+  /// Override `periphery_client_key_file`
+  pub komodo_periphery_client_key_file: Option<PathBuf>,
 }
 
 fn default_config_path() -> String {
@@ -277,6 +305,11 @@ pub struct CoreConfig {
   #[serde(default = "default_frontend_path")]
   pub frontend_path: String,
 
+  /// The maximum size in MB of archives uploaded into containers.
+  /// Default: 1024.
+  #[serde(default = "default_max_upload_size_mb")]
+  pub max_upload_size_mb: u64,
+
   /// Configure database connection
   #[serde(alias = "mongo")]
   pub database: DatabaseConfig,
@@ -414,12 +447,25 @@ pub struct CoreConfig {
   // ===========
   // = Pruning =
   // ===========
-  /// Number of days to keep stats, or 0 to disable pruning.
-  /// Stats older than this number of days are deleted on a daily cycle
+  /// Number of days to keep system and container stats, or 0 to disable pruning.
+  /// Stats older than this number of days are deleted on a daily cycle.
+  /// Older system stats are still served from the rollups.
   /// Default: 14
   #[serde(default = "default_prune_days")]
   pub keep_stats_for_days: u64,
 
+  /// Number of days to keep the 5 minute min / avg / max rollups
+  /// of the system stats, or 0 to disable pruning.
+  /// Default: 90
+  #[serde(default = "default_keep_five_minute_stats_for_days")]
+  pub keep_five_minute_stats_for_days: u64,
+
+  /// Number of days to keep the hourly min / avg / max rollups
+  /// of the system stats, or 0 to disable pruning.
+  /// Default: 365
+  #[serde(default = "default_keep_hourly_stats_for_days")]
+  pub keep_hourly_stats_for_days: u64,
+
   /// Number of days to keep alerts, or 0 to disable pruning.
   /// Alerts older than this number of days are deleted on a daily cycle
   /// Default: 14
@@ -440,6 +486,51 @@ pub struct CoreConfig {
   #[serde(default = "default_monitoring_interval")]
   pub monitoring_interval: Timelength,
 
+  // ====================
+  // = Container Alerts =
+  // ====================
+  /// Send a restart loop alert when a Deployment / Stack container
+  /// restarts at least this many times within `container_restart_alert_window`.
+  /// Set to 0 to disable restart loop alerts.
+  /// Default: 5
+  #[serde(default = "default_container_restart_alert_threshold")]
+  pub container_restart_alert_threshold: u64,
+
+  /// The window in which container restarts are counted.
+  /// Default: `15-min`
+  #[serde(default = "default_container_restart_alert_window")]
+  pub container_restart_alert_window: Timelength,
+
+  // ===========
+  // = Metrics =
+  // ===========
+  /// Serve Prometheus metrics at `/metrics`.
+  /// Default: false
+  #[serde(default)]
+  pub metrics_enabled: bool,
+
+  /// If set, scrapes of `/metrics` must pass
+  /// `Authorization: Bearer <metrics_token>`.
+  #[serde(default)]
+  pub metrics_token: String,
+
+  /// Push the server and container stats to an external
+  /// time series database on each monitoring tick.
+  /// Default: `none`
+  #[serde(default)]
+  pub stats_export_format: StatsExportFormat,
+
+  /// The url to push the stats to.
+  /// - otlp: The OTLP / HTTP metrics endpoint, eg `http://collector:4318/v1/metrics`
+  /// - influx: The line protocol write endpoint, eg `http://influxdb:8086/api/v2/write?org=komodo&bucket=komodo`
+  #[serde(default)]
+  pub stats_export_url: String,
+
+  /// If set, the stats are pushed with `Authorization: <stats_export_token>`.
+  /// Include the scheme, eg `Bearer <token>` or `Token <influx token>`.
+  #[serde(default)]
+  pub stats_export_token: String,
+
   // ===================
   // = Cloud Providers =
   // ===================
@@ -493,6 +584,17 @@ pub struct CoreConfig {
   #[serde(default = "default_ssl_cert_file")]
   pub ssl_cert_file: PathBuf,
 
+  /// Path to the client cert presented to Periphery,
+  /// for Periphery agents which require mutual TLS.
+  /// Default: none
+  #[serde(default)]
+  pub periphery_client_cert_file: Option<PathBuf>,
+
+  /// Path to the client key matching `periphery_client_cert_file`.
+  /// Default: none
+  #[serde(default)]
+  pub periphery_client_key_file: Option<PathBuf>,
+
   // =========
   // = Other =
   // =========
@@ -525,6 +627,10 @@ fn default_frontend_path() -> String {
   "/app/frontend".to_string()
 }
 
+fn default_max_upload_size_mb() -> u64 {
+  1024
+}
+
 fn default_jwt_ttl() -> Timelength {
   Timelength::OneDay
 }
@@ -548,6 +654,14 @@ fn default_prune_days() -> u64 {
   14
 }
 
+fn default_keep_five_minute_stats_for_days() -> u64 {
+  90
+}
+
+fn default_keep_hourly_stats_for_days() -> u64 {
+  365
+}
+
 fn default_poll_interval() -> Timelength {
   Timelength::FiveMinutes
 }
@@ -556,6 +670,37 @@ fn default_monitoring_interval() -> Timelength {
   Timelength::FifteenSeconds
 }
 
+fn default_container_restart_alert_threshold() -> u64 {
+  5
+}
+
+fn default_container_restart_alert_window() -> Timelength {
+  Timelength::FifteenMinutes
+}
+
+/// The format to push stats in.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Hash,
+  Serialize,
+  Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsExportFormat {
+  /// Don't export stats
+  #[default]
+  None,
+  /// OTLP / HTTP metrics, JSON encoded
+  Otlp,
+  /// Influx line protocol
+  Influx,
+}
+
 fn default_ssl_key_file() -> PathBuf {
   "/config/ssl/key.pem".parse().unwrap()
 }
@@ -574,6 +719,7 @@ impl CoreConfig {
       passkey: empty_or_redacted(&config.passkey),
       first_server: config.first_server,
       frontend_path: config.frontend_path,
+      max_upload_size_mb: config.max_upload_size_mb,
       jwt_secret: empty_or_redacted(&config.jwt_secret),
       jwt_ttl: config.jwt_ttl,
       repo_directory: config.repo_directory,
@@ -582,7 +728,21 @@ impl CoreConfig {
       resource_poll_interval: config.resource_poll_interval,
       monitoring_interval: config.monitoring_interval,
       keep_stats_for_days: config.keep_stats_for_days,
+      keep_five_minute_stats_for_days: config
+        .keep_five_minute_stats_for_days,
+      keep_hourly_stats_for_days: config.keep_hourly_stats_for_days,
       keep_alerts_for_days: config.keep_alerts_for_days,
+      container_restart_alert_threshold: config
+        .container_restart_alert_threshold,
+      container_restart_alert_window: config
+        .container_restart_alert_window,
+      metrics_enabled: config.metrics_enabled,
+      metrics_token: empty_or_redacted(&config.metrics_token),
+      stats_export_format: config.stats_export_format,
+      stats_export_url: config.stats_export_url,
+      stats_export_token: empty_or_redacted(
+        &config.stats_export_token,
+      ),
       logging: config.logging,
       transparent_mode: config.transparent_mode,
       ui_write_disabled: config.ui_write_disabled,
@@ -664,6 +824,8 @@ impl CoreConfig {
       ssl_enabled: config.ssl_enabled,
       ssl_key_file: config.ssl_key_file,
       ssl_cert_file: config.ssl_cert_file,
+      periphery_client_cert_file: config.periphery_client_cert_file,
+      periphery_client_key_file: config.periphery_client_key_file,
     }
   }
 }
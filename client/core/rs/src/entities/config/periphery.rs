@@ -118,10 +118,38 @@ pub struct Env {
   pub periphery_repo_dir: Option<PathBuf>,
   /// Override `stack_dir`
   pub periphery_stack_dir: Option<PathBuf>,
+  /// Override `backup_dir`
+  pub periphery_backup_dir: Option<PathBuf>,
+  /// Override `backup_image`
+  pub periphery_backup_image: Option<String>,
+  /// Override `backup_s3.endpoint`
+  pub periphery_backup_s3_endpoint: Option<String>,
+  /// Override `backup_s3.region`
+  pub periphery_backup_s3_region: Option<String>,
+  /// Override `backup_s3.bucket`
+  pub periphery_backup_s3_bucket: Option<String>,
+  /// Override `backup_s3.prefix`
+  pub periphery_backup_s3_prefix: Option<String>,
+  /// Override `backup_s3.access_key_id`
+  pub periphery_backup_s3_access_key_id: Option<String>,
+  /// Override `backup_s3.access_key_id` with file
+  pub periphery_backup_s3_access_key_id_file: Option<PathBuf>,
+  /// Override `backup_s3.secret_access_key`
+  pub periphery_backup_s3_secret_access_key: Option<String>,
+  /// Override `backup_s3.secret_access_key` with file
+  pub periphery_backup_s3_secret_access_key_file: Option<PathBuf>,
+  /// Override `file_roots`
+  pub periphery_file_roots: Option<Vec<PathBuf>>,
+  /// Override `max_upload_size_mb`
+  pub periphery_max_upload_size_mb: Option<u64>,
   /// Override `stats_polling_rate`
   pub periphery_stats_polling_rate: Option<Timelength>,
   /// Override `legacy_compose_cli`
   pub periphery_legacy_compose_cli: Option<bool>,
+  /// Override `container_engine`
+  pub periphery_container_engine: Option<ContainerEngine>,
+  /// Override `container_socket`
+  pub periphery_container_socket: Option<PathBuf>,
 
   // LOGGING
   /// Override `logging.level`
@@ -139,17 +167,41 @@ pub struct Env {
   pub periphery_passkeys: Option<Vec<String>>,
   /// Override `passkeys` from file
   pub periphery_passkeys_file: Option<PathBuf>,
+  /// Override `allow_unsigned_requests`
+  pub periphery_allow_unsigned_requests: Option<bool>,
+  /// Override `rotated_passkey_file`
+  pub periphery_rotated_passkey_file: Option<PathBuf>,
+  /// Override `read_only`
+  pub periphery_read_only: Option<bool>,
+  /// Override `disabled_capabilities`
+  pub periphery_disabled_capabilities:
+    Option<Vec<PeripheryCapability>>,
+  /// Override `allowed_commands`
+  pub periphery_allowed_commands: Option<Vec<String>>,
   /// Override `include_disk_mounts`
   pub periphery_include_disk_mounts: Option<Vec<PathBuf>>,
   /// Override `exclude_disk_mounts`
   pub periphery_exclude_disk_mounts: Option<Vec<PathBuf>>,
 
+  /// Override `core_address`
+  pub periphery_core_address: Option<String>,
+  /// Override `core_server`
+  pub periphery_core_server: Option<String>,
+  /// Override `core_passkey`
+  pub periphery_core_passkey: Option<String>,
+  /// Override `core_passkey` from file
+  pub periphery_core_passkey_file: Option<PathBuf>,
+
   /// Override `ssl_enabled`
   pub periphery_ssl_enabled: Option<bool>,
   /// Override `ssl_key_file`
   pub periphery_ssl_key_file: Option<PathBuf>,
   /// Override `ssl_cert_file`
   pub periphery_ssl_cert_file: Option<PathBuf>,
+  /// Override `ssl_client_ca_file`
+  pub periphery_ssl_client_ca_file: Option<PathBuf>,
+  /// Override `ssl_client_fingerprints`
+  pub periphery_ssl_client_fingerprints: Option<Vec<String>>,
 }
 
 /// # Periphery Configuration File
@@ -172,6 +224,38 @@ pub struct PeripheryConfig {
   #[serde(default = "default_stack_dir")]
   pub stack_dir: PathBuf,
 
+  /// The system directory where volume backups will be stored,
+  /// in a folder for each docker endpoint and volume. Mount a network share here
+  /// to store the backups on another host.
+  /// When `backup_s3` is configured, archives are only staged here.
+  /// Default: `/etc/komodo/backups`
+  #[serde(default = "default_backup_dir")]
+  pub backup_dir: PathBuf,
+
+  /// The image used to run `tar` against the volume
+  /// when backing up / restoring it.
+  /// Default: `alpine`
+  #[serde(default = "default_backup_image")]
+  pub backup_image: String,
+
+  /// Store the volume backups in an S3 compatible bucket,
+  /// such as AWS S3 or MinIO, rather than in `backup_dir`.
+  /// Used when `bucket` is set.
+  #[serde(default)]
+  pub backup_s3: BackupS3Config,
+
+  /// Extra directories the host file browser can access,
+  /// in addition to `repo_dir` and `stack_dir`.
+  /// Default: none
+  #[serde(default)]
+  pub file_roots: Vec<PathBuf>,
+
+  /// The maximum size in MB of archives uploaded into containers.
+  /// Other requests are limited to 2 MB.
+  /// Default: `1024`
+  #[serde(default = "default_max_upload_size_mb")]
+  pub max_upload_size_mb: u64,
+
   /// The rate at which the system stats will be polled to update the cache.
   /// Default: `5-sec`
   #[serde(default = "default_stats_polling_rate")]
@@ -183,6 +267,24 @@ pub struct PeripheryConfig {
   #[serde(default)]
   pub legacy_compose_cli: bool,
 
+  /// The container engine used to manage containers.
+  /// Podman is used through its Docker compatible socket and `podman compose`.
+  /// Default: `docker`
+  #[serde(default)]
+  pub container_engine: ContainerEngine,
+
+  /// The path to the container engine api socket.
+  /// Default: `DOCKER_HOST` or `/var/run/docker.sock` for Docker,
+  /// `/run/podman/podman.sock` for Podman.
+  pub container_socket: Option<PathBuf>,
+
+  /// Additional container engine endpoints, eg. a rootless socket
+  /// or a remote engine. Deployments and Stacks select one by name,
+  /// otherwise they use the engine at `container_socket`.
+  /// Default: none
+  #[serde(default, alias = "docker_endpoint")]
+  pub docker_endpoints: Vec<DockerEndpoint>,
+
   /// Logging configuration
   #[serde(default)]
   pub logging: LogConfig,
@@ -201,6 +303,38 @@ pub struct PeripheryConfig {
   #[serde(default)]
   pub passkeys: Vec<String>,
 
+  /// Also accept requests sending the passkey itself
+  /// in the `authorization` header, rather than signing with it.
+  /// Only enable while Core is older than Periphery.
+  /// Default: false
+  #[serde(default)]
+  pub allow_unsigned_requests: bool,
+
+  /// Where the passkey rotated by Core is stored.
+  /// Once Core rotates the passkey, the configured `passkeys`
+  /// are no longer accepted.
+  /// Default: `/etc/komodo/rotated_passkey.json`
+  #[serde(default = "default_rotated_passkey_file")]
+  pub rotated_passkey_file: PathBuf,
+
+  /// Only allow requests which observe the host,
+  /// rejecting any which change it (deploys, builds, commands, terminals...).
+  /// Default: false
+  #[serde(default)]
+  pub read_only: bool,
+
+  /// Reject the requests in these categories.
+  /// Default: none
+  #[serde(default)]
+  pub disabled_capabilities: Vec<PeripheryCapability>,
+
+  /// If non-empty, `RunCommand` only runs commands
+  /// matching one of these wildcard patterns.
+  /// Commands using shell control characters are rejected.
+  /// Default: none
+  #[serde(default)]
+  pub allowed_commands: Vec<String>,
+
   /// If non-empty, only includes specific mount paths in the disk report.
   #[serde(default)]
   pub include_disk_mounts: Vec<PathBuf>,
@@ -224,6 +358,29 @@ pub struct PeripheryConfig {
   #[serde(default, alias = "docker_registry")]
   pub docker_registries: Vec<DockerRegistry>,
 
+  /// The address of Komodo Core, eg. `https://komodo.example.com`.
+  /// If set, periphery opens an outbound connection to Core,
+  /// for hosts which can't accept inbound connections.
+  /// The Server must have `outbound` enabled.
+  /// Container terminals, following logs, copying files and
+  /// Docker events are not available over the connection.
+  /// Default: empty (disabled)
+  #[serde(default)]
+  pub core_address: String,
+
+  /// The id or name of the Server in Core this periphery connects as.
+  /// Only used with `core_address`.
+  #[serde(default)]
+  pub core_server: String,
+
+  /// The passkey to authenticate the first connection with Core,
+  /// matching the Core `passkey`. Core then assigns the Server
+  /// a passkey of its own, stored in `rotated_passkey_file`,
+  /// and no longer accepts this one for the Server.
+  /// Only used with `core_address`.
+  #[serde(default)]
+  pub core_passkey: String,
+
   /// Whether to enable ssl.
   /// Default: false (will change in later release)
   #[serde(default = "default_ssl_enabled")]
@@ -238,6 +395,22 @@ pub struct PeripheryConfig {
   /// Default: `/etc/komodo/ssl/periphery/cert.pem`.
   #[serde(default = "default_ssl_cert_file")]
   pub ssl_cert_file: PathBuf,
+
+  /// Require Core to present a client certificate
+  /// signed by the CA in this file (mutual TLS).
+  /// Only used with `ssl_enabled`.
+  /// Default: none
+  #[serde(default)]
+  pub ssl_client_ca_file: Option<PathBuf>,
+
+  /// Require Core to present a client certificate
+  /// with one of these sha256 fingerprints (mutual TLS).
+  /// Can be combined with `ssl_client_ca_file`,
+  /// in which case either is accepted.
+  /// Only used with `ssl_enabled`.
+  /// Default: none
+  #[serde(default)]
+  pub ssl_client_fingerprints: Vec<String>,
 }
 
 fn default_periphery_port() -> u16 {
@@ -252,10 +425,26 @@ fn default_stack_dir() -> PathBuf {
   "/etc/komodo/stacks".parse().unwrap()
 }
 
+fn default_backup_dir() -> PathBuf {
+  "/etc/komodo/backups".parse().unwrap()
+}
+
+fn default_backup_image() -> String {
+  String::from("alpine")
+}
+
+fn default_max_upload_size_mb() -> u64 {
+  1024
+}
+
 fn default_stats_polling_rate() -> Timelength {
   Timelength::FiveSeconds
 }
 
+fn default_rotated_passkey_file() -> PathBuf {
+  "/etc/komodo/rotated_passkey.json".parse().unwrap()
+}
+
 fn default_ssl_enabled() -> bool {
   false
 }
@@ -274,19 +463,37 @@ impl Default for PeripheryConfig {
       port: default_periphery_port(),
       repo_dir: default_repo_dir(),
       stack_dir: default_stack_dir(),
+      backup_dir: default_backup_dir(),
+      backup_image: default_backup_image(),
+      backup_s3: Default::default(),
+      file_roots: Default::default(),
+      max_upload_size_mb: default_max_upload_size_mb(),
       stats_polling_rate: default_stats_polling_rate(),
       legacy_compose_cli: Default::default(),
+      container_engine: Default::default(),
+      container_socket: Default::default(),
+      docker_endpoints: Default::default(),
       logging: Default::default(),
       allowed_ips: Default::default(),
       passkeys: Default::default(),
+      allow_unsigned_requests: Default::default(),
+      rotated_passkey_file: default_rotated_passkey_file(),
+      read_only: Default::default(),
+      disabled_capabilities: Default::default(),
+      allowed_commands: Default::default(),
       include_disk_mounts: Default::default(),
       exclude_disk_mounts: Default::default(),
       secrets: Default::default(),
       git_providers: Default::default(),
       docker_registries: Default::default(),
+      core_address: Default::default(),
+      core_server: Default::default(),
+      core_passkey: Default::default(),
       ssl_enabled: default_ssl_enabled(),
       ssl_key_file: default_ssl_key_file(),
       ssl_cert_file: default_ssl_cert_file(),
+      ssl_client_ca_file: Default::default(),
+      ssl_client_fingerprints: Default::default(),
     }
   }
 }
@@ -297,8 +504,24 @@ impl PeripheryConfig {
       port: self.port,
       repo_dir: self.repo_dir.clone(),
       stack_dir: self.stack_dir.clone(),
+      backup_dir: self.backup_dir.clone(),
+      backup_image: self.backup_image.clone(),
+      backup_s3: BackupS3Config {
+        access_key_id: empty_or_redacted(
+          &self.backup_s3.access_key_id,
+        ),
+        secret_access_key: empty_or_redacted(
+          &self.backup_s3.secret_access_key,
+        ),
+        ..self.backup_s3.clone()
+      },
+      file_roots: self.file_roots.clone(),
+      max_upload_size_mb: self.max_upload_size_mb,
       stats_polling_rate: self.stats_polling_rate,
       legacy_compose_cli: self.legacy_compose_cli,
+      container_engine: self.container_engine,
+      container_socket: self.container_socket.clone(),
+      docker_endpoints: self.docker_endpoints.clone(),
       logging: self.logging.clone(),
       allowed_ips: self.allowed_ips.clone(),
       passkeys: self
@@ -306,6 +529,11 @@ impl PeripheryConfig {
         .iter()
         .map(|passkey| empty_or_redacted(passkey))
         .collect(),
+      allow_unsigned_requests: self.allow_unsigned_requests,
+      rotated_passkey_file: self.rotated_passkey_file.clone(),
+      read_only: self.read_only,
+      disabled_capabilities: self.disabled_capabilities.clone(),
+      allowed_commands: self.allowed_commands.clone(),
       include_disk_mounts: self.include_disk_mounts.clone(),
       exclude_disk_mounts: self.exclude_disk_mounts.clone(),
       secrets: self
@@ -347,9 +575,87 @@ impl PeripheryConfig {
             .collect(),
         })
         .collect(),
+      core_address: self.core_address.clone(),
+      core_server: self.core_server.clone(),
+      core_passkey: empty_or_redacted(&self.core_passkey),
       ssl_enabled: self.ssl_enabled,
       ssl_key_file: self.ssl_key_file.clone(),
       ssl_cert_file: self.ssl_cert_file.clone(),
+      ssl_client_ca_file: self.ssl_client_ca_file.clone(),
+      ssl_client_fingerprints: self.ssl_client_fingerprints.clone(),
     }
   }
 }
+
+/// The container engine Periphery manages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerEngine {
+  #[default]
+  Docker,
+  Podman,
+}
+
+/// An S3 compatible bucket to store the volume backups in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct BackupS3Config {
+  /// The S3 api endpoint, eg. `http://minio:9000`.
+  /// Empty for AWS S3.
+  #[serde(default)]
+  pub endpoint: String,
+  /// The bucket region.
+  /// Default: `us-east-1`
+  #[serde(default)]
+  pub region: String,
+  /// The bucket to store the backups in.
+  /// Backups are stored on the host when empty.
+  #[serde(default)]
+  pub bucket: String,
+  /// Prefix for the backup keys, which are
+  /// `{prefix}{endpoint}/{volume}/{timestamp}.tar.gz`.
+  /// Required when `bucket` is set.
+  /// Use a different prefix for each Server sharing the bucket.
+  #[serde(default)]
+  pub prefix: String,
+  /// The access key id.
+  #[serde(default)]
+  pub access_key_id: String,
+  /// The secret access key.
+  #[serde(default)]
+  pub secret_access_key: String,
+}
+
+/// A container engine endpoint in addition to the default one.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DockerEndpoint {
+  /// The name Deployments and Stacks use to select the endpoint.
+  pub name: String,
+  /// The engine api host, eg. `unix:///run/user/1000/docker.sock`
+  /// or `tcp://10.0.0.5:2375`.
+  pub host: String,
+}
+
+/// Categories of Periphery requests which can be disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeripheryCapability {
+  /// Run arbitrary shell commands (`RunCommand`).
+  /// Also required to clone / pull repos with `on_clone` / `on_pull`
+  /// (including build `pre_build`), and deploy stacks with
+  /// `pre_deploy` / `post_deploy` commands.
+  RunCommand,
+  /// Open terminals into containers.
+  Terminal,
+  /// Build images.
+  Build,
+  /// Clone / pull / delete repos.
+  Git,
+  /// Deploy and manage compose stacks.
+  Compose,
+  /// Deploy and manage containers, networks, images and volumes.
+  Container,
+  /// Browse, read and write files on the host.
+  Files,
+  /// Delete, remove and prune anything.
+  Destructive,
+}
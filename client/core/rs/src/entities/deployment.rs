@@ -63,6 +63,13 @@ pub struct DeploymentConfig {
   #[builder(default)]
   pub server_id: String,
 
+  /// The docker endpoint on the server to deploy on,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If empty, uses the default endpoint.
+  #[serde(default)]
+  #[builder(default)]
+  pub docker_endpoint: String,
+
   /// The image which the deployment deploys.
   /// Can either be a user inputted image, or a Komodo Build.
   #[serde(default)]
@@ -84,6 +91,14 @@ pub struct DeploymentConfig {
   #[builder(default)]
   pub skip_secret_interp: bool,
 
+  /// Create the container through the Docker Engine API,
+  /// rather than the `docker run` command.
+  /// The config is validated before anything is created,
+  /// and no shell is involved. `extra_args` are not supported.
+  #[serde(default)]
+  #[builder(default)]
+  pub engine_api: bool,
+
   /// Whether to redeploy the deployment whenever the attached build finishes.
   #[serde(default)]
   #[builder(default)]
@@ -226,11 +241,13 @@ impl Default for DeploymentConfig {
   fn default() -> Self {
     Self {
       server_id: Default::default(),
+      docker_endpoint: Default::default(),
       send_alerts: default_send_alerts(),
       links: Default::default(),
       image: Default::default(),
       image_registry_account: Default::default(),
       skip_secret_interp: Default::default(),
+      engine_api: Default::default(),
       redeploy_on_build: Default::default(),
       poll_for_updates: Default::default(),
       auto_update: Default::default(),
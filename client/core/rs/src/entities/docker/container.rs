@@ -15,6 +15,10 @@ use super::{ContainerConfig, GraphDriverData, PortBinding};
 pub struct ContainerListItem {
   /// The Server which holds the container.
   pub server_id: Option<String>,
+  /// The periphery docker endpoint which runs the container.
+  /// Empty for the default endpoint.
+  #[serde(default)]
+  pub docker_endpoint: String,
   /// The first name in Names, not including the initial '/'
   pub name: String,
   /// The ID of this container
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::I64;
+
+use super::container::ContainerStateStatusEnum;
+
+/// The disk space used by docker, like `docker system df -v`.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct DockerDiskUsage {
+  /// Totals for the images
+  pub images: DiskUsageSummary,
+  /// Totals for the container writable layers
+  pub containers: DiskUsageSummary,
+  /// Totals for the volumes
+  pub volumes: DiskUsageSummary,
+  /// Totals for the build cache
+  pub build_cache: DiskUsageSummary,
+  /// The space used by each image
+  pub image_usage: Vec<ImageDiskUsage>,
+  /// The space used by each container writable layer
+  pub container_usage: Vec<ContainerDiskUsage>,
+  /// The space used by each volume
+  pub volume_usage: Vec<VolumeDiskUsage>,
+  /// The space used by each build cache record
+  pub build_cache_usage: Vec<BuildCacheDiskUsage>,
+}
+
+/// A row of the `docker system df` summary.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct DiskUsageSummary {
+  /// The number of items
+  pub total: I64,
+  /// The number of items in use
+  pub active: I64,
+  /// The space used in bytes
+  pub size: I64,
+  /// The space which can be freed by pruning in bytes
+  pub reclaimable: I64,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct ImageDiskUsage {
+  /// The first tag in `repo_tags`, or Id if no tags.
+  pub name: String,
+  pub id: String,
+  /// Date and time at which the image was created as a Unix timestamp (number of seconds sinds EPOCH).
+  pub created: I64,
+  /// Total size of the image including all layers it is composed of.
+  pub size: I64,
+  /// The size of the layers shared with other images.
+  pub shared_size: I64,
+  /// The size of the layers only used by this image.
+  pub unique_size: I64,
+  /// The number of containers using the image.
+  pub containers: I64,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct ContainerDiskUsage {
+  pub name: String,
+  pub id: Option<String>,
+  pub image: Option<String>,
+  pub state: ContainerStateStatusEnum,
+  /// The size of files that have been created or changed by this container.
+  pub size_rw: I64,
+  /// The total size of all the files in this container.
+  pub size_root_fs: I64,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct VolumeDiskUsage {
+  pub name: String,
+  pub driver: String,
+  /// Amount of disk space used by the volume (in bytes). Only available for volumes using the `local` driver, otherwise `-1`.
+  pub size: I64,
+  /// The number of containers referencing this volume.
+  pub ref_count: I64,
+}
+
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct BuildCacheDiskUsage {
+  pub id: String,
+  /// The cache record type, eg. `regular`, `source.local`, `exec.cachemount`
+  pub typ: String,
+  pub description: String,
+  pub in_use: bool,
+  /// Whether the cache record is shared with other records.
+  pub shared: bool,
+  /// Amount of disk space used by the cache record (in bytes).
+  pub size: I64,
+  /// Date and time at which the cache record was last used, in RFC 3339 format.
+  pub last_used_at: Option<String>,
+  pub usage_count: I64,
+}
@@ -6,6 +6,7 @@ use typeshare::typeshare;
 use super::I64;
 
 pub mod container;
+pub mod disk_usage;
 pub mod image;
 pub mod network;
 pub mod volume;
@@ -24,6 +24,20 @@ pub struct VolumeListItem {
   pub in_use: bool,
 }
 
+/// A volume backup archive in the periphery backup storage.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+pub struct VolumeBackup {
+  /// The archive file name. Pass this to restore the backup.
+  pub name: String,
+  /// The size of the archive in bytes.
+  pub size: U64,
+  /// When the backup was created, in unix milliseconds.
+  pub created_at: I64,
+}
+
 #[typeshare]
 #[derive(
   Debug, Clone, Default, PartialEq, Serialize, Deserialize,
@@ -662,9 +662,17 @@ pub enum Operation {
   PruneImages,
   DeleteVolume,
   PruneVolumes,
+  BackupVolume,
+  RestoreVolume,
   PruneDockerBuilders,
   PruneBuildx,
   PruneSystem,
+  WriteServerFile,
+  DeleteServerFile,
+  RotateServerCertificate,
+  ResetServerCertificate,
+  RotateServerPasskey,
+  ResetServerPasskey,
 
   // stack
   CreateStack,
@@ -691,6 +699,9 @@ pub enum Operation {
   UnpauseStackService,
   StopStackService,
   DestroyStackService,
+  ExecStackService,
+  DownloadFromStackService,
+  UploadToStackService,
 
   // deployment
   CreateDeployment,
@@ -705,6 +716,9 @@ pub enum Operation {
   UnpauseDeployment,
   StopDeployment,
   DestroyDeployment,
+  ExecDeployment,
+  DownloadFromDeployment,
+  UploadToDeployment,
 
   // build
   CreateBuild,
@@ -16,7 +16,55 @@ use super::{
 };
 
 #[typeshare]
-pub type Server = Resource<ServerConfig, ()>;
+pub type Server = Resource<ServerConfig, ServerInfo>;
+
+/// Server info, managed by Core rather than configured.
+#[typeshare]
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ServerInfo {
+  /// The sha256 fingerprint of the Periphery ssl cert,
+  /// pinned on first connection. Requests are rejected
+  /// if Periphery presents a different cert.
+  /// Empty until pinned, or for Periphery not using ssl.
+  pub certificate_fingerprint: String,
+  /// The passkey rotated by Core for this Server,
+  /// used to sign requests in place of the Core `passkey`.
+  /// Empty until rotated. Redacted in responses.
+  pub passkey: String,
+}
+
+impl ServerInfo {
+  /// Prepares the info for transport by redacting the passkey.
+  /// The length is kept to show whether it was rotated.
+  pub fn sanitize(&mut self) {
+    self.passkey = "#".repeat(self.passkey.len());
+  }
+}
+
+impl<'de> Deserialize<'de> for ServerInfo {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    #[derive(Deserialize)]
+    struct ServerInfoInner {
+      #[serde(default)]
+      certificate_fingerprint: String,
+      #[serde(default)]
+      passkey: String,
+    }
+    // Servers created before the info was added store null.
+    let info = Option::<ServerInfoInner>::deserialize(deserializer)?;
+    Ok(
+      info
+        .map(|info| ServerInfo {
+          certificate_fingerprint: info.certificate_fingerprint,
+          passkey: info.passkey,
+        })
+        .unwrap_or_default(),
+    )
+  }
+}
 
 #[typeshare]
 pub type ServerListItem = ResourceListItem<ServerListItemInfo>;
@@ -56,6 +104,20 @@ pub struct ServerConfig {
   #[partial_default(default_address())]
   pub address: String,
 
+  /// Whether periphery opens the connection to Core,
+  /// instead of Core connecting to the `address`.
+  /// Use for hosts behind NAT which can't accept inbound connections.
+  /// Periphery must be configured with `core_address`.
+  ///
+  /// Note. Only api requests are sent over the connection.
+  /// Container terminals, following logs, copying files to / from
+  /// containers and Docker events are not available,
+  /// so container states only refresh on the monitoring interval.
+  /// Default: false
+  #[serde(default)]
+  #[builder(default)]
+  pub outbound: bool,
+
   /// An optional region label
   #[serde(default)]
   #[builder(default)]
@@ -134,6 +196,13 @@ pub struct ServerConfig {
   #[partial_default(default_send_alerts())]
   pub send_disk_alerts: bool,
 
+  /// Whether to send alerts when the periphery version
+  /// does not match the core version.
+  #[serde(default = "default_send_alerts")]
+  #[builder(default = "default_send_alerts()")]
+  #[partial_default(default_send_alerts())]
+  pub send_version_mismatch_alerts: bool,
+
   /// The percentage threshhold which triggers WARNING state for CPU.
   #[serde(default = "default_cpu_warning")]
   #[builder(default = "default_cpu_warning()")]
@@ -158,6 +227,18 @@ pub struct ServerConfig {
   #[partial_default(default_mem_critical())]
   pub mem_critical: f64,
 
+  /// The percentage threshhold which triggers WARNING state for SWAP.
+  #[serde(default = "default_swap_warning")]
+  #[builder(default = "default_swap_warning()")]
+  #[partial_default(default_swap_warning())]
+  pub swap_warning: f64,
+
+  /// The percentage threshhold which triggers CRITICAL state for SWAP.
+  #[serde(default = "default_swap_critical")]
+  #[builder(default = "default_swap_critical()")]
+  #[partial_default(default_swap_critical())]
+  pub swap_critical: f64,
+
   /// The percentage threshhold which triggers WARNING state for DISK.
   #[serde(default = "default_disk_warning")]
   #[builder(default = "default_disk_warning()")]
@@ -169,6 +250,28 @@ pub struct ServerConfig {
   #[builder(default = "default_disk_critical()")]
   #[partial_default(default_disk_critical())]
   pub disk_critical: f64,
+
+  /// The percentage threshhold of used inodes
+  /// which triggers WARNING state for DISK INODES.
+  #[serde(default = "default_inode_warning")]
+  #[builder(default = "default_inode_warning()")]
+  #[partial_default(default_inode_warning())]
+  pub inode_warning: f64,
+
+  /// The percentage threshhold of used inodes
+  /// which triggers CRITICAL state for DISK INODES.
+  #[serde(default = "default_inode_critical")]
+  #[builder(default = "default_inode_critical()")]
+  #[partial_default(default_inode_critical())]
+  pub inode_critical: f64,
+
+  /// Triggers WARNING when a disk is forecast to be full
+  /// within this many days, based on its growth over the last day.
+  /// Set to 0 to disable the forecast alert.
+  #[serde(default = "default_disk_forecast_warning_days")]
+  #[builder(default = "default_disk_forecast_warning_days()")]
+  #[partial_default(default_disk_forecast_warning_days())]
+  pub disk_forecast_warning_days: f64,
 }
 
 impl ServerConfig {
@@ -217,6 +320,14 @@ fn default_mem_critical() -> f64 {
   95.0
 }
 
+fn default_swap_warning() -> f64 {
+  50.0
+}
+
+fn default_swap_critical() -> f64 {
+  90.0
+}
+
 fn default_disk_warning() -> f64 {
   75.0
 }
@@ -225,10 +336,23 @@ fn default_disk_critical() -> f64 {
   95.0
 }
 
+fn default_inode_warning() -> f64 {
+  75.0
+}
+
+fn default_inode_critical() -> f64 {
+  95.0
+}
+
+fn default_disk_forecast_warning_days() -> f64 {
+  7.0
+}
+
 impl Default for ServerConfig {
   fn default() -> Self {
     Self {
       address: Default::default(),
+      outbound: Default::default(),
       enabled: default_enabled(),
       timeout_seconds: default_timeout_seconds(),
       ignore_mounts: Default::default(),
@@ -239,13 +363,20 @@ impl Default for ServerConfig {
       send_cpu_alerts: default_send_alerts(),
       send_mem_alerts: default_send_alerts(),
       send_disk_alerts: default_send_alerts(),
+      send_version_mismatch_alerts: default_send_alerts(),
       region: Default::default(),
       cpu_warning: default_cpu_warning(),
       cpu_critical: default_cpu_critical(),
       mem_warning: default_mem_warning(),
       mem_critical: default_mem_critical(),
+      swap_warning: default_swap_warning(),
+      swap_critical: default_swap_critical(),
       disk_warning: default_disk_warning(),
       disk_critical: default_disk_critical(),
+      inode_warning: default_inode_warning(),
+      inode_critical: default_inode_critical(),
+      disk_forecast_warning_days: default_disk_forecast_warning_days(
+      ),
     }
   }
 }
@@ -265,7 +396,12 @@ pub struct ServerHealthState {
 pub struct ServerHealth {
   pub cpu: ServerHealthState,
   pub mem: ServerHealthState,
+  #[serde(default)]
+  pub swap: ServerHealthState,
   pub disks: HashMap<PathBuf, ServerHealthState>,
+  /// The health of the inode usage of each disk
+  #[serde(default)]
+  pub inodes: HashMap<PathBuf, ServerHealthState>,
 }
 
 /// Current pending actions on the server.
@@ -320,6 +456,23 @@ pub enum ServerState {
   Disabled,
 }
 
+/// A file or directory on the server host,
+/// inside one of the periphery file roots.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HostFileEntry {
+  /// The file name
+  pub name: String,
+  /// The absolute path on the host
+  pub path: String,
+  /// Whether the entry is a directory
+  pub is_dir: bool,
+  /// The file size in bytes. 0 for directories.
+  pub size: I64,
+  /// Unix timestamp in milliseconds of the last modification.
+  pub modified: I64,
+}
+
 /// Server-specific query
 #[typeshare]
 pub type ServerQuery = ResourceQuery<ServerQuerySpecifics>;
@@ -206,6 +206,13 @@ pub struct StackConfig {
   #[builder(default)]
   pub server_id: String,
 
+  /// The docker endpoint on the server to deploy the stack on,
+  /// by name from the `docker_endpoints` in the periphery config.
+  /// If empty, uses the default endpoint.
+  #[serde(default)]
+  #[builder(default)]
+  pub docker_endpoint: String,
+
   /// Configure quick links that are displayed in the resource header
   #[serde(default, deserialize_with = "string_list_deserializer")]
   #[partial_attr(serde(
@@ -494,6 +501,7 @@ impl Default for StackConfig {
   fn default() -> Self {
     Self {
       server_id: Default::default(),
+      docker_endpoint: Default::default(),
       project_name: Default::default(),
       run_directory: Default::default(),
       file_paths: Default::default(),
@@ -57,9 +57,103 @@ pub struct SystemStatsRecord {
   /// Total network egress in bytes
   #[serde(default)]
   pub network_egress_bytes: f64,
-  // /// Network usage by interface name (ingress, egress in bytes)
-  // #[serde(default)]
-  // pub network_usage_interface: Vec<SingleNetworkInterfaceUsage>, // interface -> (ingress, egress)
+  /// Network usage broken down by interface
+  #[serde(default)]
+  pub network_usage_interface: Vec<SingleNetworkInterfaceUsage>,
+  /// System load averages
+  #[serde(default)]
+  pub load_average: LoadAverage,
+  /// Swap used in GB
+  #[serde(default)]
+  pub swap_used_gb: f64,
+  /// Total swap in GB
+  #[serde(default)]
+  pub swap_total_gb: f64,
+}
+
+/// Downsampled system stats stored on the database.
+/// Aggregates the raw stats over the `granularity`
+/// long bucket starting at `ts`.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", collection_name(StatsRollup))]
+pub struct SystemStatsRollup {
+  /// Unix timestamp in milliseconds of the bucket start
+  #[cfg_attr(feature = "mongo", index)]
+  pub ts: I64,
+  /// Server id
+  #[cfg_attr(feature = "mongo", index)]
+  pub sid: String,
+  /// The bucket length, either `5-min` or `1-hr`
+  #[cfg_attr(feature = "mongo", index)]
+  pub granularity: Timelength,
+  /// The number of raw samples in the bucket
+  pub count: I64,
+  /// Cpu usage percentage
+  pub cpu_perc: StatsAggregate,
+  /// Memory used in GB
+  pub mem_used_gb: StatsAggregate,
+  /// Total memory in GB at the end of the bucket
+  pub mem_total_gb: f64,
+  /// Disk used in GB
+  pub disk_used_gb: StatsAggregate,
+  /// Total disk size in GB at the end of the bucket
+  pub disk_total_gb: f64,
+  /// Network ingress in bytes
+  pub network_ingress_bytes: StatsAggregate,
+  /// Network egress in bytes
+  pub network_egress_bytes: StatsAggregate,
+}
+
+/// The min / avg / max of a stat over a rollup bucket.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct StatsAggregate {
+  pub min: f64,
+  pub avg: f64,
+  pub max: f64,
+}
+
+/// Container stats stored on the database.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", collection_name(ContainerStats))]
+pub struct ContainerStatsRecord {
+  /// Unix timestamp in milliseconds
+  #[cfg_attr(feature = "mongo", index)]
+  pub ts: I64,
+  /// Server id
+  #[cfg_attr(feature = "mongo", index)]
+  pub sid: String,
+  /// The container name
+  #[cfg_attr(feature = "mongo", index)]
+  pub container: String,
+  /// Cpu usage percentage
+  pub cpu_perc: f64,
+  /// Memory usage percentage of the limit
+  pub mem_perc: f64,
+  /// Memory used in bytes
+  pub mem_used_bytes: f64,
+  /// Memory limit in bytes
+  pub mem_limit_bytes: f64,
+  /// Total network ingress in bytes since container start
+  pub network_ingress_bytes: f64,
+  /// Total network egress in bytes since container start
+  pub network_egress_bytes: f64,
+  /// Total bytes read from block devices since container start
+  pub block_read_bytes: f64,
+  /// Total bytes written to block devices since container start
+  pub block_write_bytes: f64,
+  /// Number of processes / threads in the container
+  pub pids: I64,
 }
 
 /// Realtime system stats data.
@@ -86,9 +180,18 @@ pub struct SystemStats {
   /// Network egress usage in MB
   #[serde(default)]
   pub network_egress_bytes: f64,
-  // /// Network usage by interface name (ingress, egress in bytes)
-  // #[serde(default)]
-  // pub network_usage_interface: Vec<SingleNetworkInterfaceUsage>, // interface -> (ingress, egress)
+  /// Network usage broken down by interface
+  #[serde(default)]
+  pub network_usage_interface: Vec<SingleNetworkInterfaceUsage>,
+  /// System load averages
+  #[serde(default)]
+  pub load_average: LoadAverage,
+  /// Swap used in GB
+  #[serde(default)]
+  pub swap_used_gb: f64,
+  /// Total swap in GB
+  #[serde(default)]
+  pub swap_total_gb: f64,
   // metadata
   /// The rate the system stats are being polled from the system
   pub polling_rate: Timelength,
@@ -110,9 +213,58 @@ pub struct SingleDiskUsage {
   pub used_gb: f64,
   /// Total size of the disk in GB
   pub total_gb: f64,
+  /// Used inodes on the disk.
+  /// Only available on Unix.
+  #[serde(default)]
+  pub inodes_used: I64,
+  /// Total inodes on the disk.
+  /// 0 if the file system doesn't report inodes.
+  #[serde(default)]
+  pub inodes_total: I64,
+  /// Bytes read from the disk per second
+  /// since the previous stats poll
+  #[serde(default)]
+  pub read_bytes_per_sec: f64,
+  /// Bytes written to the disk per second
+  /// since the previous stats poll
+  #[serde(default)]
+  pub write_bytes_per_sec: f64,
+}
+
+/// The system load averages.
+/// Only available on Unix.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct LoadAverage {
+  /// Load average over the last minute
+  pub one: f64,
+  /// Load average over the last 5 minutes
+  pub five: f64,
+  /// Load average over the last 15 minutes
+  pub fifteen: f64,
+}
+
+/// Projection of when a disk will be full,
+/// computed from its recent growth.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiskForecast {
+  /// The mount point of the disk
+  pub mount: PathBuf,
+  /// Latest used portion of the disk in GB
+  pub used_gb: f64,
+  /// Total size of the disk in GB
+  pub total_gb: f64,
+  /// Growth rate of the used portion in GB per day.
+  /// Negative if the disk usage is shrinking.
+  pub growth_gb_per_day: f64,
+  /// Days until the disk is full at the current growth rate.
+  /// Null if the disk is not growing.
+  pub days_until_full: Option<f64>,
 }
 
-/// Info for network interface usage.
+/// Info for network interface usage
+/// since the previous stats poll.
 #[typeshare]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SingleNetworkInterfaceUsage {
@@ -122,6 +274,20 @@ pub struct SingleNetworkInterfaceUsage {
   pub ingress_bytes: f64,
   /// The egress in bytes
   pub egress_bytes: f64,
+  /// The number of errors on received packets
+  #[serde(default)]
+  pub ingress_errors: f64,
+  /// The number of errors on transmitted packets
+  #[serde(default)]
+  pub egress_errors: f64,
+  /// The number of received packets dropped.
+  /// Only available on Linux.
+  #[serde(default)]
+  pub ingress_dropped: f64,
+  /// The number of transmitted packets dropped.
+  /// Only available on Linux.
+  #[serde(default)]
+  pub egress_dropped: f64,
 }
 
 pub fn sum_disk_usage(disks: &[SingleDiskUsage]) -> TotalDiskUsage {
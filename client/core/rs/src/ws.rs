@@ -12,7 +12,10 @@ use tracing::{Instrument, debug, info, info_span, warn};
 use typeshare::typeshare;
 use uuid::Uuid;
 
-use crate::{KomodoClient, entities::update::UpdateListItem};
+use crate::{
+  KomodoClient,
+  entities::{SearchCombinator, U64, update::UpdateListItem},
+};
 
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +37,149 @@ impl WsLoginMessage {
   }
 }
 
+/// Query params to open an interactive shell in a Deployment container,
+/// at `/ws/terminal/deployment`. Requires Execute permission.
+///
+/// After the login message, stdin / stdout are passed
+/// as Binary messages, and the session is controlled
+/// with [TerminalControlMessage] Text messages.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectDeploymentExecQuery {
+  /// Id or name
+  pub deployment: String,
+  /// The shell to run in the container.
+  /// Default: `sh`
+  #[serde(default = "default_shell")]
+  pub shell: String,
+}
+
+/// Query params to open an interactive shell in a Stack service container,
+/// at `/ws/terminal/stack`. Requires Execute permission.
+///
+/// Uses the same message protocol as [ConnectDeploymentExecQuery].
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectStackExecQuery {
+  /// Id or name
+  pub stack: String,
+  /// The service name
+  pub service: String,
+  /// The shell to run in the container.
+  /// Default: `sh`
+  #[serde(default = "default_shell")]
+  pub shell: String,
+}
+
+pub fn default_shell() -> String {
+  String::from("sh")
+}
+
+/// Follows the Deployment container log, at `/ws/log/deployment`.
+/// Requires Read permission.
+///
+/// Sent as JSON after the login message.
+/// Each log line is then passed as a Text message.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowDeploymentLog {
+  /// Id or name
+  pub deployment: String,
+  /// Pass `--tail` for the initial log contents. Max of 5000
+  #[serde(default = "default_follow_tail")]
+  pub tail: U64,
+  /// Only pass through lines matching the search terms.
+  /// If empty, all lines are passed through.
+  #[serde(default)]
+  pub terms: Vec<String>,
+  /// And: Only lines matching all terms
+  /// Or: Lines matching any one of the terms
+  #[serde(default)]
+  pub combinator: SearchCombinator,
+  /// Invert the search (search for everything not matching terms)
+  #[serde(default)]
+  pub invert: bool,
+  /// Enable `--timestamps`
+  #[serde(default)]
+  pub timestamps: bool,
+}
+
+/// Follows the Stack log, at `/ws/log/stack`.
+/// Requires Read permission.
+///
+/// Uses the same message protocol as [FollowDeploymentLog].
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowStackLog {
+  /// Id or name
+  pub stack: String,
+  /// Filter the logs to only ones from specific services.
+  /// If empty, will include logs from all services.
+  #[serde(default)]
+  pub services: Vec<String>,
+  /// Pass `--tail` for the initial log contents. Max of 5000
+  #[serde(default = "default_follow_tail")]
+  pub tail: U64,
+  /// Only pass through lines matching the search terms.
+  /// If empty, all lines are passed through.
+  #[serde(default)]
+  pub terms: Vec<String>,
+  /// And: Only lines matching all terms
+  /// Or: Lines matching any one of the terms
+  #[serde(default)]
+  pub combinator: SearchCombinator,
+  /// Invert the search (search for everything not matching terms)
+  #[serde(default)]
+  pub invert: bool,
+  /// Enable `--timestamps`
+  #[serde(default)]
+  pub timestamps: bool,
+}
+
+/// Follows the log of any container on the Server, at `/ws/log/container`.
+/// Requires Read permission on the Server.
+///
+/// Uses the same message protocol as [FollowDeploymentLog].
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowContainerLog {
+  /// Id or name
+  pub server: String,
+  /// The container name
+  pub container: String,
+  /// Pass `--tail` for the initial log contents. Max of 5000
+  #[serde(default = "default_follow_tail")]
+  pub tail: U64,
+  /// Only pass through lines matching the search terms.
+  /// If empty, all lines are passed through.
+  #[serde(default)]
+  pub terms: Vec<String>,
+  /// And: Only lines matching all terms
+  /// Or: Lines matching any one of the terms
+  #[serde(default)]
+  pub combinator: SearchCombinator,
+  /// Invert the search (search for everything not matching terms)
+  #[serde(default)]
+  pub invert: bool,
+  /// Enable `--timestamps`
+  #[serde(default)]
+  pub timestamps: bool,
+}
+
+fn default_follow_tail() -> u64 {
+  50
+}
+
+/// Controls an open terminal session.
+/// Sent as JSON in a Text message.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type", content = "params")]
+pub enum TerminalControlMessage {
+  /// Resize the terminal
+  Resize { rows: u16, cols: u16 },
+}
+
 #[derive(Debug, Clone)]
 pub enum UpdateWsMessage {
   Update(UpdateListItem),
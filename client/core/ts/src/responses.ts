@@ -61,6 +61,7 @@ export type ReadResponses = {
   GetServer: Types.GetServerResponse;
   GetServerState: Types.GetServerStateResponse;
   GetPeripheryVersion: Types.GetPeripheryVersionResponse;
+  ListPeripheryVersions: Types.ListPeripheryVersionsResponse;
   ListDockerContainers: Types.ListDockerContainersResponse;
   ListAllDockerContainers: Types.ListAllDockerContainersResponse;
   InspectDockerContainer: Types.InspectDockerContainerResponse;
@@ -74,9 +75,16 @@ export type ReadResponses = {
   ListDockerImageHistory: Types.ListDockerImageHistoryResponse;
   ListDockerVolumes: Types.ListDockerVolumesResponse;
   InspectDockerVolume: Types.InspectDockerVolumeResponse;
+  ListDockerVolumeBackups: Types.ListDockerVolumeBackupsResponse;
+  GetDockerDiskUsage: Types.GetDockerDiskUsageResponse;
+  ListDockerEndpoints: Types.ListDockerEndpointsResponse;
   ListComposeProjects: Types.ListComposeProjectsResponse;
   GetServerActionState: Types.GetServerActionStateResponse;
   GetHistoricalServerStats: Types.GetHistoricalServerStatsResponse;
+  GetHistoricalContainerStats: Types.GetHistoricalContainerStatsResponse;
+  GetServerDiskForecast: Types.GetServerDiskForecastResponse;
+  ListServerFiles: Types.ListServerFilesResponse;
+  GetServerFile: Types.GetServerFileResponse;
   ListServers: Types.ListServersResponse;
   ListFullServers: Types.ListFullServersResponse;
 
@@ -212,6 +220,12 @@ export type WriteResponses = {
   UpdateServer: Types.Server;
   RenameServer: Types.Update;
   CreateNetwork: Types.Update;
+  WriteServerFile: Types.Update;
+  DeleteServerFile: Types.Update;
+  RotateServerCertificate: Types.Update;
+  ResetServerCertificate: Types.Update;
+  RotateServerPasskey: Types.Update;
+  ResetServerPasskey: Types.Update;
 
   // ==== DEPLOYMENT ====
   CreateDeployment: Types.Deployment;
@@ -342,6 +356,8 @@ export type ExecuteResponses = {
   PruneImages: Types.Update;
   DeleteVolume: Types.Update;
   PruneVolumes: Types.Update;
+  BackupVolume: Types.Update;
+  RestoreVolume: Types.Update;
   PruneDockerBuilders: Types.Update;
   PruneBuildx: Types.Update;
   PruneSystem: Types.Update;
@@ -467,6 +467,8 @@ export type Execution =
 	| { type: "PruneImages", params: PruneImages }
 	| { type: "DeleteVolume", params: DeleteVolume }
 	| { type: "PruneVolumes", params: PruneVolumes }
+	| { type: "BackupVolume", params: BackupVolume }
+	| { type: "RestoreVolume", params: RestoreVolume }
 	| { type: "PruneDockerBuilders", params: PruneDockerBuilders }
 	| { type: "PruneBuildx", params: PruneBuildx }
 	| { type: "PruneSystem", params: PruneSystem }
@@ -766,6 +768,12 @@ export enum TerminationSignal {
 export interface DeploymentConfig {
 	/** The id of server the deployment is deployed on. */
 	server_id?: string;
+	/**
+	 * The docker endpoint on the server to deploy on,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If empty, uses the default endpoint.
+	 */
+	docker_endpoint?: string;
 	/**
 	 * The image which the deployment deploys.
 	 * Can either be a user inputted image, or a Komodo Build.
@@ -782,6 +790,13 @@ export interface DeploymentConfig {
 	image_registry_account?: string;
 	/** Whether to skip secret interpolation into the deployment environment variables. */
 	skip_secret_interp?: boolean;
+	/**
+	 * Create the container through the Docker Engine API,
+	 * rather than the `docker run` command.
+	 * The config is validated before anything is created,
+	 * and no shell is involved. `extra_args` are not supported.
+	 */
+	engine_api?: boolean;
 	/** Whether to redeploy the deployment whenever the attached build finishes. */
 	redeploy_on_build?: boolean;
 	/** Whether to poll for any updates to the image. */
@@ -982,6 +997,19 @@ export type AlertData =
 	used_gb: number;
 	/** The total memory */
 	total_gb: number;
+}}
+	/** A server has high swap usage. */
+	| { type: "ServerSwap", data: {
+	/** The id of the server */
+	id: string;
+	/** The name of the server */
+	name: string;
+	/** The region of the server */
+	region?: string;
+	/** The used swap in GB */
+	used_gb: number;
+	/** The total swap in GB */
+	total_gb: number;
 }}
 	/** A server has high disk usage. */
 	| { type: "ServerDisk", data: {
@@ -997,6 +1025,53 @@ export type AlertData =
 	used_gb: number;
 	/** The total size of the disk in GB */
 	total_gb: number;
+}}
+	/** A server disk is running out of inodes. */
+	| { type: "ServerDiskInodes", data: {
+	/** The id of the server */
+	id: string;
+	/** The name of the server */
+	name: string;
+	/** The region of the server */
+	region?: string;
+	/** The mount path of the disk */
+	path: string;
+	/** The used inodes on the disk */
+	used: I64;
+	/** The total inodes on the disk */
+	total: I64;
+}}
+	/** A server disk is forecast to be full soon. */
+	| { type: "ServerDiskForecast", data: {
+	/** The id of the server */
+	id: string;
+	/** The name of the server */
+	name: string;
+	/** The region of the server */
+	region?: string;
+	/** The mount path of the disk */
+	path: string;
+	/** The used portion of the disk in GB */
+	used_gb: number;
+	/** The total size of the disk in GB */
+	total_gb: number;
+	/** The growth rate of the used portion in GB per day */
+	growth_gb_per_day: number;
+	/** The forecast number of days until the disk is full */
+	days_until_full: number;
+}}
+	/** A server's periphery version does not match the core version. */
+	| { type: "PeripheryVersionMismatch", data: {
+	/** The id of the server */
+	id: string;
+	/** The name of the server */
+	name: string;
+	/** The region of the server */
+	region?: string;
+	/** The version of periphery on the server */
+	periphery_version: string;
+	/** The version of core */
+	core_version: string;
 }}
 	/** A container's state has changed unexpectedly. */
 	| { type: "ContainerStateChange", data: {
@@ -1012,6 +1087,40 @@ export type AlertData =
 	from: DeploymentState;
 	/** The current container state */
 	to: DeploymentState;
+}}
+	/** A container is restarting repeatedly. */
+	| { type: "ContainerRestartLoop", data: {
+	/** The id of the deployment or stack */
+	id: string;
+	/** The name of the deployment or stack */
+	name: string;
+	/** The server id of server that the container is on */
+	server_id: string;
+	/** The server name */
+	server_name: string;
+	/** The stack service, if the container belongs to a stack */
+	service?: string;
+	/** The container name */
+	container: string;
+	/** The number of restarts observed in the window */
+	restarts: I64;
+	/** The window length in minutes */
+	window_minutes: I64;
+}}
+	/** A container was killed after running out of memory. */
+	| { type: "ContainerOomKilled", data: {
+	/** The id of the deployment or stack */
+	id: string;
+	/** The name of the deployment or stack */
+	name: string;
+	/** The server id of server that the container is on */
+	server_id: string;
+	/** The server name */
+	server_name: string;
+	/** The stack service, if the container belongs to a stack */
+	service?: string;
+	/** The container name */
+	container: string;
 }}
 	/** A Deployment has an image update available */
 	| { type: "DeploymentImageUpdateAvailable", data: {
@@ -1111,6 +1220,49 @@ export type AlertData =
 	id: string;
 	/** The name of the repo */
 	name: string;
+}}
+	/** A procedure has failed */
+	| { type: "ProcedureFailed", data: {
+	/** The id of the procedure */
+	id: string;
+	/** The name of the procedure */
+	name: string;
+}}
+	/** An action has failed */
+	| { type: "ActionFailed", data: {
+	/** The id of the action */
+	id: string;
+	/** The name of the action */
+	name: string;
+}}
+	/** A resource sync has failed */
+	| { type: "ResourceSyncFailed", data: {
+	/** The id of the resource sync */
+	id: string;
+	/** The name of the resource sync */
+	name: string;
+}}
+	/** A deployment has failed to deploy */
+	| { type: "DeployFailed", data: {
+	/** The id of the deployment */
+	id: string;
+	/** The name of the deployment */
+	name: string;
+	/** The server id of server that the deployment is on */
+	server_id: string;
+	/** The server name */
+	server_name: string;
+}}
+	/** A stack has failed to deploy */
+	| { type: "StackDeployFailed", data: {
+	/** The id of the stack */
+	id: string;
+	/** The name of the stack */
+	name: string;
+	/** The server id of server that the stack is on */
+	server_id: string;
+	/** The server name */
+	server_name: string;
 }};
 
 /** Representation of an alert in the system. */
@@ -1199,6 +1351,91 @@ export interface ContainerStats {
 
 export type GetDeploymentStatsResponse = ContainerStats;
 
+/** A row of the `docker system df` summary. */
+export interface DiskUsageSummary {
+	/** The number of items */
+	total: I64;
+	/** The number of items in use */
+	active: I64;
+	/** The space used in bytes */
+	size: I64;
+	/** The space which can be freed by pruning in bytes */
+	reclaimable: I64;
+}
+
+export interface ImageDiskUsage {
+	/** The first tag in `repo_tags`, or Id if no tags. */
+	name: string;
+	id: string;
+	/** Date and time at which the image was created as a Unix timestamp (number of seconds sinds EPOCH). */
+	created: I64;
+	/** Total size of the image including all layers it is composed of. */
+	size: I64;
+	/** The size of the layers shared with other images. */
+	shared_size: I64;
+	/** The size of the layers only used by this image. */
+	unique_size: I64;
+	/** The number of containers using the image. */
+	containers: I64;
+}
+
+export interface ContainerDiskUsage {
+	name: string;
+	id?: string;
+	image?: string;
+	state: ContainerStateStatusEnum;
+	/** The size of files that have been created or changed by this container. */
+	size_rw: I64;
+	/** The total size of all the files in this container. */
+	size_root_fs: I64;
+}
+
+export interface VolumeDiskUsage {
+	name: string;
+	driver: string;
+	/** Amount of disk space used by the volume (in bytes). Only available for volumes using the `local` driver, otherwise `-1`. */
+	size: I64;
+	/** The number of containers referencing this volume. */
+	ref_count: I64;
+}
+
+export interface BuildCacheDiskUsage {
+	id: string;
+	/** The cache record type, eg. `regular`, `source.local`, `exec.cachemount` */
+	typ: string;
+	description: string;
+	in_use: boolean;
+	/** Whether the cache record is shared with other records. */
+	shared: boolean;
+	/** Amount of disk space used by the cache record (in bytes). */
+	size: I64;
+	/** Date and time at which the cache record was last used, in RFC 3339 format. */
+	last_used_at?: string;
+	usage_count: I64;
+}
+
+/** The disk space used by docker, like `docker system df -v`. */
+export interface DockerDiskUsage {
+	/** Totals for the images */
+	images: DiskUsageSummary;
+	/** Totals for the container writable layers */
+	containers: DiskUsageSummary;
+	/** Totals for the volumes */
+	volumes: DiskUsageSummary;
+	/** Totals for the build cache */
+	build_cache: DiskUsageSummary;
+	/** The space used by each image */
+	image_usage: ImageDiskUsage[];
+	/** The space used by each container writable layer */
+	container_usage: ContainerDiskUsage[];
+	/** The space used by each volume */
+	volume_usage: VolumeDiskUsage[];
+	/** The space used by each build cache record */
+	build_cache_usage: BuildCacheDiskUsage[];
+}
+
+export type GetDockerDiskUsageResponse = DockerDiskUsage;
+
 export type GetDockerRegistryAccountResponse = DockerRegistryAccount;
 
 export type GetGitProviderAccountResponse = GitProviderAccount;
@@ -1499,6 +1736,50 @@ export interface ServerActionState {
 
 export type GetServerActionStateResponse = ServerActionState;
 
+/**
+ * Projection of when a disk will be full,
+ * computed from its recent growth.
+ */
+export interface DiskForecast {
+	/** The mount point of the disk */
+	mount: string;
+	/** Latest used portion of the disk in GB */
+	used_gb: number;
+	/** Total size of the disk in GB */
+	total_gb: number;
+	/**
+	 * Growth rate of the used portion in GB per day.
+	 * Negative if the disk usage is shrinking.
+	 */
+	growth_gb_per_day: number;
+	/**
+	 * Days until the disk is full at the current growth rate.
+	 * Null if the disk is not growing.
+	 */
+	days_until_full?: number;
+}
+
+export type GetServerDiskForecastResponse = DiskForecast[];
+
+/**
+ * A file or directory on the server host,
+ * inside one of the periphery file roots.
+ */
+export interface HostFileEntry {
+	/** The file name */
+	name: string;
+	/** The absolute path on the host */
+	path: string;
+	/** Whether the entry is a directory */
+	is_dir: boolean;
+	/** The file size in bytes. 0 for directories. */
+	size: I64;
+	/** Unix timestamp in milliseconds of the last modification. */
+	modified: I64;
+}
+
+export type ListServerFilesResponse = HostFileEntry[];
+
 /** Server configuration. */
 export interface ServerConfig {
 	/**
@@ -1506,6 +1787,19 @@ export interface ServerConfig {
 	 * Default: http://localhost:8120
 	 */
 	address: string;
+	/**
+	 * Whether periphery opens the connection to Core,
+	 * instead of Core connecting to the `address`.
+	 * Use for hosts behind NAT which can't accept inbound connections.
+	 * Periphery must be configured with `core_address`.
+	 * 
+	 * Note. Only api requests are sent over the connection.
+	 * Container terminals, following logs, copying files to / from
+	 * containers and Docker events are not available,
+	 * so container states only refresh on the monitoring interval.
+	 * Default: false
+	 */
+	outbound?: boolean;
 	/** An optional region label */
 	region?: string;
 	/**
@@ -1545,6 +1839,11 @@ export interface ServerConfig {
 	send_mem_alerts: boolean;
 	/** Whether to send alerts about the servers DISK status */
 	send_disk_alerts: boolean;
+	/**
+	 * Whether to send alerts when the periphery version
+	 * does not match the core version.
+	 */
+	send_version_mismatch_alerts: boolean;
 	/** The percentage threshhold which triggers WARNING state for CPU. */
 	cpu_warning: number;
 	/** The percentage threshhold which triggers CRITICAL state for CPU. */
@@ -1553,13 +1852,50 @@ export interface ServerConfig {
 	mem_warning: number;
 	/** The percentage threshhold which triggers CRITICAL state for MEM. */
 	mem_critical: number;
+	/** The percentage threshhold which triggers WARNING state for SWAP. */
+	swap_warning: number;
+	/** The percentage threshhold which triggers CRITICAL state for SWAP. */
+	swap_critical: number;
 	/** The percentage threshhold which triggers WARNING state for DISK. */
 	disk_warning: number;
 	/** The percentage threshhold which triggers CRITICAL state for DISK. */
 	disk_critical: number;
+	/**
+	 * The percentage threshhold of used inodes
+	 * which triggers WARNING state for DISK INODES.
+	 */
+	inode_warning: number;
+	/**
+	 * The percentage threshhold of used inodes
+	 * which triggers CRITICAL state for DISK INODES.
+	 */
+	inode_critical: number;
+	/**
+	 * Triggers WARNING when a disk is forecast to be full
+	 * within this many days, based on its growth over the last day.
+	 * Set to 0 to disable the forecast alert.
+	 */
+	disk_forecast_warning_days: number;
+}
+
+/** Server info, managed by Core rather than configured. */
+export interface ServerInfo {
+	/**
+	 * The sha256 fingerprint of the Periphery ssl cert,
+	 * pinned on first connection. Requests are rejected
+	 * if Periphery presents a different cert.
+	 * Empty until pinned, or for Periphery not using ssl.
+	 */
+	certificate_fingerprint: string;
+	/**
+	 * The passkey rotated by Core for this Server,
+	 * used to sign requests in place of the Core `passkey`.
+	 * Empty until rotated. Redacted in responses.
+	 */
+	passkey: string;
 }
 
-export type Server = Resource<ServerConfig, undefined>;
+export type Server = Resource<ServerConfig, ServerInfo>;
 
 export type GetServerResponse = Server;
 
@@ -1592,6 +1928,12 @@ export type GetStackLogResponse = Log;
 export interface StackConfig {
 	/** The server to deploy the stack on. */
 	server_id?: string;
+	/**
+	 * The docker endpoint on the server to deploy the stack on,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If empty, uses the default endpoint.
+	 */
+	docker_endpoint?: string;
 	/** Configure quick links that are displayed in the resource header */
 	links?: string[];
 	/**
@@ -1747,6 +2089,8 @@ export interface FileContents {
 	contents: string;
 }
 
+export type GetServerFileResponse = FileContents;
+
 export interface StackServiceNames {
 	/** The name of the service */
 	service_name: string;
@@ -1856,6 +2200,39 @@ export interface SingleDiskUsage {
 	used_gb: number;
 	/** Total size of the disk in GB */
 	total_gb: number;
+	/**
+	 * Used inodes on the disk.
+	 * Only available on Unix.
+	 */
+	inodes_used?: I64;
+	/**
+	 * Total inodes on the disk.
+	 * 0 if the file system doesn't report inodes.
+	 */
+	inodes_total?: I64;
+	/**
+	 * Bytes read from the disk per second
+	 * since the previous stats poll
+	 */
+	read_bytes_per_sec?: number;
+	/**
+	 * Bytes written to the disk per second
+	 * since the previous stats poll
+	 */
+	write_bytes_per_sec?: number;
+}
+
+/**
+ * The system load averages.
+ * Only available on Unix.
+ */
+export interface LoadAverage {
+	/** Load average over the last minute */
+	one: number;
+	/** Load average over the last 5 minutes */
+	five: number;
+	/** Load average over the last 15 minutes */
+	fifteen: number;
 }
 
 export enum Timelength {
@@ -1882,6 +2259,33 @@ export enum Timelength {
 	ThirtyDays = "30-day",
 }
 
+/**
+ * Info for network interface usage
+ * since the previous stats poll.
+ */
+export interface SingleNetworkInterfaceUsage {
+	/** The network interface name */
+	name: string;
+	/** The ingress in bytes */
+	ingress_bytes: number;
+	/** The egress in bytes */
+	egress_bytes: number;
+	/** The number of errors on received packets */
+	ingress_errors?: number;
+	/** The number of errors on transmitted packets */
+	egress_errors?: number;
+	/**
+	 * The number of received packets dropped.
+	 * Only available on Linux.
+	 */
+	ingress_dropped?: number;
+	/**
+	 * The number of transmitted packets dropped.
+	 * Only available on Linux.
+	 */
+	egress_dropped?: number;
+}
+
 /** Realtime system stats data. */
 export interface SystemStats {
 	/** Cpu usage percentage */
@@ -1903,6 +2307,14 @@ export interface SystemStats {
 	network_ingress_bytes?: number;
 	/** Network egress usage in MB */
 	network_egress_bytes?: number;
+	/** Network usage broken down by interface */
+	network_usage_interface?: SingleNetworkInterfaceUsage[];
+	/** System load averages */
+	load_average?: LoadAverage;
+	/** Swap used in GB */
+	swap_used_gb?: number;
+	/** Total swap in GB */
+	swap_total_gb?: number;
 	/** The rate the system stats are being polled from the system */
 	polling_rate: Timelength;
 	/** Unix timestamp in milliseconds when stats were last polled */
@@ -2010,9 +2422,17 @@ export enum Operation {
 	PruneImages = "PruneImages",
 	DeleteVolume = "DeleteVolume",
 	PruneVolumes = "PruneVolumes",
+	BackupVolume = "BackupVolume",
+	RestoreVolume = "RestoreVolume",
 	PruneDockerBuilders = "PruneDockerBuilders",
 	PruneBuildx = "PruneBuildx",
 	PruneSystem = "PruneSystem",
+	WriteServerFile = "WriteServerFile",
+	DeleteServerFile = "DeleteServerFile",
+	RotateServerCertificate = "RotateServerCertificate",
+	ResetServerCertificate = "ResetServerCertificate",
+	RotateServerPasskey = "RotateServerPasskey",
+	ResetServerPasskey = "ResetServerPasskey",
 	CreateStack = "CreateStack",
 	UpdateStack = "UpdateStack",
 	RenameStack = "RenameStack",
@@ -2035,6 +2455,9 @@ export enum Operation {
 	UnpauseStackService = "UnpauseStackService",
 	StopStackService = "StopStackService",
 	DestroyStackService = "DestroyStackService",
+	ExecStackService = "ExecStackService",
+	DownloadFromStackService = "DownloadFromStackService",
+	UploadToStackService = "UploadToStackService",
 	CreateDeployment = "CreateDeployment",
 	UpdateDeployment = "UpdateDeployment",
 	RenameDeployment = "RenameDeployment",
@@ -2047,6 +2470,9 @@ export enum Operation {
 	UnpauseDeployment = "UnpauseDeployment",
 	StopDeployment = "StopDeployment",
 	DestroyDeployment = "DestroyDeployment",
+	ExecDeployment = "ExecDeployment",
+	DownloadFromDeployment = "DownloadFromDeployment",
+	UploadToDeployment = "UploadToDeployment",
 	CreateBuild = "CreateBuild",
 	UpdateBuild = "UpdateBuild",
 	RenameBuild = "RenameBuild",
@@ -2998,6 +3424,11 @@ export type ListAlertersResponse = AlerterListItem[];
 export interface ContainerListItem {
 	/** The Server which holds the container. */
 	server_id?: string;
+	/**
+	 * The periphery docker endpoint which runs the container.
+	 * Empty for the default endpoint.
+	 */
+	docker_endpoint?: string;
 	/** The first name in Names, not including the initial '/' */
 	name: string;
 	/** The ID of this container */
@@ -3086,6 +3517,8 @@ export type ListDeploymentsResponse = DeploymentListItem[];
 
 export type ListDockerContainersResponse = ContainerListItem[];
 
+export type ListDockerEndpointsResponse = string[];
+
 /** individual image layer information in response to ImageHistory operation */
 export interface ImageHistoryResponseItem {
 	Id: string;
@@ -3157,6 +3590,18 @@ export type ListDockerRegistriesFromConfigResponse = DockerRegistry[];
 
 export type ListDockerRegistryAccountsResponse = DockerRegistryAccount[];
 
+/** A volume backup archive in the periphery backup storage. */
+export interface VolumeBackup {
+	/** The archive file name. Pass this to restore the backup. */
+	name: string;
+	/** The size of the archive in bytes. */
+	size: U64;
+	/** When the backup was created, in unix milliseconds. */
+	created_at: I64;
+}
+
+export type ListDockerVolumeBackupsResponse = VolumeBackup[];
+
 export interface VolumeListItem {
 	/** The name of the volume */
 	name: string;
@@ -3758,6 +4203,35 @@ export interface AwsServerTemplateConfig {
 	user_data: string;
 }
 
+/**
+ * Backs up a docker volume into a compressed archive
+ * in the periphery `backup_dir`, or `backup_s3` bucket.
+ * Response: [Update].
+ * 
+ * 1. Runs `tar -czf` against the volume in a `backup_image` container.
+ * 2. Uploads the archive to the `backup_s3` bucket, if configured.
+ * 3. Removes all but the most recent `keep` backups of the volume, if given.
+ * 
+ * Run this in a scheduled Procedure to back up volumes regularly.
+ */
+export interface BackupVolume {
+	/** Id or name */
+	server: string;
+	/** The name of the volume to back up. */
+	volume: string;
+	/**
+	 * The number of backups of the volume to keep.
+	 * If not given, all backups are kept.
+	 */
+	keep?: number;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
+}
+
 /** Builds multiple Repos in parallel that match pattern. Response: [BatchExecutionResponse]. */
 export interface BatchBuildRepo {
 	/**
@@ -4043,6 +4517,42 @@ export interface CommitSync {
 	sync: string;
 }
 
+/**
+ * Query params to open an interactive shell in a Deployment container,
+ * at `/ws/terminal/deployment`. Requires Execute permission.
+ * 
+ * After the login message, stdin / stdout are passed
+ * as Binary messages, and the session is controlled
+ * with [TerminalControlMessage] Text messages.
+ */
+export interface ConnectDeploymentExecQuery {
+	/** Id or name */
+	deployment: string;
+	/**
+	 * The shell to run in the container.
+	 * Default: `sh`
+	 */
+	shell: string;
+}
+
+/**
+ * Query params to open an interactive shell in a Stack service container,
+ * at `/ws/terminal/stack`. Requires Execute permission.
+ * 
+ * Uses the same message protocol as [ConnectDeploymentExecQuery].
+ */
+export interface ConnectStackExecQuery {
+	/** Id or name */
+	stack: string;
+	/** The service name */
+	service: string;
+	/**
+	 * The shell to run in the container.
+	 * Default: `sh`
+	 */
+	shell: string;
+}
+
 export interface Conversion {
 	/** reference on the server. */
 	local: string;
@@ -4567,6 +5077,12 @@ export interface DeleteImage {
 	server: string;
 	/** The name of the image to delete. */
 	name: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -4578,6 +5094,12 @@ export interface DeleteNetwork {
 	server: string;
 	/** The name of the network to delete. */
 	name: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -4627,6 +5149,19 @@ export interface DeleteServer {
 	id: string;
 }
 
+/**
+ * Delete a file or directory on the server host.
+ * Only files inside the periphery `stack_dir`, `repo_dir`
+ * and `file_roots` can be deleted, and not the roots themselves.
+ * Response: [Update]
+ */
+export interface DeleteServerFile {
+	/** Server Id or name */
+	server: string;
+	/** Absolute path of the file or directory */
+	path: string;
+}
+
 /**
  * Deletes the server template at the given id, and returns the deleted server template.
  * Response: [ServerTemplate]
@@ -4710,6 +5245,12 @@ export interface DeleteVolume {
 	server: string;
 	/** The name of the volume to delete. */
 	name: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -4767,6 +5308,17 @@ export interface DeployStackIfChanged {
 	stop_time?: number;
 }
 
+/**
+ * Query params to copy files to / from the Deployment container,
+ * at `/copy/deployment`.
+ */
+export interface DeploymentCopyQuery {
+	/** Id or name */
+	deployment: string;
+	/** The path inside the container */
+	path: string;
+}
+
 /**
  * Stops and destroys the container on the target server.
  * Reponse: [Update].
@@ -4782,6 +5334,12 @@ export interface DestroyContainer {
 	signal?: TerminationSignal;
 	/** Override the default termination max time. */
 	time?: number;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -4887,8 +5445,97 @@ export interface FindUser {
 	user: string;
 }
 
-/** Get a specific action. Response: [Action]. */
-export interface GetAction {
+/**
+ * Follows the log of any container on the Server, at `/ws/log/container`.
+ * Requires Read permission on the Server.
+ * 
+ * Uses the same message protocol as [FollowDeploymentLog].
+ */
+export interface FollowContainerLog {
+	/** Id or name */
+	server: string;
+	/** The container name */
+	container: string;
+	/** Pass `--tail` for the initial log contents. Max of 5000 */
+	tail: U64;
+	/**
+	 * Only pass through lines matching the search terms.
+	 * If empty, all lines are passed through.
+	 */
+	terms?: string[];
+	/**
+	 * And: Only lines matching all terms
+	 * Or: Lines matching any one of the terms
+	 */
+	combinator?: SearchCombinator;
+	/** Invert the search (search for everything not matching terms) */
+	invert?: boolean;
+	/** Enable `--timestamps` */
+	timestamps?: boolean;
+}
+
+/**
+ * Follows the Deployment container log, at `/ws/log/deployment`.
+ * Requires Read permission.
+ * 
+ * Sent as JSON after the login message.
+ * Each log line is then passed as a Text message.
+ */
+export interface FollowDeploymentLog {
+	/** Id or name */
+	deployment: string;
+	/** Pass `--tail` for the initial log contents. Max of 5000 */
+	tail: U64;
+	/**
+	 * Only pass through lines matching the search terms.
+	 * If empty, all lines are passed through.
+	 */
+	terms?: string[];
+	/**
+	 * And: Only lines matching all terms
+	 * Or: Lines matching any one of the terms
+	 */
+	combinator?: SearchCombinator;
+	/** Invert the search (search for everything not matching terms) */
+	invert?: boolean;
+	/** Enable `--timestamps` */
+	timestamps?: boolean;
+}
+
+/**
+ * Follows the Stack log, at `/ws/log/stack`.
+ * Requires Read permission.
+ * 
+ * Uses the same message protocol as [FollowDeploymentLog].
+ */
+export interface FollowStackLog {
+	/** Id or name */
+	stack: string;
+	/**
+	 * Filter the logs to only ones from specific services.
+	 * If empty, will include logs from all services.
+	 */
+	services?: string[];
+	/** Pass `--tail` for the initial log contents. Max of 5000 */
+	tail: U64;
+	/**
+	 * Only pass through lines matching the search terms.
+	 * If empty, all lines are passed through.
+	 */
+	terms?: string[];
+	/**
+	 * And: Only lines matching all terms
+	 * Or: Lines matching any one of the terms
+	 */
+	combinator?: SearchCombinator;
+	/** Invert the search (search for everything not matching terms) */
+	invert?: boolean;
+	/** Enable `--timestamps` */
+	timestamps?: boolean;
+}
+
+/** Get a specific action. Response: [Action]. */
+export interface GetAction {
 	/** Id or name */
 	action: string;
 }
@@ -5169,6 +5816,16 @@ export interface GetDeploymentsSummaryResponse {
 	unknown: I64;
 }
 
+/**
+ * Get the disk space used by docker on the server, per image,
+ * container writable layer, volume and build cache record,
+ * like `docker system df -v`. Response: [DockerDiskUsage].
+ */
+export interface GetDockerDiskUsage {
+	/** Id or name */
+	server: string;
+}
+
 /**
  * Get a specific docker registry account.
  * Response: [GetDockerRegistryAccountResponse].
@@ -5185,8 +5842,65 @@ export interface GetGitProviderAccount {
 	id: string;
 }
 
+/**
+ * Paginated endpoint serving historical (timeseries) container stats
+ * for a Deployment or Stack service.
+ * Response: [GetHistoricalContainerStatsResponse].
+ */
+export interface GetHistoricalContainerStats {
+	/** The Deployment or Stack to get container stats for. */
+	target: ResourceTarget;
+	/** The Stack service. Required when the target is a Stack. */
+	service?: string;
+	/** The granularity of the data. */
+	granularity: Timelength;
+	/**
+	 * Page of historical data. Default is 0, which is the most recent data.
+	 * Use with the `next_page` field of the response.
+	 */
+	page?: number;
+}
+
+/** Container stats stored on the database. */
+export interface ContainerStatsRecord {
+	/** Unix timestamp in milliseconds */
+	ts: I64;
+	/** Server id */
+	sid: string;
+	/** The container name */
+	container: string;
+	/** Cpu usage percentage */
+	cpu_perc: number;
+	/** Memory usage percentage of the limit */
+	mem_perc: number;
+	/** Memory used in bytes */
+	mem_used_bytes: number;
+	/** Memory limit in bytes */
+	mem_limit_bytes: number;
+	/** Total network ingress in bytes since container start */
+	network_ingress_bytes: number;
+	/** Total network egress in bytes since container start */
+	network_egress_bytes: number;
+	/** Total bytes read from block devices since container start */
+	block_read_bytes: number;
+	/** Total bytes written to block devices since container start */
+	block_write_bytes: number;
+	/** Number of processes / threads in the container */
+	pids: I64;
+}
+
+/** Response to [GetHistoricalContainerStats]. */
+export interface GetHistoricalContainerStatsResponse {
+	/** The timeseries page of data. */
+	stats: ContainerStatsRecord[];
+	/** If there is a next page of data, pass this to `page` to get it. */
+	next_page?: number;
+}
+
 /**
  * Paginated endpoint serving historical (timeseries) server stats for graphing.
+ * Pages older than the raw stats retention are served
+ * from the 5 minute or hourly rollups, merged into the granularity.
  * Response: [GetHistoricalServerStatsResponse].
  */
 export interface GetHistoricalServerStats {
@@ -5223,12 +5937,62 @@ export interface SystemStatsRecord {
 	network_ingress_bytes?: number;
 	/** Total network egress in bytes */
 	network_egress_bytes?: number;
+	/** Network usage broken down by interface */
+	network_usage_interface?: SingleNetworkInterfaceUsage[];
+	/** System load averages */
+	load_average?: LoadAverage;
+	/** Swap used in GB */
+	swap_used_gb?: number;
+	/** Total swap in GB */
+	swap_total_gb?: number;
+}
+
+/** The min / avg / max of a stat over a rollup bucket. */
+export interface StatsAggregate {
+	min: number;
+	avg: number;
+	max: number;
+}
+
+/**
+ * Downsampled system stats stored on the database.
+ * Aggregates the raw stats over the `granularity`
+ * long bucket starting at `ts`.
+ */
+export interface SystemStatsRollup {
+	/** Unix timestamp in milliseconds of the bucket start */
+	ts: I64;
+	/** Server id */
+	sid: string;
+	/** The bucket length, either `5-min` or `1-hr` */
+	granularity: Timelength;
+	/** The number of raw samples in the bucket */
+	count: I64;
+	/** Cpu usage percentage */
+	cpu_perc: StatsAggregate;
+	/** Memory used in GB */
+	mem_used_gb: StatsAggregate;
+	/** Total memory in GB at the end of the bucket */
+	mem_total_gb: number;
+	/** Disk used in GB */
+	disk_used_gb: StatsAggregate;
+	/** Total disk size in GB at the end of the bucket */
+	disk_total_gb: number;
+	/** Network ingress in bytes */
+	network_ingress_bytes: StatsAggregate;
+	/** Network egress in bytes */
+	network_egress_bytes: StatsAggregate;
 }
 
 /** Response to [GetHistoricalServerStats]. */
 export interface GetHistoricalServerStatsResponse {
-	/** The timeseries page of data. */
+	/**
+	 * The timeseries page of data.
+	 * If served from rollups, these are the averages.
+	 */
 	stats: SystemStatsRecord[];
+	/** The min / avg / max rollups, if the page is served from rollups. */
+	rollups?: SystemStatsRollup[];
 	/** If there is a next page of data, pass this to `page` to get it. */
 	next_page?: number;
 }
@@ -5431,6 +6195,31 @@ export interface GetServerActionState {
 	server: string;
 }
 
+/**
+ * Forecast when each of the server's disks will be full,
+ * computed from the growth in disk usage over the last day
+ * of historical stats.
+ * Response: [GetServerDiskForecastResponse].
+ */
+export interface GetServerDiskForecast {
+	/** Id or name */
+	server: string;
+}
+
+/**
+ * Get the contents of a text file on the server host.
+ * Only files inside the periphery `stack_dir`, `repo_dir`
+ * and `file_roots` can be read.
+ * Requires Write permission on the server.
+ * Response: [FileContents].
+ */
+export interface GetServerFile {
+	/** Id or name */
+	server: string;
+	/** Absolute path of the file */
+	path: string;
+}
+
 /** Get the state of the target server. Response: [GetServerStateResponse]. */
 export interface GetServerState {
 	/** Id or name */
@@ -5981,6 +6770,8 @@ export interface ListCommonStackExtraArgs {
 /**
  * List all docker compose projects on the target server.
  * Response: [ListComposeProjectsResponse].
+ *
+ * Only lists from the default docker endpoint of the server.
  */
 export interface ListComposeProjects {
 	/** Id or name */
@@ -5999,12 +6790,24 @@ export interface ListDeployments {
 /**
  * List all docker containers on the target server.
  * Response: [ListDockerContainersResponse].
+ *
+ * Includes the containers on all the docker endpoints of the server.
  */
 export interface ListDockerContainers {
 	/** Id or name */
 	server: string;
 }
 
+/**
+ * List the names of the docker endpoints configured on the server's
+ * periphery, in addition to the default endpoint.
+ * Response: [ListDockerEndpointsResponse].
+ */
+export interface ListDockerEndpoints {
+	/** Id or name */
+	server: string;
+}
+
 /** Get image history from the server. Response: [ListDockerImageHistoryResponse]. */
 export interface ListDockerImageHistory {
 	/** Id or name */
@@ -6016,13 +6819,19 @@ export interface ListDockerImageHistory {
 /**
  * List the docker images locally cached on the target server.
  * Response: [ListDockerImagesResponse].
+ *
+ * Only lists from the default docker endpoint of the server.
  */
 export interface ListDockerImages {
 	/** Id or name */
 	server: string;
 }
 
-/** List the docker networks on the server. Response: [ListDockerNetworksResponse]. */
+/**
+ * List the docker networks on the server. Response: [ListDockerNetworksResponse].
+ *
+ * Only lists from the default docker endpoint of the server.
+ */
 export interface ListDockerNetworks {
 	/** Id or name */
 	server: string;
@@ -6056,9 +6865,28 @@ export interface ListDockerRegistryAccounts {
 	username?: string;
 }
 
+/**
+ * List the backups of a docker volume on the server, latest first.
+ * Response: [ListDockerVolumeBackupsResponse].
+ */
+export interface ListDockerVolumeBackups {
+	/** Id or name */
+	server: string;
+	/** The volume name */
+	volume: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
+}
+
 /**
  * List all docker volumes on the target server.
  * Response: [ListDockerVolumesResponse].
+ *
+ * Only lists from the default docker endpoint of the server.
  */
 export interface ListDockerVolumes {
 	/** Id or name */
@@ -6160,6 +6988,22 @@ export interface ListGitProvidersFromConfig {
 	target?: ResourceTarget;
 }
 
+/**
+ * List the periphery version of every server the user has access to,
+ * compared against the core version.
+ * Response: [ListPeripheryVersionsResponse].
+ */
+export interface ListPeripheryVersions {
+}
+
+/** Response for [ListPeripheryVersions]. */
+export interface ListPeripheryVersionsResponse {
+	/** The version of core. */
+	core_version: string;
+	/** The periphery version of each server. */
+	servers: PeripheryVersionItem[];
+}
+
 /**
  * List permissions for the calling user.
  * Does not include any permissions on UserGroups they may be a part of.
@@ -6198,6 +7042,23 @@ export interface ListSecrets {
 	target?: ResourceTarget;
 }
 
+/**
+ * List the files in a directory on the server host.
+ * Only directories inside the periphery `stack_dir`, `repo_dir`
+ * and `file_roots` can be listed.
+ * Requires Write permission on the server.
+ * Response: [ListServerFilesResponse].
+ */
+export interface ListServerFiles {
+	/** Id or name */
+	server: string;
+	/**
+	 * Absolute path of the directory.
+	 * If empty, lists the file roots.
+	 */
+	path?: string;
+}
+
 /** List server templates matching structured query. Response: [ListServerTemplatesResponse]. */
 export interface ListServerTemplates {
 	query?: ServerTemplateQuery;
@@ -6358,6 +7219,12 @@ export interface NameAndId {
 export interface PauseAllContainers {
 	/** Name or id */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6370,6 +7237,12 @@ export interface PauseContainer {
 	server: string;
 	/** The container name */
 	container: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6393,6 +7266,22 @@ export interface PauseStack {
 	services?: string[];
 }
 
+export interface PeripheryVersionItem {
+	/** The server id */
+	server_id: string;
+	/** The server name */
+	server_name: string;
+	/** The server state */
+	state: ServerState;
+	/** The version of periphery, or 'unknown' if it can't be reached. */
+	version: string;
+	/**
+	 * Whether the periphery version matches the core version.
+	 * False if the version is unknown.
+	 */
+	matches_core: boolean;
+}
+
 export interface PermissionToml {
 	/**
 	 * Id can be:
@@ -6436,6 +7325,12 @@ export interface Port {
 export interface PruneBuildx {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6446,6 +7341,12 @@ export interface PruneBuildx {
 export interface PruneContainers {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6456,6 +7357,12 @@ export interface PruneContainers {
 export interface PruneDockerBuilders {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6466,6 +7373,12 @@ export interface PruneDockerBuilders {
 export interface PruneImages {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6476,6 +7389,12 @@ export interface PruneImages {
 export interface PruneNetworks {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6486,6 +7405,12 @@ export interface PruneNetworks {
 export interface PruneSystem {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6496,6 +7421,12 @@ export interface PruneSystem {
 export interface PruneVolumes {
 	/** Id or name */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /** Pulls the image for the target deployment. Response: [Update] */
@@ -6768,6 +7699,12 @@ export interface ResourcesToml {
 export interface RestartAllContainers {
 	/** Name or id */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6780,6 +7717,12 @@ export interface RestartContainer {
 	server: string;
 	/** The container name */
 	container: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -6803,6 +7746,79 @@ export interface RestartStack {
 	services?: string[];
 }
 
+/**
+ * Restores a docker volume from a backup made with [BackupVolume].
+ * The current volume contents are replaced. Response: [Update].
+ * 
+ * Containers using the volume must be stopped first.
+ */
+export interface RestoreVolume {
+	/** Id or name */
+	server: string;
+	/** The name of the volume to restore. */
+	volume: string;
+	/**
+	 * The backup file name, from [ListDockerVolumeBackups].
+	 * If not given, the latest backup is restored.
+	 */
+	backup?: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
+}
+
+/**
+ * Clears the pinned Periphery cert fingerprint on the Server,
+ * so the cert presented on the next connection is pinned instead.
+ * Use when the Periphery cert was replaced outside of Komodo.
+ * Response: [Update]
+ */
+export interface ResetServerCertificate {
+	/** Server Id or name */
+	server: string;
+}
+
+/**
+ * Clears the passkey rotated for the Server,
+ * so Core uses the Core `passkey` for it again.
+ * Use when the Periphery rotated passkey file was lost.
+ * Response: [Update]
+ */
+export interface ResetServerPasskey {
+	/** Server Id or name */
+	server: string;
+}
+
+/**
+ * Has Periphery generate a new self-signed ssl cert,
+ * and pins the new cert fingerprint on the Server.
+ * The new fingerprint is received over the connection
+ * verified with the currently pinned cert.
+ * Response: [Update]
+ */
+export interface RotateServerCertificate {
+	/** Server Id or name */
+	server: string;
+}
+
+/**
+ * Rotates the passkey Core signs requests to the Server with.
+ * Periphery accepts both the previous and new passkey
+ * until Core first signs a request with the new passkey,
+ * so there is no downtime. After rotating, the passkeys
+ * in the Periphery config are no longer accepted.
+ * Servers using an outbound connection connect to Core
+ * with the new passkey from then on.
+ * Response: [Update]
+ */
+export interface RotateServerPasskey {
+	/** Server Id or name */
+	server: string;
+}
+
 /** Runs the target Action. Response: [Update] */
 export interface RunAction {
 	/** Id or name */
@@ -6946,7 +7962,10 @@ export interface ServerHealthState {
 export interface ServerHealth {
 	cpu: ServerHealthState;
 	mem: ServerHealthState;
+	swap?: ServerHealthState;
 	disks: Record<string, ServerHealthState>;
+	/** The health of the inode usage of each disk */
+	inodes?: Record<string, ServerHealthState>;
 }
 
 /**
@@ -6968,16 +7987,6 @@ export interface SetUsersInUserGroup {
 	users: string[];
 }
 
-/** Info for network interface usage. */
-export interface SingleNetworkInterfaceUsage {
-	/** The network interface name */
-	name: string;
-	/** The ingress in bytes */
-	ingress_bytes: number;
-	/** The egress in bytes */
-	egress_bytes: number;
-}
-
 /** Configuration for a Slack alerter. */
 export interface SlackAlerterEndpoint {
 	/** The Slack app webhook url */
@@ -6988,10 +7997,29 @@ export interface Sleep {
 	duration_ms?: I64;
 }
 
+/**
+ * Query params to copy files to / from a Stack service container,
+ * at `/copy/stack`.
+ */
+export interface StackCopyQuery {
+	/** Id or name */
+	stack: string;
+	/** The service name */
+	service: string;
+	/** The path inside the container */
+	path: string;
+}
+
 /** Starts all containers on the target server. Response: [Update] */
 export interface StartAllContainers {
 	/** Name or id */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -7004,6 +8032,12 @@ export interface StartContainer {
 	server: string;
 	/** The container name */
 	container: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -7031,6 +8065,12 @@ export interface StartStack {
 export interface StopAllContainers {
 	/** Name or id */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -7047,6 +8087,12 @@ export interface StopContainer {
 	signal?: TerminationSignal;
 	/** Override the default termination max time. */
 	time?: number;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -7099,6 +8145,12 @@ export interface TotalDiskUsage {
 export interface UnpauseAllContainers {
 	/** Name or id */
 	server: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -7113,6 +8165,12 @@ export interface UnpauseContainer {
 	server: string;
 	/** The container name */
 	container: string;
+	/**
+	 * The docker endpoint to target,
+	 * by name from the `docker_endpoints` in the periphery config.
+	 * If not given, the default endpoint is used.
+	 */
+	docker_endpoint?: string;
 }
 
 /**
@@ -7498,6 +8556,22 @@ export interface UrlBuilderConfig {
 	passkey?: string;
 }
 
+/**
+ * Write a file on the server host, creating it and
+ * any missing parent directories.
+ * Only files inside the periphery `stack_dir`, `repo_dir`
+ * and `file_roots` can be written.
+ * Response: [Update]
+ */
+export interface WriteServerFile {
+	/** Server Id or name */
+	server: string;
+	/** Absolute path of the file */
+	path: string;
+	/** The contents to write. */
+	contents: string;
+}
+
 /** Update file contents in Files on Server or Git Repo mode. Response: [Update]. */
 export interface WriteStackFileContents {
 	/** The name or id of the target Stack. */
@@ -7552,6 +8626,8 @@ export type ExecuteRequest =
 	| { type: "PruneImages", params: PruneImages }
 	| { type: "DeleteVolume", params: DeleteVolume }
 	| { type: "PruneVolumes", params: PruneVolumes }
+	| { type: "BackupVolume", params: BackupVolume }
+	| { type: "RestoreVolume", params: RestoreVolume }
 	| { type: "PruneDockerBuilders", params: PruneDockerBuilders }
 	| { type: "PruneBuildx", params: PruneBuildx }
 	| { type: "PruneSystem", params: PruneSystem }
@@ -7636,8 +8712,13 @@ export type ReadRequest =
 	| { type: "GetServer", params: GetServer }
 	| { type: "GetServerState", params: GetServerState }
 	| { type: "GetPeripheryVersion", params: GetPeripheryVersion }
+	| { type: "ListPeripheryVersions", params: ListPeripheryVersions }
 	| { type: "GetServerActionState", params: GetServerActionState }
 	| { type: "GetHistoricalServerStats", params: GetHistoricalServerStats }
+	| { type: "GetHistoricalContainerStats", params: GetHistoricalContainerStats }
+	| { type: "GetServerDiskForecast", params: GetServerDiskForecast }
+	| { type: "ListServerFiles", params: ListServerFiles }
+	| { type: "GetServerFile", params: GetServerFile }
 	| { type: "ListServers", params: ListServers }
 	| { type: "ListFullServers", params: ListFullServers }
 	| { type: "InspectDockerContainer", params: InspectDockerContainer }
@@ -7648,6 +8729,9 @@ export type ReadRequest =
 	| { type: "InspectDockerImage", params: InspectDockerImage }
 	| { type: "ListDockerImageHistory", params: ListDockerImageHistory }
 	| { type: "InspectDockerVolume", params: InspectDockerVolume }
+	| { type: "ListDockerVolumeBackups", params: ListDockerVolumeBackups }
+	| { type: "GetDockerDiskUsage", params: GetDockerDiskUsage }
+	| { type: "ListDockerEndpoints", params: ListDockerEndpoints }
 	| { type: "ListAllDockerContainers", params: ListAllDockerContainers }
 	| { type: "ListDockerContainers", params: ListDockerContainers }
 	| { type: "ListDockerNetworks", params: ListDockerNetworks }
@@ -7752,6 +8836,12 @@ export type WriteRequest =
 	| { type: "UpdateServer", params: UpdateServer }
 	| { type: "RenameServer", params: RenameServer }
 	| { type: "CreateNetwork", params: CreateNetwork }
+	| { type: "WriteServerFile", params: WriteServerFile }
+	| { type: "DeleteServerFile", params: DeleteServerFile }
+	| { type: "RotateServerCertificate", params: RotateServerCertificate }
+	| { type: "ResetServerCertificate", params: ResetServerCertificate }
+	| { type: "RotateServerPasskey", params: RotateServerPasskey }
+	| { type: "ResetServerPasskey", params: ResetServerPasskey }
 	| { type: "CreateDeployment", params: CreateDeployment }
 	| { type: "CopyDeployment", params: CopyDeployment }
 	| { type: "CreateDeploymentFromContainer", params: CreateDeploymentFromContainer }
@@ -7844,3 +8934,13 @@ export type WsLoginMessage =
 	secret: string;
 }};
 
+/**
+ * Controls an open terminal session.
+ * Sent as JSON in a Text message.
+ */
+export type TerminalControlMessage = 
+	/** Resize the terminal */
+	| { type: "Resize", params: {
+	rows: number;
+	cols: number;
+}};
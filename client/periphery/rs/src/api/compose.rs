@@ -92,6 +92,37 @@ pub struct GetComposeLogSearch {
 
 //
 
+/// Follows `docker compose logs -f`, at the periphery `/log/compose` websocket.
+/// Sent as the first message after connecting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowComposeLog {
+  /// The name of the project
+  pub project: String,
+  /// Filter the logs to only ones from specific services.
+  /// If empty, will include logs from all services.
+  #[serde(default)]
+  pub services: Vec<String>,
+  /// Pass `--tail` for the initial log contents. Max of 5000
+  #[serde(default = "default_tail")]
+  pub tail: u64,
+  /// Only pass through lines matching the search terms.
+  /// If empty, all lines are passed through.
+  #[serde(default)]
+  pub terms: Vec<String>,
+  /// And: Only lines matching all terms
+  /// Or: Lines matching any one of the terms
+  #[serde(default)]
+  pub combinator: SearchCombinator,
+  /// Invert the search (search for everything not matching terms)
+  #[serde(default)]
+  pub invert: bool,
+  /// Enable `--timestamps`
+  #[serde(default)]
+  pub timestamps: bool,
+}
+
+//
+
 /// Write the compose contents to the file on the host, for stacks using
 /// `files_on_host`.
 #[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
@@ -147,6 +178,10 @@ pub struct ComposePull {
   pub git_token: Option<String>,
   /// If provided, use it to login in. Otherwise check periphery local git providers.
   pub registry_token: Option<String>,
+  /// Track the pull progress under this id,
+  /// see [GetPullProgress][crate::api::image::GetPullProgress].
+  #[serde(default)]
+  pub progress_id: Option<String>,
 }
 
 /// Response for [ComposePull]
@@ -175,6 +210,10 @@ pub struct ComposeUp {
   /// Propogate any secret replacers from core interpolation.
   #[serde(default)]
   pub replacers: Vec<(String, String)>,
+  /// Track the auto pull progress under this id,
+  /// see [GetPullProgress][crate::api::image::GetPullProgress].
+  #[serde(default)]
+  pub progress_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -2,6 +2,7 @@ use komodo_client::entities::{
   SearchCombinator, TerminationSignal,
   deployment::Deployment,
   docker::container::{Container, ContainerStats},
+  stats::ContainerStatsRecord,
   update::Log,
 };
 use resolver_api::Resolve;
@@ -53,6 +54,43 @@ pub struct GetContainerLogSearch {
 
 //
 
+/// Follows `docker logs -f`, at the periphery `/log/container` websocket.
+/// Sent as the first message after connecting.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FollowContainerLog {
+  pub name: String,
+  /// Pass `--tail` for the initial log contents.
+  #[serde(default = "default_tail")]
+  pub tail: u64,
+  /// Only pass through lines matching the search terms.
+  /// If empty, all lines are passed through.
+  #[serde(default)]
+  pub terms: Vec<String>,
+  #[serde(default)]
+  pub combinator: SearchCombinator,
+  #[serde(default)]
+  pub invert: bool,
+  /// Enable `--timestamps`
+  #[serde(default)]
+  pub timestamps: bool,
+}
+
+//
+
+/// Query params for the periphery `/copy/container` route.
+/// `GET` responds with a tar archive of the path,
+/// `POST` extracts the tar archive body into the path.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContainerCopyQuery {
+  /// The container name
+  pub container: String,
+  /// The path inside the container.
+  /// For uploads, this must be an existing directory.
+  pub path: String,
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
 #[response(ContainerStats)]
 #[error(serror::Error)]
@@ -69,6 +107,15 @@ pub struct GetContainerStatsList {}
 
 //
 
+/// The stats of the running containers, read through the engine api,
+/// for Core to record. The `ts` and `sid` are set by Core.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<ContainerStatsRecord>)]
+#[error(serror::Error)]
+pub struct GetContainerStatsRecords {}
+
+//
+
 // =======
 // ACTIONS
 // =======
@@ -85,6 +132,10 @@ pub struct Deploy {
   /// Propogate any secret replacers from core interpolation.
   #[serde(default)]
   pub replacers: Vec<(String, String)>,
+  /// Track the image pull progress under this id,
+  /// see [GetPullProgress][crate::api::image::GetPullProgress].
+  #[serde(default)]
+  pub progress_id: Option<String>,
 }
 
 //
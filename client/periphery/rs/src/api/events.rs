@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Passed as Text messages at the periphery `/events` websocket,
+/// as they are received from the Docker events API.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DockerEvent {
+  #[serde(rename = "type")]
+  pub ty: DockerEventType,
+  /// eg. `start`, `die`, `health_status: healthy`, `pull`.
+  pub action: String,
+  /// The id of the container / image / network / volume.
+  pub id: String,
+  /// The container name, if a container event.
+  pub name: Option<String>,
+  /// Unix timestamp in ms.
+  pub ts: i64,
+}
+
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DockerEventType {
+  Container,
+  Image,
+  Network,
+  Volume,
+}
@@ -0,0 +1,54 @@
+use komodo_client::entities::{
+  FileContents, server::HostFileEntry, update::Log,
+};
+use resolver_api::Resolve;
+use serde::{Deserialize, Serialize};
+
+//
+
+/// List the entries in a directory inside the file roots.
+/// If `path` is empty, lists the file roots themselves.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<HostFileEntry>)]
+#[error(serror::Error)]
+pub struct ListFiles {
+  /// Absolute path of the directory
+  #[serde(default)]
+  pub path: String,
+}
+
+//
+
+/// Read a text file inside the file roots.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(FileContents)]
+#[error(serror::Error)]
+pub struct ReadFile {
+  /// Absolute path of the file
+  pub path: String,
+}
+
+//
+
+/// Write a file inside the file roots,
+/// creating it and any missing parent directories.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Log)]
+#[error(serror::Error)]
+pub struct WriteFile {
+  /// Absolute path of the file
+  pub path: String,
+  pub contents: String,
+}
+
+//
+
+/// Delete a file or directory inside the file roots.
+/// The file roots themselves can't be deleted.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Log)]
+#[error(serror::Error)]
+pub struct DeleteFile {
+  /// Absolute path of the file or directory
+  pub path: String,
+}
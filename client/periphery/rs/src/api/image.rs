@@ -35,6 +35,21 @@ pub struct PullImage {
   pub account: Option<String>,
   /// Override registry token for account with one sent from core.
   pub token: Option<String>,
+  /// Track the pull progress under this id, see [GetPullProgress].
+  #[serde(default)]
+  pub progress_id: Option<String>,
+}
+
+//
+
+/// Get the progress of a pull running with the `progress_id`.
+/// Contains the latest output line for each layer,
+/// or null if no pull is running with the id.
+#[derive(Debug, Clone, Serialize, Deserialize, Resolve)]
+#[response(Option<String>)]
+#[error(serror::Error)]
+pub struct GetPullProgress {
+  pub id: String,
 }
 
 //
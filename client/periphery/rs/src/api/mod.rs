@@ -2,8 +2,9 @@ use komodo_client::entities::{
   SystemCommand,
   config::{DockerRegistry, GitProvider},
   docker::{
-    container::ContainerListItem, image::ImageListItem,
-    network::NetworkListItem, volume::VolumeListItem,
+    container::ContainerListItem, disk_usage::DockerDiskUsage,
+    image::ImageListItem, network::NetworkListItem,
+    volume::VolumeListItem,
   },
   stack::ComposeProject,
   update::Log,
@@ -15,10 +16,13 @@ use serror::Serror;
 pub mod build;
 pub mod compose;
 pub mod container;
+pub mod events;
+pub mod files;
 pub mod git;
 pub mod image;
 pub mod network;
 pub mod stats;
+pub mod terminal;
 pub mod volume;
 
 //
@@ -43,7 +47,29 @@ pub struct GetVersionResponse {
   pub version: String,
 }
 
-/// Returns all containers, networks, images, compose projects
+/// Selects the docker endpoint for the websocket and copy routes.
+/// Requests to `/` send `docker_endpoint` in the body instead,
+/// alongside `type` and `params`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DockerEndpointQuery {
+  /// The name of the docker endpoint in the periphery config.
+  /// If empty, uses the default endpoint.
+  pub docker_endpoint: Option<String>,
+}
+
+//
+
+/// The names of the `docker_endpoints` in the periphery config.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<String>)]
+#[error(serror::Error)]
+pub struct ListDockerEndpoints {}
+
+//
+
+/// Returns all containers, networks, images, compose projects.
+/// Containers are listed from all the docker endpoints,
+/// the rest only from the default endpoint.
 #[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
 #[response(GetDockerListsResponse)]
 #[error(serror::Error)]
@@ -60,6 +86,15 @@ pub struct GetDockerListsResponse {
 
 //
 
+/// Returns the disk space used by images, containers,
+/// volumes and build cache, like `docker system df -v`.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(DockerDiskUsage)]
+#[error(serror::Error)]
+pub struct GetDockerDiskUsage {}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
 #[response(ListGitProvidersResponse)]
 #[error(serror::Error)]
@@ -85,6 +120,39 @@ pub struct ListSecrets {}
 
 //
 
+/// Accepts the new passkey in place of the current passkeys.
+/// The current passkeys are still accepted
+/// until the first request signed with the new passkey.
+#[derive(Serialize, Deserialize, Clone, Resolve)]
+#[response(Log)]
+#[error(serror::Error)]
+pub struct RotatePasskey {
+  pub passkey: String,
+}
+
+impl std::fmt::Debug for RotatePasskey {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("RotatePasskey").finish_non_exhaustive()
+  }
+}
+//
+
+/// Generates a new self-signed ssl cert, replacing the configured
+/// cert / key files, and starts serving it for new connections.
+/// Returns the new fingerprint for core to pin.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(RotateSslCertResponse)]
+#[error(serror::Error)]
+pub struct RotateSslCert {}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RotateSslCertResponse {
+  /// The sha256 fingerprint of the new cert
+  pub fingerprint: String,
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
 #[response(Log)]
 #[error(serror::Error)]
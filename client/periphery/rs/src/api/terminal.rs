@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Query params to open an interactive shell in a container,
+/// at the periphery `/terminal/container` websocket.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContainerExecQuery {
+  /// The container name
+  pub container: String,
+  /// The shell to run in the container
+  pub shell: String,
+}
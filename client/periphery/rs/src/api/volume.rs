@@ -1,4 +1,7 @@
-use komodo_client::entities::{docker::volume::Volume, update::Log};
+use komodo_client::entities::{
+  docker::volume::{Volume, VolumeBackup},
+  update::Log,
+};
 use resolver_api::Resolve;
 use serde::{Deserialize, Serialize};
 
@@ -27,3 +30,41 @@ pub struct DeleteVolume {
 #[response(Log)]
 #[error(serror::Error)]
 pub struct PruneVolumes {}
+
+//
+
+/// Archives the volume contents into
+/// `{backup_dir}/{endpoint}/{name}/{timestamp}.tar.gz`,
+/// where `endpoint` is `_default` for the default docker endpoint,
+/// then uploads it to the `backup_s3` bucket if configured.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<Log>)]
+#[error(serror::Error)]
+pub struct BackupVolume {
+  pub name: String,
+  /// Remove all but the most recent `keep` backups of the volume
+  /// after backing up. If not given, all backups are kept.
+  pub keep: Option<u32>,
+}
+
+//
+
+/// Replaces the volume contents with a backup.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Log)]
+#[error(serror::Error)]
+pub struct RestoreVolume {
+  pub name: String,
+  /// The backup file name. If not given, restores the latest backup.
+  pub backup: Option<String>,
+}
+
+//
+
+/// Lists the backups of the volume, latest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Resolve)]
+#[response(Vec<VolumeBackup>)]
+#[error(serror::Error)]
+pub struct ListVolumeBackups {
+  pub name: String,
+}
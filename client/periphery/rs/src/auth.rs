@@ -0,0 +1,230 @@
+//! Requests to periphery are signed with the passkey,
+//! rather than sending the passkey itself. The signature covers
+//! the method, path, body, a timestamp and a nonce,
+//! so a captured request can't be altered or replayed.
+
+use std::{
+  collections::HashMap,
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use komodo_client::entities::komodo_timestamp;
+use reqwest::header::HeaderValue;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::PeripheryClient;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The unix timestamp (ms) the request was signed at.
+pub const TIMESTAMP_HEADER: &str = "x-komodo-timestamp";
+/// Unique per request.
+pub const NONCE_HEADER: &str = "x-komodo-nonce";
+/// The hex encoded HMAC-SHA256 of the request.
+pub const SIGNATURE_HEADER: &str = "x-komodo-signature";
+
+/// Periphery rejects requests signed further than this from its own clock,
+/// and remembers the nonces it has seen for this long.
+pub const REPLAY_WINDOW_MS: i64 = 5 * 60 * 1000;
+
+pub fn sign_request(
+  passkey: &str,
+  method: &str,
+  path: &str,
+  timestamp: i64,
+  nonce: &str,
+  body: &[u8],
+) -> String {
+  let mut mac = HmacSha256::new_from_slice(passkey.as_bytes())
+    .expect("HMAC can take key of any size");
+  mac.update(&signing_payload(method, path, timestamp, nonce, body));
+  hex::encode(mac.finalize().into_bytes())
+}
+
+/// Compares in constant time.
+pub fn verify_signature(
+  passkey: &str,
+  method: &str,
+  path: &str,
+  timestamp: i64,
+  nonce: &str,
+  body: &[u8],
+  signature: &str,
+) -> bool {
+  let Ok(signature) = hex::decode(signature) else {
+    return false;
+  };
+  let mut mac = HmacSha256::new_from_slice(passkey.as_bytes())
+    .expect("HMAC can take key of any size");
+  mac.update(&signing_payload(method, path, timestamp, nonce, body));
+  mac.verify_slice(&signature).is_ok()
+}
+
+/// Returns false if the nonce was already used within the replay window.
+pub fn check_nonce(nonce: &str, timestamp: i64) -> bool {
+  static NONCES: OnceLock<Mutex<HashMap<String, i64>>> =
+    OnceLock::new();
+  let mut nonces =
+    NONCES.get_or_init(Default::default).lock().unwrap();
+  let now = komodo_timestamp();
+  // Requests outside the window are rejected by timestamp anyways
+  nonces.retain(|_, timestamp| now - *timestamp <= REPLAY_WINDOW_MS);
+  if nonces.contains_key(nonce) {
+    return false;
+  }
+  nonces.insert(nonce.to_string(), timestamp);
+  true
+}
+
+fn signing_payload(
+  method: &str,
+  path: &str,
+  timestamp: i64,
+  nonce: &str,
+  body: &[u8],
+) -> Vec<u8> {
+  let body_hash = hex::encode(Sha256::digest(body));
+  format!("{method}\n{path}\n{timestamp}\n{nonce}\n{body_hash}")
+    .into_bytes()
+}
+
+impl PeripheryClient {
+  pub(crate) fn signed_request(
+    &self,
+    req: reqwest::RequestBuilder,
+  ) -> anyhow::Result<reqwest::Request> {
+    let mut req =
+      req.build().context("failed to build periphery request")?;
+    let timestamp = komodo_timestamp();
+    let nonce = Uuid::new_v4().simple().to_string();
+    let url = req.url();
+    let path = match url.query() {
+      Some(query) => format!("{}?{query}", url.path()),
+      None => url.path().to_string(),
+    };
+    let signature = sign_request(
+      &self.passkey,
+      req.method().as_str(),
+      &path,
+      timestamp,
+      &nonce,
+      req
+        .body()
+        .and_then(|body| body.as_bytes())
+        .unwrap_or_default(),
+    );
+    let headers = req.headers_mut();
+    headers.insert(TIMESTAMP_HEADER, HeaderValue::from(timestamp));
+    headers.insert(
+      NONCE_HEADER,
+      HeaderValue::from_str(&nonce).context("invalid nonce")?,
+    );
+    headers.insert(
+      SIGNATURE_HEADER,
+      HeaderValue::from_str(&signature)
+        .context("invalid signature")?,
+    );
+    Ok(req)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PASSKEY: &str = "passkey";
+  const TIMESTAMP: i64 = 1729300000000;
+  const NONCE: &str = "8c1d0f3e6a2b4c5d9e7f1a2b3c4d5e6f";
+  const BODY: &[u8] = br#"{"type":"GetVersion","params":{}}"#;
+
+  fn signature() -> String {
+    sign_request(PASSKEY, "POST", "/", TIMESTAMP, NONCE, BODY)
+  }
+
+  #[test]
+  fn sign_verify_round_trip() {
+    assert!(verify_signature(
+      PASSKEY,
+      "POST",
+      "/",
+      TIMESTAMP,
+      NONCE,
+      BODY,
+      &signature()
+    ));
+    assert!(!verify_signature(
+      "other",
+      "POST",
+      "/",
+      TIMESTAMP,
+      NONCE,
+      BODY,
+      &signature()
+    ));
+  }
+
+  #[test]
+  fn tampered_request_rejected() {
+    let signature = signature();
+    // path
+    assert!(!verify_signature(
+      PASSKEY,
+      "POST",
+      "/terminal",
+      TIMESTAMP,
+      NONCE,
+      BODY,
+      &signature
+    ));
+    // body
+    assert!(!verify_signature(
+      PASSKEY,
+      "POST",
+      "/",
+      TIMESTAMP,
+      NONCE,
+      br#"{"type":"PruneSystem","params":{}}"#,
+      &signature
+    ));
+    // timestamp
+    assert!(!verify_signature(
+      PASSKEY,
+      "POST",
+      "/",
+      TIMESTAMP + 1,
+      NONCE,
+      BODY,
+      &signature
+    ));
+    // nonce
+    assert!(!verify_signature(
+      PASSKEY, "POST", "/", TIMESTAMP, "other", BODY, &signature
+    ));
+    // method
+    assert!(!verify_signature(
+      PASSKEY, "GET", "/", TIMESTAMP, NONCE, BODY, &signature
+    ));
+  }
+
+  #[test]
+  fn bad_hex_signature_rejected() {
+    for signature in ["", "not hex", "abc", &signature()[2..]] {
+      assert!(!verify_signature(
+        PASSKEY, "POST", "/", TIMESTAMP, NONCE, BODY, signature
+      ));
+    }
+  }
+
+  #[test]
+  fn nonce_reuse_rejected() {
+    let nonce = Uuid::new_v4().simple().to_string();
+    let timestamp = komodo_timestamp();
+    assert!(check_nonce(&nonce, timestamp));
+    assert!(!check_nonce(&nonce, timestamp));
+    let other = Uuid::new_v4().simple().to_string();
+    assert!(check_nonce(&other, timestamp));
+  }
+}
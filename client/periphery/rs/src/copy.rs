@@ -0,0 +1,86 @@
+use anyhow::{Context, anyhow};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+
+use crate::{
+  PeripheryClient,
+  api::{DockerEndpointQuery, container::ContainerCopyQuery},
+  tls::periphery_http_client,
+};
+
+impl PeripheryClient {
+  /// Downloads the path from the container as a tar archive.
+  /// The archive is streamed in the response body.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn download_from_container(
+    &self,
+    query: &ContainerCopyQuery,
+  ) -> anyhow::Result<Response> {
+    self
+      .copy_request(self.copy_builder(Method::GET)?.query(query))
+      .await
+  }
+
+  /// Extracts the tar archive into the path in the container.
+  /// The archive is buffered, as the request body is signed.
+  #[tracing::instrument(level = "debug", skip(self, tar))]
+  pub async fn upload_to_container(
+    &self,
+    query: &ContainerCopyQuery,
+    tar: impl Into<reqwest::Body>,
+  ) -> anyhow::Result<()> {
+    self
+      .copy_request(
+        self
+          .copy_builder(Method::POST)?
+          .query(query)
+          .header(reqwest::header::CONTENT_TYPE, "application/x-tar")
+          .body(tar),
+      )
+      .await?;
+    Ok(())
+  }
+
+  fn copy_builder(
+    &self,
+    method: Method,
+  ) -> anyhow::Result<RequestBuilder> {
+    if self.tunnel.is_some() {
+      return Err(anyhow!(
+        "Not available for servers using an outbound connection"
+      ));
+    }
+    let req =
+      periphery_http_client(self.certificate_fingerprint.as_deref())?
+        .request(
+          method,
+          format!(
+            "{}/copy/container",
+            self.address.trim_end_matches('/')
+          ),
+        )
+        .query(&DockerEndpointQuery {
+          docker_endpoint: self.docker_endpoint.clone(),
+        });
+    Ok(req)
+  }
+
+  async fn copy_request(
+    &self,
+    req: RequestBuilder,
+  ) -> anyhow::Result<Response> {
+    let res =
+      periphery_http_client(self.certificate_fingerprint.as_deref())?
+        .execute(self.signed_request(req)?)
+        .await
+        .context("failed at request to periphery")?;
+    let status = res.status();
+    if status == StatusCode::OK {
+      return Ok(res);
+    }
+    let text = res
+      .text()
+      .await
+      .context("failed to convert response to text")?;
+    Err(serror::deserialize_error(text).context(status))
+  }
+}
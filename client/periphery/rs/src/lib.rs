@@ -1,4 +1,4 @@
-use std::{sync::OnceLock, time::Duration};
+use std::time::Duration;
 
 use anyhow::Context;
 use reqwest::StatusCode;
@@ -7,23 +7,27 @@ use serde::{Serialize, de::DeserializeOwned};
 use serde_json::json;
 
 pub mod api;
+pub mod auth;
+mod copy;
+pub mod tls;
+pub mod tunnel;
+mod ws;
 
-fn periphery_http_client() -> &'static reqwest::Client {
-  static PERIPHERY_HTTP_CLIENT: OnceLock<reqwest::Client> =
-    OnceLock::new();
-  PERIPHERY_HTTP_CLIENT.get_or_init(|| {
-    reqwest::Client::builder()
-      // Use to allow communication with Periphery self-signed certs.
-      .danger_accept_invalid_certs(true)
-      .build()
-      .expect("Failed to build Periphery http client")
-  })
-}
+pub use ws::PeripheryWebSocket;
+
+use crate::{tls::periphery_http_client, tunnel::PeripheryTunnel};
 
+#[derive(Clone)]
 pub struct PeripheryClient {
   address: String,
   passkey: String,
   timeout: Duration,
+  /// Set for periphery agents which connect out to core.
+  tunnel: Option<PeripheryTunnel>,
+  /// The pinned periphery certificate fingerprint.
+  certificate_fingerprint: Option<String>,
+  /// The periphery docker endpoint to run requests against.
+  docker_endpoint: Option<String>,
 }
 
 impl PeripheryClient {
@@ -36,7 +40,64 @@ impl PeripheryClient {
       address: address.into(),
       passkey: passkey.into(),
       timeout: timeout.into(),
+      tunnel: None,
+      certificate_fingerprint: None,
+      docker_endpoint: None,
+    }
+  }
+
+  /// Send the requests over the connection
+  /// opened by periphery, rather than to the address.
+  pub fn with_tunnel(mut self, tunnel: PeripheryTunnel) -> Self {
+    self.tunnel = Some(tunnel);
+    self
+  }
+
+  /// Reject the connection unless periphery
+  /// presents the certificate with this fingerprint.
+  pub fn with_certificate_fingerprint(
+    mut self,
+    fingerprint: impl Into<String>,
+  ) -> Self {
+    self.certificate_fingerprint = Some(fingerprint.into());
+    self
+  }
+
+  /// Run the requests against the periphery docker endpoint
+  /// with this name. Empty uses the default endpoint.
+  pub fn with_docker_endpoint(
+    mut self,
+    docker_endpoint: impl Into<String>,
+  ) -> Self {
+    let docker_endpoint = docker_endpoint.into();
+    self.docker_endpoint =
+      (!docker_endpoint.is_empty()).then_some(docker_endpoint);
+    self
+  }
+
+  /// Connects without verifying the certificate, and returns
+  /// the fingerprint periphery presented for it to be pinned.
+  /// The passkey is not sent. Returns None if periphery is not using https.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn fetch_certificate_fingerprint(
+    &self,
+  ) -> anyhow::Result<Option<String>> {
+    if self.tunnel.is_some() || !self.address.starts_with("https") {
+      return Ok(None);
     }
+    let res = periphery_http_client(None)?
+      .get(&self.address)
+      .timeout(self.timeout)
+      .send()
+      .await
+      .context("failed at request to periphery")?;
+    let fingerprint = res
+      .extensions()
+      .get::<reqwest::tls::TlsInfo>()
+      .and_then(|info| info.peer_certificate())
+      .map(tls::certificate_fingerprint)
+      .context("periphery did not present a certificate")?;
+    Ok(Some(fingerprint))
   }
 
   // tracing will skip self, to avoid including passkey in traces
@@ -81,18 +142,40 @@ impl PeripheryClient {
     tracing::trace!(
       "sending request | type: {req_type} | body: {request:?}"
     );
-    let mut req = periphery_http_client()
-      .post(&self.address)
-      .json(&json!({
-        "type": req_type,
-        "params": request
-      }))
-      .header("authorization", &self.passkey);
+    let mut body = json!({
+      "type": req_type,
+      "params": request
+    });
+    if let Some(docker_endpoint) = &self.docker_endpoint {
+      body["docker_endpoint"] = json!(docker_endpoint);
+    }
+    if let Some(tunnel) = &self.tunnel {
+      let res = tunnel.send(body, timeout).await?;
+      let status = StatusCode::from_u16(res.status)
+        .context("periphery responded with invalid status")?;
+      tracing::debug!(
+        "got tunnel response | type: {req_type} | {status}"
+      );
+      if status == StatusCode::OK {
+        return serde_json::from_str(&res.body).with_context(|| format!(
+          "failed to parse response to json | type: {req_type} | body: {request:?}"
+        ));
+      } else {
+        return Err(
+          serror::deserialize_error(res.body).context(status),
+        );
+      }
+    }
+    let client =
+      periphery_http_client(self.certificate_fingerprint.as_deref())?;
+    let mut req = client.post(&self.address).json(&body);
     if let Some(timeout) = timeout {
       req = req.timeout(timeout);
     }
-    let res =
-      req.send().await.context("failed at request to periphery")?;
+    let res = client
+      .execute(self.signed_request(req)?)
+      .await
+      .context("failed at request to periphery")?;
     let status = res.status();
     tracing::debug!(
       "got response | type: {req_type} | {status} | body: {res:?}",
@@ -0,0 +1,201 @@
+//! Periphery usually serves self-signed certs, so rather than
+//! verifying against root CAs, core pins each server's certificate
+//! by fingerprint. Core can also present a client certificate,
+//! which periphery can require (mutual TLS).
+
+use std::{
+  collections::HashMap,
+  path::Path,
+  sync::{Arc, Mutex, OnceLock},
+};
+
+use anyhow::{Context, anyhow};
+use rustls::{
+  DigitallySignedStruct, SignatureScheme,
+  client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+  },
+  crypto::{
+    CryptoProvider, verify_tls12_signature, verify_tls13_signature,
+  },
+  pki_types::{
+    CertificateDer, PrivateKeyDer, ServerName, UnixTime,
+    pem::PemObject,
+  },
+};
+use sha2::{Digest, Sha256};
+
+/// The lowercase hex sha256 of the DER encoded certificate.
+pub fn certificate_fingerprint(der: &[u8]) -> String {
+  hex::encode(Sha256::digest(der))
+}
+
+/// Fingerprints may be configured in other formats,
+/// eg `AB:CD:...` as output by openssl.
+pub fn normalize_fingerprint(fingerprint: &str) -> String {
+  fingerprint
+    .chars()
+    .filter(|c| c.is_ascii_hexdigit())
+    .collect::<String>()
+    .to_lowercase()
+}
+
+/// Reads the fingerprint of the first certificate in the pem file.
+pub fn certificate_file_fingerprint(
+  path: &Path,
+) -> anyhow::Result<String> {
+  let cert =
+    CertificateDer::from_pem_file(path).with_context(|| {
+      format!("Failed to read certificate from {path:?}")
+    })?;
+  Ok(certificate_fingerprint(&cert))
+}
+
+struct ClientIdentity {
+  certs: Vec<CertificateDer<'static>>,
+  key: PrivateKeyDer<'static>,
+}
+
+static CLIENT_IDENTITY: OnceLock<ClientIdentity> = OnceLock::new();
+
+/// Present the certificate to periphery on every connection.
+/// Call once on startup, before any requests are made.
+pub fn init_client_identity(
+  cert_file: &Path,
+  key_file: &Path,
+) -> anyhow::Result<()> {
+  let certs = CertificateDer::pem_file_iter(cert_file)
+    .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+    .with_context(|| {
+      format!("Failed to read client certificate from {cert_file:?}")
+    })?;
+  if certs.is_empty() {
+    return Err(anyhow!("No certificates found in {cert_file:?}"));
+  }
+  let key =
+    PrivateKeyDer::from_pem_file(key_file).with_context(|| {
+      format!("Failed to read client key from {key_file:?}")
+    })?;
+  CLIENT_IDENTITY
+    .set(ClientIdentity { certs, key })
+    .map_err(|_| anyhow!("Client identity already initialized"))
+}
+
+/// Clients are cached by the pinned fingerprint.
+/// No pinned fingerprint accepts any certificate.
+pub(crate) fn periphery_http_client(
+  fingerprint: Option<&str>,
+) -> anyhow::Result<reqwest::Client> {
+  static PERIPHERY_HTTP_CLIENTS: OnceLock<
+    Mutex<HashMap<Option<String>, reqwest::Client>>,
+  > = OnceLock::new();
+  let fingerprint = fingerprint.map(normalize_fingerprint);
+  let mut clients = PERIPHERY_HTTP_CLIENTS
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap();
+  if let Some(client) = clients.get(&fingerprint) {
+    return Ok(client.clone());
+  }
+  let client = build_http_client(fingerprint.clone())?;
+  clients.insert(fingerprint, client.clone());
+  Ok(client)
+}
+
+fn build_http_client(
+  fingerprint: Option<String>,
+) -> anyhow::Result<reqwest::Client> {
+  let provider =
+    CryptoProvider::get_default().cloned().unwrap_or_else(|| {
+      Arc::new(rustls::crypto::ring::default_provider())
+    });
+  let builder =
+    rustls::ClientConfig::builder_with_provider(provider.clone())
+      .with_safe_default_protocol_versions()
+      .context("Invalid tls protocol versions")?
+      .dangerous()
+      .with_custom_certificate_verifier(Arc::new(
+        PinnedCertVerifier {
+          fingerprint,
+          provider,
+        },
+      ));
+  let config = match CLIENT_IDENTITY.get() {
+    Some(identity) => builder
+      .with_client_auth_cert(
+        identity.certs.clone(),
+        identity.key.clone_key(),
+      )
+      .context("Invalid client certificate")?,
+    None => builder.with_no_client_auth(),
+  };
+  reqwest::Client::builder()
+    .use_preconfigured_tls(config)
+    .tls_info(true)
+    .build()
+    .context("Failed to build Periphery http client")
+}
+
+#[derive(Debug)]
+struct PinnedCertVerifier {
+  fingerprint: Option<String>,
+  provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    _intermediates: &[CertificateDer<'_>],
+    _server_name: &ServerName<'_>,
+    _ocsp_response: &[u8],
+    _now: UnixTime,
+  ) -> Result<ServerCertVerified, rustls::Error> {
+    let Some(pinned) = &self.fingerprint else {
+      return Ok(ServerCertVerified::assertion());
+    };
+    let fingerprint = certificate_fingerprint(end_entity);
+    if &fingerprint == pinned {
+      Ok(ServerCertVerified::assertion())
+    } else {
+      Err(rustls::Error::General(format!(
+        "Periphery certificate fingerprint {fingerprint} does not match pinned fingerprint {pinned}"
+      )))
+    }
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    verify_tls12_signature(
+      message,
+      cert,
+      dss,
+      &self.provider.signature_verification_algorithms,
+    )
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    verify_tls13_signature(
+      message,
+      cert,
+      dss,
+      &self.provider.signature_verification_algorithms,
+    )
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self
+      .provider
+      .signature_verification_algorithms
+      .supported_schemes()
+  }
+}
@@ -0,0 +1,162 @@
+//! Periphery agents which can't accept inbound connections
+//! connect out to core over websocket instead,
+//! and core sends the requests back over the same connection.
+
+use std::{
+  collections::HashMap,
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+  },
+  time::Duration,
+};
+
+use anyhow::{Context, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::auth::{sign_request, verify_signature};
+
+/// Periphery connects to core at this path.
+pub const TUNNEL_PATH: &str = "/ws/periphery";
+
+/// Periphery passes the Server id or name with this header
+/// when connecting, along with the signature headers from [crate::auth].
+pub const TUNNEL_SERVER_HEADER: &str = "x-komodo-server";
+
+/// Signs the connection with the passkey for the Server.
+/// The signature covers the Server, so it can't be
+/// used to connect as another Server.
+pub fn sign_tunnel_connection(
+  passkey: &str,
+  server: &str,
+  timestamp: i64,
+  nonce: &str,
+) -> String {
+  sign_request(
+    passkey,
+    "GET",
+    TUNNEL_PATH,
+    timestamp,
+    nonce,
+    server.as_bytes(),
+  )
+}
+
+/// Compares in constant time.
+pub fn verify_tunnel_connection(
+  passkey: &str,
+  server: &str,
+  timestamp: i64,
+  nonce: &str,
+  signature: &str,
+) -> bool {
+  verify_signature(
+    passkey,
+    "GET",
+    TUNNEL_PATH,
+    timestamp,
+    nonce,
+    server.as_bytes(),
+    signature,
+  )
+}
+
+/// Sent from core to periphery as a Text message.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TunnelRequest {
+  pub id: u64,
+  /// The json serialized periphery request,
+  /// the same as the http request body.
+  pub request: Value,
+}
+
+/// Sent from periphery to core as a Text message,
+/// mirroring the http response.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TunnelResponse {
+  pub id: u64,
+  /// The http status code
+  pub status: u16,
+  pub body: String,
+}
+
+type PendingResponses =
+  Arc<Mutex<HashMap<u64, oneshot::Sender<TunnelResponse>>>>;
+
+/// Core's handle to a connected periphery,
+/// which requests are sent over instead of http.
+#[derive(Clone)]
+pub struct PeripheryTunnel {
+  sender: mpsc::Sender<TunnelRequest>,
+  pending: PendingResponses,
+  next_id: Arc<AtomicU64>,
+}
+
+impl PeripheryTunnel {
+  /// The receiver gets the requests
+  /// to forward over the periphery connection.
+  pub fn new() -> (PeripheryTunnel, mpsc::Receiver<TunnelRequest>) {
+    let (sender, receiver) = mpsc::channel(100);
+    let tunnel = PeripheryTunnel {
+      sender,
+      pending: Default::default(),
+      next_id: Default::default(),
+    };
+    (tunnel, receiver)
+  }
+
+  /// Resolves the pending request matching the response id.
+  pub fn respond(&self, response: TunnelResponse) {
+    let sender = self.pending.lock().unwrap().remove(&response.id);
+    if let Some(sender) = sender {
+      let _ = sender.send(response);
+    }
+  }
+
+  /// Fails all the pending requests,
+  /// call once the connection is closed.
+  pub fn close(&self) {
+    self.pending.lock().unwrap().clear();
+  }
+
+  /// Whether both handles are for the same connection.
+  pub fn is_same(&self, other: &PeripheryTunnel) -> bool {
+    Arc::ptr_eq(&self.pending, &other.pending)
+  }
+
+  pub(crate) async fn send(
+    &self,
+    request: Value,
+    timeout: Option<Duration>,
+  ) -> anyhow::Result<TunnelResponse> {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = oneshot::channel();
+    self.pending.lock().unwrap().insert(id, sender);
+    if self
+      .sender
+      .send(TunnelRequest { id, request })
+      .await
+      .is_err()
+    {
+      self.pending.lock().unwrap().remove(&id);
+      return Err(anyhow!("periphery connection is closed"));
+    }
+    let res = match timeout {
+      Some(timeout) => {
+        match tokio::time::timeout(timeout, receiver).await {
+          Ok(res) => res,
+          Err(_) => {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(anyhow!(
+              "timed out waiting for periphery response"
+            ));
+          }
+        }
+      }
+      None => receiver.await,
+    };
+    res.context("periphery connection closed before response")
+  }
+}
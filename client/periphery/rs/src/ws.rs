@@ -0,0 +1,151 @@
+use anyhow::{Context, anyhow};
+use futures::SinkExt;
+use reqwest::{
+  RequestBuilder, StatusCode, Upgraded,
+  header::{
+    CONNECTION, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE,
+  },
+};
+use serde::Serialize;
+use tokio_tungstenite::{
+  WebSocketStream,
+  tungstenite::{
+    Message, handshake::client::generate_key, protocol::Role,
+  },
+};
+
+use crate::{
+  PeripheryClient,
+  api::{
+    DockerEndpointQuery, compose::FollowComposeLog,
+    container::FollowContainerLog, terminal::ContainerExecQuery,
+  },
+  tls::periphery_http_client,
+};
+
+pub type PeripheryWebSocket = WebSocketStream<Upgraded>;
+
+impl PeripheryClient {
+  /// Opens an interactive shell in the container.
+  /// Stdin / stdout are passed as Binary messages.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn connect_container_exec(
+    &self,
+    query: &ContainerExecQuery,
+  ) -> anyhow::Result<PeripheryWebSocket> {
+    self
+      .connect_websocket(
+        self.websocket_request("/terminal/container")?.query(query),
+      )
+      .await
+  }
+
+  /// Follows the container log.
+  /// Each log line is passed as a Text message.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn follow_container_log(
+    &self,
+    request: &FollowContainerLog,
+  ) -> anyhow::Result<PeripheryWebSocket> {
+    self
+      .connect_websocket_with_request("/log/container", request)
+      .await
+  }
+
+  /// Follows the compose project log.
+  /// Each log line is passed as a Text message.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn follow_compose_log(
+    &self,
+    request: &FollowComposeLog,
+  ) -> anyhow::Result<PeripheryWebSocket> {
+    self
+      .connect_websocket_with_request("/log/compose", request)
+      .await
+  }
+
+  /// Subscribes to container / image / network / volume events.
+  /// Each event is passed as a Text message containing a json [DockerEvent].
+  ///
+  /// [DockerEvent]: crate::api::events::DockerEvent
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn subscribe_docker_events(
+    &self,
+  ) -> anyhow::Result<PeripheryWebSocket> {
+    self
+      .connect_websocket(self.websocket_request("/events")?)
+      .await
+  }
+
+  /// The request params are sent as the first message,
+  /// as they don't all fit in the query.
+  async fn connect_websocket_with_request(
+    &self,
+    path: &str,
+    request: &impl Serialize,
+  ) -> anyhow::Result<PeripheryWebSocket> {
+    let mut socket = self
+      .connect_websocket(self.websocket_request(path)?)
+      .await?;
+    let request = serde_json::to_string(request)
+      .context("failed to serialize request")?;
+    socket
+      .send(Message::text(request))
+      .await
+      .context("failed to send request to periphery")?;
+    Ok(socket)
+  }
+
+  fn websocket_request(
+    &self,
+    path: &str,
+  ) -> anyhow::Result<RequestBuilder> {
+    let req =
+      periphery_http_client(self.certificate_fingerprint.as_deref())?
+        .get(format!("{}{path}", self.address.trim_end_matches('/')))
+        .query(&DockerEndpointQuery {
+          docker_endpoint: self.docker_endpoint.clone(),
+        });
+    Ok(req)
+  }
+
+  async fn connect_websocket(
+    &self,
+    req: RequestBuilder,
+  ) -> anyhow::Result<PeripheryWebSocket> {
+    if self.tunnel.is_some() {
+      return Err(anyhow!(
+        "Not available for servers using an outbound connection"
+      ));
+    }
+    // The client only speaks http 1, which supports the upgrade.
+    let req = self.signed_request(
+      req
+        .header(CONNECTION, "Upgrade")
+        .header(UPGRADE, "websocket")
+        .header(SEC_WEBSOCKET_VERSION, "13")
+        .header(SEC_WEBSOCKET_KEY, generate_key()),
+    )?;
+    let res =
+      periphery_http_client(self.certificate_fingerprint.as_deref())?
+        .execute(req)
+        .await
+        .context("failed at request to periphery")?;
+    let status = res.status();
+    if status != StatusCode::SWITCHING_PROTOCOLS {
+      let text = res
+        .text()
+        .await
+        .context("failed to convert response to text")?;
+      return Err(serror::deserialize_error(text).context(status));
+    }
+    let upgraded = res
+      .upgrade()
+      .await
+      .context("failed to upgrade periphery connection")?;
+    Ok(
+      WebSocketStream::from_raw_socket(upgraded, Role::Client, None)
+        .await,
+    )
+  }
+}
@@ -9,20 +9,32 @@ const ALERT_TYPES: Types.AlertData["type"][] = [
   "ServerUnreachable",
   "ServerCpu",
   "ServerMem",
+  "ServerSwap",
   "ServerDisk",
+  "ServerDiskInodes",
+  "ServerDiskForecast",
+  "PeripheryVersionMismatch",
   // Stack
   "StackStateChange",
   "StackImageUpdateAvailable",
   "StackAutoUpdated",
+  "StackDeployFailed",
   // Deployment
   "ContainerStateChange",
   "DeploymentImageUpdateAvailable",
   "DeploymentAutoUpdated",
+  "DeployFailed",
+  // Container
+  "ContainerRestartLoop",
+  "ContainerOomKilled",
   // Misc
   "AwsBuilderTerminationFailed",
   "ResourceSyncPendingUpdates",
   "BuildFailed",
   "RepoBuildFailed",
+  "ProcedureFailed",
+  "ActionFailed",
+  "ResourceSyncFailed",
 ];
 
 export const AlertTypeConfig = ({
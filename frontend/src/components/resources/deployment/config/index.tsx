@@ -22,6 +22,7 @@ import {
   TerminationTimeout,
 } from "./components/term-signal";
 import { extract_registry_domain } from "@lib/utils";
+import { DockerEndpointSelector } from "@components/resources/server/docker-endpoint";
 
 export const DeploymentConfig = ({
   id,
@@ -92,6 +93,14 @@ export const DeploymentConfig = ({
                   </ConfigItem>
                 );
               },
+              docker_endpoint: (docker_endpoint, set) => (
+                <DockerEndpointSelector
+                  server_id={update.server_id ?? config.server_id}
+                  selected={docker_endpoint}
+                  onSelect={(docker_endpoint) => set({ docker_endpoint })}
+                  disabled={disabled}
+                />
+              ),
             },
           },
           {
@@ -302,6 +311,10 @@ export const DeploymentConfig = ({
             label: "Extra Args",
             labelHidden: true,
             components: {
+              engine_api: {
+                description:
+                  "Create the container through the Docker Engine API rather than 'docker run'. Extra Args are not supported.",
+              },
               extra_args: (value, set) => (
                 <ConfigItem
                   label="Extra Args"
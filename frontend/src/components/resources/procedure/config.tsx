@@ -632,6 +632,7 @@ type MinExecutionType = Exclude<
   | "DeleteNetwork"
   | "DeleteImage"
   | "DeleteVolume"
+  | "RestoreVolume"
   | "TestAlerter"
 >;
 
@@ -1277,6 +1278,39 @@ const TARGET_COMPONENTS: ExecutionConfigs = {
       />
     ),
   },
+  BackupVolume: {
+    params: { server: "", volume: "" },
+    Component: ({ params, setParams, disabled }) => (
+      <div className="flex gap-4 items-center flex-wrap">
+        <ResourceSelector
+          type="Server"
+          selected={params.server}
+          onSelect={(server) => setParams({ ...params, server })}
+          disabled={disabled}
+        />
+        <Input
+          placeholder="Volume name"
+          value={params.volume}
+          onChange={(e) => setParams({ ...params, volume: e.target.value })}
+          className="w-[200px]"
+          disabled={disabled}
+        />
+        <Input
+          placeholder="Backups to keep (default: all)"
+          value={params.keep?.toString() ?? ""}
+          onChange={(e) => {
+            const keep = Number(e.target.value);
+            setParams({
+              ...params,
+              keep: e.target.value && keep ? keep : undefined,
+            });
+          }}
+          className="w-[250px]"
+          disabled={disabled}
+        />
+      </div>
+    ),
+  },
   PruneDockerBuilders: {
     params: { server: "" },
     Component: ({ params, setParams, disabled }) => (
@@ -1,7 +1,9 @@
 import { Config } from "@components/config";
-import { ConfigList } from "@components/config/util";
+import { ConfigItem, ConfigList } from "@components/config/util";
+import { ConfirmButton } from "@components/util";
 import { useInvalidate, useLocalStorage, useRead, useWrite } from "@lib/hooks";
 import { Types } from "komodo_client";
+import { History, KeyRound, RotateCw } from "lucide-react";
 import { ReactNode } from "react";
 
 export const ServerConfig = ({
@@ -15,7 +17,8 @@ export const ServerConfig = ({
     target: { type: "Server", id },
   }).data;
   const invalidate = useInvalidate();
-  const config = useRead("GetServer", { server: id }).data?.config;
+  const server = useRead("GetServer", { server: id }).data;
+  const config = server?.config;
   const global_disabled =
     useRead("GetCoreInfo", {}).data?.ui_write_disabled ?? false;
   const [update, set] = useLocalStorage<Partial<Types.ServerConfig>>(
@@ -61,6 +64,16 @@ export const ServerConfig = ({
               },
             },
           },
+          {
+            label: "Outbound",
+            labelHidden: true,
+            components: {
+              outbound: {
+                description:
+                  "Periphery connects out to Core instead of Core connecting to the address. Use for hosts which can't accept inbound connections. Configure periphery with 'core_address', 'core_server' and 'core_passkey'. Container terminals, following logs, copying files and Docker events are not available over the outbound connection.",
+              },
+            },
+          },
           {
             label: "Enabled",
             labelHidden: true,
@@ -83,6 +96,32 @@ export const ServerConfig = ({
               },
             },
           },
+          {
+            label: "Certificate",
+            labelHidden: true,
+            components: {
+              ["certificate" as any]: () => (
+                <CertificateFingerprint
+                  id={id}
+                  fingerprint={server?.info?.certificate_fingerprint}
+                  disabled={disabled}
+                />
+              ),
+            },
+          },
+          {
+            label: "Passkey",
+            labelHidden: true,
+            components: {
+              ["passkey" as any]: () => (
+                <PasskeyRotation
+                  id={id}
+                  rotated={!!server?.info?.passkey}
+                  disabled={disabled}
+                />
+              ),
+            },
+          },
           {
             label: "Disks",
             labelHidden: true,
@@ -137,6 +176,17 @@ export const ServerConfig = ({
               },
             },
           },
+          {
+            label: "Version",
+            labelHidden: true,
+            components: {
+              send_version_mismatch_alerts: {
+                label: "Send Version Mismatch Alerts",
+                description:
+                  "Send an alert if the Periphery agent version does not match the Core version.",
+              },
+            },
+          },
           {
             label: "CPU",
             labelHidden: true,
@@ -177,6 +227,16 @@ export const ServerConfig = ({
                 description:
                   "Send a 'Critical' alert if the memory usage in % is above these thresholds",
               },
+              swap_warning: {
+                label: "Swap Warning",
+                description:
+                  "Send a 'Warning' alert if the swap usage in % is above these thresholds. Uses the memory alert toggle.",
+              },
+              swap_critical: {
+                label: "Swap Critical",
+                description:
+                  "Send a 'Critical' alert if the swap usage in % is above these thresholds. Uses the memory alert toggle.",
+              },
             },
           },
           {
@@ -196,6 +256,18 @@ export const ServerConfig = ({
                 description:
                   "Send a 'Critical' alert if the disk usage in % is above these thresholds",
               },
+              inode_warning: {
+                description:
+                  "Send a 'Warning' alert if the inode usage in % (for any mounted disk) is above these thresholds",
+              },
+              inode_critical: {
+                description:
+                  "Send a 'Critical' alert if the inode usage in % (for any mounted disk) is above these thresholds",
+              },
+              disk_forecast_warning_days: {
+                description:
+                  "Send a 'Warning' alert if a disk is forecast to be full within this many days, based on its growth over the last day. Set to 0 to disable.",
+              },
             },
           },
         ],
@@ -203,3 +275,97 @@ export const ServerConfig = ({
     />
   );
 };
+
+const CertificateFingerprint = ({
+  id,
+  fingerprint,
+  disabled,
+}: {
+  id: string;
+  fingerprint: string | undefined;
+  disabled: boolean;
+}) => {
+  const invalidate = useInvalidate();
+  const { mutate: rotate, isPending: rotatePending } = useWrite(
+    "RotateServerCertificate",
+    { onSuccess: () => invalidate(["GetServer"]) }
+  );
+  const { mutate: reset, isPending: resetPending } = useWrite(
+    "ResetServerCertificate",
+    { onSuccess: () => invalidate(["GetServer"]) }
+  );
+  return (
+    <ConfigItem
+      label="Certificate Fingerprint"
+      description="The Periphery ssl cert is pinned on first connection, and requests are rejected if Periphery presents a different cert. Rotate to have Periphery generate a new cert, or reset if the cert was replaced outside of Komodo."
+    >
+      <div className="flex items-center gap-4">
+        <div className="font-mono text-sm text-muted-foreground break-all">
+          {fingerprint || "Not pinned"}
+        </div>
+        <ConfirmButton
+          title="Rotate"
+          icon={<RotateCw className="w-4 h-4" />}
+          onClick={() => rotate({ server: id })}
+          loading={rotatePending}
+          disabled={disabled || !fingerprint}
+        />
+        <ConfirmButton
+          title="Reset"
+          variant="destructive"
+          icon={<History className="w-4 h-4" />}
+          onClick={() => reset({ server: id })}
+          loading={resetPending}
+          disabled={disabled || !fingerprint}
+        />
+      </div>
+    </ConfigItem>
+  );
+};
+
+const PasskeyRotation = ({
+  id,
+  rotated,
+  disabled,
+}: {
+  id: string;
+  rotated: boolean;
+  disabled: boolean;
+}) => {
+  const invalidate = useInvalidate();
+  const { mutate: rotate, isPending: rotatePending } = useWrite(
+    "RotateServerPasskey",
+    { onSuccess: () => invalidate(["GetServer"]) }
+  );
+  const { mutate: reset, isPending: resetPending } = useWrite(
+    "ResetServerPasskey",
+    { onSuccess: () => invalidate(["GetServer"]) }
+  );
+  return (
+    <ConfigItem
+      label="Passkey"
+      description="Requests to Periphery are signed with the passkey. Rotate to generate a new passkey for this Server only. Periphery accepts both passkeys until the rollover completes, after which the passkeys in the Periphery config are no longer accepted. Outbound Servers are assigned a passkey on first connection, and must connect with it from then on. Reset if the Periphery rotated passkey file was lost."
+    >
+      <div className="flex items-center gap-4">
+        <div className="text-sm text-muted-foreground">
+          {rotated ? "Rotated for this Server" : "Using the Core passkey"}
+        </div>
+        <ConfirmButton
+          title="Rotate Passkey"
+          icon={<KeyRound className="w-4 h-4" />}
+          onClick={() => rotate({ server: id })}
+          loading={rotatePending}
+          disabled={disabled}
+        />
+        <ConfirmButton
+          title="Reset"
+          variant="destructive"
+          icon={<History className="w-4 h-4" />}
+          onClick={() => reset({ server: id })}
+          loading={resetPending}
+          disabled={disabled || !rotated}
+        />
+      </div>
+    </ConfigItem>
+  );
+};
@@ -0,0 +1,72 @@
+import { ConfigItem } from "@components/config/util";
+import { useRead } from "@lib/hooks";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@ui/select";
+
+const DEFAULT_ENDPOINT = "Default";
+
+/** Only shown when the server's periphery has docker endpoints configured. */
+export const DockerEndpointSelector = ({
+  server_id,
+  selected,
+  onSelect,
+  disabled,
+}: {
+  server_id: string | undefined;
+  selected: string | undefined;
+  onSelect: (docker_endpoint: string) => void;
+  disabled: boolean;
+}) => {
+  const _endpoints =
+    useRead(
+      "ListDockerEndpoints",
+      { server: server_id! },
+      { enabled: !!server_id }
+    ).data ?? [];
+
+  if (!_endpoints.length && !selected) return null;
+
+  const endpoints =
+    !selected || _endpoints.includes(selected)
+      ? _endpoints
+      : [..._endpoints, selected];
+
+  return (
+    <ConfigItem
+      label="Docker Endpoint"
+      boldLabel
+      description="Choose the docker engine on the Server to deploy on."
+    >
+      <Select
+        value={selected || DEFAULT_ENDPOINT}
+        onValueChange={(value) =>
+          onSelect(value === DEFAULT_ENDPOINT ? "" : value)
+        }
+        disabled={disabled}
+      >
+        <SelectTrigger className="w-[200px]" disabled={disabled}>
+          <SelectValue placeholder="Select Endpoint" />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value={DEFAULT_ENDPOINT} className="cursor-pointer">
+            {DEFAULT_ENDPOINT}
+          </SelectItem>
+          {endpoints.map((endpoint) => (
+            <SelectItem
+              key={endpoint}
+              value={endpoint}
+              className="cursor-pointer"
+            >
+              {endpoint}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+    </ConfigItem>
+  );
+};
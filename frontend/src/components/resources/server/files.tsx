@@ -0,0 +1,241 @@
+import { Section } from "@components/layouts";
+import { MonacoEditor, MonacoLanguage } from "@components/monaco";
+import { ConfirmButton } from "@components/util";
+import { useInvalidate, useRead, useWrite } from "@lib/hooks";
+import { Types } from "komodo_client";
+import { Button } from "@ui/button";
+import { Card, CardContent, CardHeader } from "@ui/card";
+import { useToast } from "@ui/use-toast";
+import {
+  ArrowUp,
+  File,
+  Folder,
+  History,
+  Loader2,
+  Save,
+  Trash,
+} from "lucide-react";
+import { ReactNode, useState } from "react";
+import { useServer } from ".";
+import { fmt_date_with_minutes } from "@lib/formatting";
+
+const language_from_path = (path: string): MonacoLanguage => {
+  const name = path.split("/").pop() ?? "";
+  if (name.endsWith(".yaml") || name.endsWith(".yml")) return "yaml";
+  if (name.endsWith(".toml")) return "toml";
+  if (name.endsWith(".json")) return "json";
+  if (name.endsWith(".sh")) return "shell";
+  if (name === ".env" || name.endsWith(".env")) return "key_value";
+  if (name === "Dockerfile" || name.endsWith(".dockerfile"))
+    return "dockerfile";
+  return undefined;
+};
+
+const parent_dir = (path: string) => {
+  const parent = path.split("/").slice(0, -1).join("/");
+  return parent || "/";
+};
+
+const format_size = (size: number) => {
+  if (size < 1024) return `${size} B`;
+  if (size < 1024 * 1024) return `${(size / 1024).toFixed(1)} KiB`;
+  return `${(size / 1024 / 1024).toFixed(1)} MiB`;
+};
+
+export const ServerFiles = ({
+  id,
+  titleOther,
+}: {
+  id: string;
+  titleOther: ReactNode;
+}) => {
+  const state = useServer(id)?.info.state ?? Types.ServerState.NotOk;
+  // Empty dir lists the file roots
+  const [dir, setDir] = useState("");
+  const [file, setFile] = useState<string>();
+
+  if (state !== Types.ServerState.Ok) {
+    return (
+      <Section titleOther={titleOther}>
+        <h2 className="text-muted-foreground">
+          Server unreachable, files are not available
+        </h2>
+      </Section>
+    );
+  }
+
+  return (
+    <Section titleOther={titleOther}>
+      {file ? (
+        <FileEditor
+          id={id}
+          path={file}
+          close={() => setFile(undefined)}
+        />
+      ) : (
+        <Directory
+          id={id}
+          dir={dir}
+          setDir={setDir}
+          openFile={setFile}
+        />
+      )}
+    </Section>
+  );
+};
+
+const Directory = ({
+  id,
+  dir,
+  setDir,
+  openFile,
+}: {
+  id: string;
+  dir: string;
+  setDir: (dir: string) => void;
+  openFile: (path: string) => void;
+}) => {
+  const { data: roots } = useRead("ListServerFiles", { server: id });
+  const { data: entries, isPending } = useRead("ListServerFiles", {
+    server: id,
+    path: dir,
+  });
+  const is_root = !dir || roots?.some((root) => root.path === dir);
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center gap-4 pb-4">
+        <Button
+          variant="outline"
+          size="icon"
+          disabled={!dir}
+          onClick={() => setDir(is_root ? "" : parent_dir(dir))}
+        >
+          <ArrowUp className="w-4 h-4" />
+        </Button>
+        <div className="font-mono">{dir || "File roots"}</div>
+        {isPending && <Loader2 className="w-4 h-4 animate-spin" />}
+      </CardHeader>
+      <CardContent className="flex flex-col gap-1">
+        {entries?.length === 0 && (
+          <div className="text-muted-foreground">Empty directory</div>
+        )}
+        {entries?.map((entry) => (
+          <div
+            key={entry.path}
+            className="flex items-center justify-between gap-4 px-2 py-1 rounded-md cursor-pointer hover:bg-accent"
+            onClick={() =>
+              entry.is_dir ? setDir(entry.path) : openFile(entry.path)
+            }
+          >
+            <div className="flex items-center gap-2 font-mono">
+              {entry.is_dir ? (
+                <Folder className="w-4 h-4" />
+              ) : (
+                <File className="w-4 h-4" />
+              )}
+              {entry.name}
+            </div>
+            <div className="flex items-center gap-4 text-muted-foreground text-sm">
+              {!entry.is_dir && <div>{format_size(entry.size)}</div>}
+              <div>{fmt_date_with_minutes(new Date(entry.modified))}</div>
+              {/* The file roots themselves can't be deleted */}
+              {dir && <DeleteFile id={id} path={entry.path} />}
+            </div>
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+};
+
+const FileEditor = ({
+  id,
+  path,
+  close,
+}: {
+  id: string;
+  path: string;
+  close: () => void;
+}) => {
+  const { data: file, isPending } = useRead("GetServerFile", {
+    server: id,
+    path,
+  });
+  const [edit, setEdit] = useState<string>();
+  const { toast } = useToast();
+  const invalidate = useInvalidate();
+  const { mutate, isPending: writePending } = useWrite("WriteServerFile", {
+    onSuccess: (res) => {
+      toast({
+        title: res.success ? "Contents written." : "Failed to write contents.",
+        variant: res.success ? undefined : "destructive",
+      });
+      if (res.success) {
+        setEdit(undefined);
+        invalidate(["GetServerFile"], ["ListServerFiles"]);
+      }
+    },
+  });
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between gap-4 pb-4">
+        <div className="flex items-center gap-4">
+          <Button variant="outline" size="icon" onClick={close}>
+            <ArrowUp className="w-4 h-4" />
+          </Button>
+          <div className="font-mono">{path}</div>
+          {isPending && <Loader2 className="w-4 h-4 animate-spin" />}
+        </div>
+        <div className="flex items-center gap-2">
+          <Button
+            variant="outline"
+            className="flex items-center gap-2"
+            disabled={edit === undefined}
+            onClick={() => setEdit(undefined)}
+          >
+            <History className="w-4 h-4" />
+            Reset
+          </Button>
+          <ConfirmButton
+            title="Save"
+            icon={<Save className="w-4 h-4" />}
+            disabled={edit === undefined}
+            loading={writePending}
+            onClick={() =>
+              edit !== undefined &&
+              mutate({ server: id, path, contents: edit })
+            }
+          />
+        </div>
+      </CardHeader>
+      <CardContent>
+        {file && (
+          <MonacoEditor
+            value={edit ?? file.contents}
+            language={language_from_path(path)}
+            onValueChange={setEdit}
+          />
+        )}
+      </CardContent>
+    </Card>
+  );
+};
+
+const DeleteFile = ({ id, path }: { id: string; path: string }) => {
+  const invalidate = useInvalidate();
+  const { mutate, isPending } = useWrite("DeleteServerFile", {
+    onSuccess: () => invalidate(["ListServerFiles"]),
+  });
+  return (
+    <div onClick={(e) => e.stopPropagation()}>
+      <ConfirmButton
+        variant="ghost"
+        size="icon"
+        title="Delete"
+        icon={<Trash className="w-4 h-4" />}
+        loading={isPending}
+        onClick={() => mutate({ server: id, path })}
+      />
+    </div>
+  );
+};
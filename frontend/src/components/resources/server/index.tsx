@@ -36,6 +36,8 @@ import { StackTable } from "../stack/table";
 import { ResourceComponents } from "..";
 import { ServerInfo } from "./info";
 import { ServerStats } from "./stats";
+import { ServerFiles } from "./files";
+import { useEditPermissions } from "@pages/resource";
 import { RenameResource } from "@components/config/util";
 import { GroupActions } from "@components/group-actions";
 
@@ -61,8 +63,9 @@ const Icon = ({ id, size }: { id?: string; size: number }) => {
 
 const ConfigStatsDockerResources = ({ id }: { id: string }) => {
   const [view, setView] = useLocalStorage<
-    "Config" | "Stats" | "Docker" | "Resources"
+    "Config" | "Stats" | "Docker" | "Files" | "Resources"
   >(`server-${id}-tab`, "Config");
+  const { canWrite } = useEditPermissions({ type: "Server", id });
 
   const is_admin = useUser().data?.admin ?? false;
   const disable_non_admin_create =
@@ -86,7 +89,10 @@ const ConfigStatsDockerResources = ({ id }: { id: string }) => {
 
   const noResources = noDeployments && noRepos && noStacks;
 
-  const currentView = view === "Resources" && noResources ? "Config" : view;
+  const currentView =
+    (view === "Resources" && noResources) || (view === "Files" && !canWrite)
+      ? "Config"
+      : view;
 
   const tabsList = (
     <TabsList className="justify-start w-fit">
@@ -102,6 +108,10 @@ const ConfigStatsDockerResources = ({ id }: { id: string }) => {
         Docker
       </TabsTrigger>
 
+      <TabsTrigger value="Files" className="w-[110px]" disabled={!canWrite}>
+        Files
+      </TabsTrigger>
+
       <TabsTrigger
         value="Resources"
         className="w-[110px]"
@@ -129,6 +139,10 @@ const ConfigStatsDockerResources = ({ id }: { id: string }) => {
         <ServerInfo id={id} titleOther={tabsList} />
       </TabsContent>
 
+      <TabsContent value="Files">
+        <ServerFiles id={id} titleOther={tabsList} />
+      </TabsContent>
+
       <TabsContent value="Resources">
         <Section titleOther={tabsList}>
           <Section
@@ -0,0 +1,252 @@
+import { Section } from "@components/layouts";
+import { DockerResourceLink } from "@components/util";
+import { format_size_bytes } from "@lib/formatting";
+import { useRead } from "@lib/hooks";
+import { Badge } from "@ui/badge";
+import { DataTable, SortableHeader } from "@ui/data-table";
+import { Types } from "komodo_client";
+import { ReactNode } from "react";
+
+export const DiskUsage = ({
+  id,
+  titleOther,
+}: {
+  id: string;
+  titleOther: ReactNode;
+}) => {
+  const usage = useRead(
+    "GetDockerDiskUsage",
+    { server: id },
+    { refetchInterval: 30_000 }
+  ).data;
+
+  const summary: (Types.DiskUsageSummary & { type: string })[] = usage
+    ? [
+        { type: "Images", ...usage.images },
+        { type: "Containers", ...usage.containers },
+        { type: "Volumes", ...usage.volumes },
+        { type: "Build Cache", ...usage.build_cache },
+      ]
+    : [];
+
+  return (
+    <Section titleOther={titleOther}>
+      <DataTable
+        tableKey="server-disk-usage"
+        data={summary}
+        columns={[
+          { accessorKey: "type", header: "Type" },
+          { accessorKey: "total", header: "Total" },
+          { accessorKey: "active", header: "Active" },
+          {
+            accessorKey: "size",
+            header: "Size",
+            cell: ({ row }) => format_size_bytes(row.original.size),
+          },
+          {
+            accessorKey: "reclaimable",
+            header: "Reclaimable",
+            cell: ({ row }) => format_size_bytes(row.original.reclaimable),
+          },
+        ]}
+      />
+
+      <Section title="Images">
+        <DataTable
+          tableKey="server-disk-usage-images"
+          data={usage?.image_usage ?? []}
+          columns={[
+            {
+              accessorKey: "name",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Name" />
+              ),
+              cell: ({ row }) => (
+                <DockerResourceLink
+                  type="image"
+                  server_id={id}
+                  name={row.original.name}
+                  id={row.original.id}
+                  extra={
+                    row.original.containers === 0 && (
+                      <Badge variant="destructive">Unused</Badge>
+                    )
+                  }
+                />
+              ),
+              size: 200,
+            },
+            {
+              accessorKey: "size",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Size" />
+              ),
+              cell: ({ row }) => format_size_bytes(row.original.size),
+            },
+            {
+              accessorKey: "shared_size",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Shared" />
+              ),
+              cell: ({ row }) => format_size_bytes(row.original.shared_size),
+            },
+            {
+              accessorKey: "unique_size",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Unique" />
+              ),
+              cell: ({ row }) => format_size_bytes(row.original.unique_size),
+            },
+            {
+              accessorKey: "containers",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Containers" />
+              ),
+            },
+          ]}
+        />
+      </Section>
+
+      <Section title="Containers">
+        <DataTable
+          tableKey="server-disk-usage-containers"
+          data={usage?.container_usage ?? []}
+          columns={[
+            {
+              accessorKey: "name",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Name" />
+              ),
+              cell: ({ row }) => (
+                <DockerResourceLink
+                  type="container"
+                  server_id={id}
+                  name={row.original.name}
+                />
+              ),
+              size: 200,
+            },
+            {
+              accessorKey: "state",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="State" />
+              ),
+            },
+            {
+              accessorKey: "size_rw",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Writable Layer" />
+              ),
+              cell: ({ row }) => format_size_bytes(row.original.size_rw),
+            },
+            {
+              accessorKey: "size_root_fs",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Total" />
+              ),
+              cell: ({ row }) => format_size_bytes(row.original.size_root_fs),
+            },
+          ]}
+        />
+      </Section>
+
+      <Section title="Volumes">
+        <DataTable
+          tableKey="server-disk-usage-volumes"
+          data={usage?.volume_usage ?? []}
+          columns={[
+            {
+              accessorKey: "name",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Name" />
+              ),
+              cell: ({ row }) => (
+                <DockerResourceLink
+                  type="volume"
+                  server_id={id}
+                  name={row.original.name}
+                  extra={
+                    row.original.ref_count === 0 && (
+                      <Badge variant="destructive">Unused</Badge>
+                    )
+                  }
+                />
+              ),
+              size: 200,
+            },
+            {
+              accessorKey: "driver",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Driver" />
+              ),
+            },
+            {
+              accessorKey: "size",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Size" />
+              ),
+              cell: ({ row }) =>
+                row.original.size < 0
+                  ? "Unknown"
+                  : format_size_bytes(row.original.size),
+            },
+            {
+              accessorKey: "ref_count",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Containers" />
+              ),
+            },
+          ]}
+        />
+      </Section>
+
+      <Section title="Build Cache">
+        <DataTable
+          tableKey="server-disk-usage-build-cache"
+          data={usage?.build_cache_usage ?? []}
+          columns={[
+            {
+              accessorKey: "id",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Id" />
+              ),
+            },
+            {
+              accessorKey: "typ",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Type" />
+              ),
+            },
+            {
+              accessorKey: "size",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Size" />
+              ),
+              cell: ({ row }) => format_size_bytes(row.original.size),
+            },
+            {
+              accessorKey: "in_use",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="In Use" />
+              ),
+              cell: ({ row }) => (row.original.in_use ? "Yes" : "No"),
+            },
+            {
+              accessorKey: "shared",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Shared" />
+              ),
+              cell: ({ row }) => (row.original.shared ? "Yes" : "No"),
+            },
+            {
+              accessorKey: "last_used_at",
+              header: ({ column }) => (
+                <SortableHeader column={column} title="Last Used" />
+              ),
+            },
+          ]}
+        />
+      </Section>
+    </Section>
+  );
+};
@@ -7,6 +7,7 @@ import { useLocalStorage } from "@lib/hooks";
 import { Images } from "./images";
 import { Containers } from "./containers";
 import { Volumes } from "./volumes";
+import { DiskUsage } from "./disk-usage";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@ui/tabs";
 
 export const ServerInfo = ({
@@ -18,7 +19,7 @@ export const ServerInfo = ({
 }) => {
   const state = useServer(id)?.info.state ?? Types.ServerState.NotOk;
   const [show2, setShow2] = useLocalStorage<
-    "Containers" | "Networks" | "Volumes" | "Images"
+    "Containers" | "Networks" | "Volumes" | "Images" | "Disk Usage"
   >("server-info-show-config-v2", "Containers");
 
   if ([Types.ServerState.NotOk, Types.ServerState.Disabled].includes(state)) {
@@ -45,6 +46,9 @@ export const ServerInfo = ({
       <TabsTrigger value="Images" className="w-[110px]">
         Images
       </TabsTrigger>
+      <TabsTrigger value="Disk Usage" className="w-[110px]">
+        Disk Usage
+      </TabsTrigger>
     </TabsList>
   );
 
@@ -63,6 +67,9 @@ export const ServerInfo = ({
         <TabsContent value="Images">
           <Images id={id} titleOther={tabsList} />
         </TabsContent>
+        <TabsContent value="Disk Usage">
+          <DiskUsage id={id} titleOther={tabsList} />
+        </TabsContent>
       </Tabs>
     </Section>
   );
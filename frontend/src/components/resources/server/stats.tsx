@@ -93,6 +93,7 @@ export const ServerStats = ({
           <div className="flex flex-col xl:flex-row gap-4">
             <CPU stats={stats} />
             <RAM stats={stats} />
+            <SWAP stats={stats} />
             <DISK stats={stats} />
             <NETWORK stats={stats} />
           </div>
@@ -366,6 +367,18 @@ const RAM = ({ stats }: { stats: Types.SystemStats | undefined }) => {
   );
 };
 
+const SWAP = ({ stats }: { stats: Types.SystemStats | undefined }) => {
+  const used = stats?.swap_used_gb ?? 0;
+  const total = stats?.swap_total_gb ?? 0;
+  return (
+    <StatBar
+      title="Swap Usage"
+      icon={<MemoryStick className="w-5 h-5" />}
+      percentage={total > 0 ? (used / total) * 100 : 0}
+    />
+  );
+};
+
 const DISK = ({ stats }: { stats: Types.SystemStats | undefined }) => {
   const used = stats?.disks.reduce((acc, curr) => (acc += curr.used_gb), 0);
   const total = stats?.disks.reduce((acc, curr) => (acc += curr.total_gb), 0);
@@ -22,6 +22,7 @@ import {
 } from "@lib/hooks";
 import { ReactNode } from "react";
 import { CopyWebhook, ResourceLink, ResourceSelector } from "../common";
+import { DockerEndpointSelector } from "../server/docker-endpoint";
 import {
   Select,
   SelectContent,
@@ -152,6 +153,14 @@ export const StackConfig = ({
           </ConfigItem>
         );
       },
+      docker_endpoint: (docker_endpoint, set) => (
+        <DockerEndpointSelector
+          server_id={update.server_id ?? config.server_id}
+          selected={docker_endpoint}
+          onSelect={(docker_endpoint) => set({ docker_endpoint })}
+          disabled={disabled}
+        />
+      ),
     },
   };
 
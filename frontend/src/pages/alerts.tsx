@@ -26,20 +26,41 @@ import { ResourceComponents } from "@components/resources";
 import { ResourceSelector } from "@components/resources/common";
 
 const ALERT_TYPES_BY_RESOURCE: { [key: string]: Types.AlertData["type"][] } = {
-  Server: ["ServerUnreachable", "ServerCpu", "ServerMem", "ServerDisk"],
-  Stack: ["StackStateChange", "StackImageUpdateAvailable", "StackAutoUpdated"],
+  Server: [
+    "ServerUnreachable",
+    "ServerCpu",
+    "ServerMem",
+    "ServerSwap",
+    "ServerDisk",
+    "ServerDiskInodes",
+    "ServerDiskForecast",
+    "PeripheryVersionMismatch",
+  ],
+  Stack: [
+    "StackStateChange",
+    "StackImageUpdateAvailable",
+    "StackAutoUpdated",
+    "StackDeployFailed",
+    "ContainerRestartLoop",
+    "ContainerOomKilled",
+  ],
   Deployment: [
     "ContainerStateChange",
     "DeploymentImageUpdateAvailable",
     "DeploymentAutoUpdated",
+    "DeployFailed",
+    "ContainerRestartLoop",
+    "ContainerOomKilled",
   ],
   Build: ["BuildFailed"],
   Repo: ["RepoBuildFailed"],
-  ResourceSync: ["ResourceSyncPendingUpdates"],
+  Procedure: ["ProcedureFailed"],
+  Action: ["ActionFailed"],
+  ResourceSync: ["ResourceSyncPendingUpdates", "ResourceSyncFailed"],
 };
 
 const FALLBACK_ALERT_TYPES = [
-  ...Object.values(ALERT_TYPES_BY_RESOURCE).flat(),
+  ...new Set(Object.values(ALERT_TYPES_BY_RESOURCE).flat()),
   "AwsBuilderTerminationFailed",
 ];
 
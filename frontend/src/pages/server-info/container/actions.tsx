@@ -37,7 +37,13 @@ const DestroyContainer = ({
       name={container_name}
       title="Destroy"
       icon={<Trash className="h-4 w-4" />}
-      onClick={() => destroy({ server: id, container: container_name })}
+      onClick={() =>
+        destroy({
+          server: id,
+          container: container_name,
+          docker_endpoint: container.docker_endpoint,
+        })
+      }
       disabled={isPending}
       loading={isPending || destroying}
     />
@@ -70,7 +76,13 @@ const RestartContainer = ({
       name={container_name}
       title="Restart"
       icon={<RefreshCcw className="h-4 w-4" />}
-      onClick={() => restart({ server: id, container: container_name })}
+      onClick={() =>
+        restart({
+          server: id,
+          container: container_name,
+          docker_endpoint: container.docker_endpoint,
+        })
+      }
       disabled={restartPending}
       loading={restartPending || action_state?.restarting_containers}
     />
@@ -104,7 +116,13 @@ const StartStopContainer = ({
       <ConfirmButton
         title="Start"
         icon={<Play className="h-4 w-4" />}
-        onClick={() => start({ server: id, container: container_name })}
+        onClick={() =>
+          start({
+            server: id,
+            container: container_name,
+            docker_endpoint: container.docker_endpoint,
+          })
+        }
         disabled={startPending}
         loading={startPending || action_state?.starting_containers}
       />
@@ -116,7 +134,13 @@ const StartStopContainer = ({
         name={container_name}
         title="Stop"
         icon={<Square className="h-4 w-4" />}
-        onClick={() => stop({ server: id, container: container_name })}
+        onClick={() =>
+          stop({
+            server: id,
+            container: container_name,
+            docker_endpoint: container.docker_endpoint,
+          })
+        }
         disabled={stopPending}
         loading={stopPending || action_state?.stopping_containers}
       />
@@ -152,7 +176,13 @@ const PauseUnpauseContainer = ({
       <ConfirmButton
         title="Unpause"
         icon={<Play className="h-4 w-4" />}
-        onClick={() => unpause({ server: id, container: container_name })}
+        onClick={() =>
+          unpause({
+            server: id,
+            container: container_name,
+            docker_endpoint: container.docker_endpoint,
+          })
+        }
         disabled={unpausePending}
         loading={unpausePending || action_state?.unpausing_containers}
       />
@@ -164,7 +194,13 @@ const PauseUnpauseContainer = ({
         name={container_name}
         title="Pause"
         icon={<Pause className="h-4 w-4" />}
-        onClick={() => pause({ server: id, container: container_name })}
+        onClick={() =>
+          pause({
+            server: id,
+            container: container_name,
+            docker_endpoint: container.docker_endpoint,
+          })
+        }
         disabled={pausePending}
         loading={pausePending || action_state?.pausing_containers}
       />
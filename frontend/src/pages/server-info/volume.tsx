@@ -16,10 +16,19 @@ import { Types } from "komodo_client";
 import { Badge } from "@ui/badge";
 import { Button } from "@ui/button";
 import { DataTable } from "@ui/data-table";
-import { ChevronLeft, Info, Loader2, SearchCode, Trash } from "lucide-react";
+import {
+  Archive,
+  ChevronLeft,
+  History,
+  Info,
+  Loader2,
+  SearchCode,
+  Trash,
+} from "lucide-react";
 import { useNavigate, useParams } from "react-router-dom";
 import { useState } from "react";
 import { MonacoEditor } from "@components/monaco";
+import { fmt_date_with_minutes } from "@lib/formatting";
 
 export const VolumePage = () => {
   const { type, id, volume } = useParams() as {
@@ -180,6 +189,18 @@ const VolumePageInner = ({
 
       <DockerLabelsSection labels={volume.Labels} />
 
+      <VolumeBackups
+        server={id}
+        volume={volume_name}
+        canExecute={canExecute}
+        running={
+          containers?.some(
+            (container) =>
+              container.state === Types.ContainerStateStatusEnum.Running
+          ) ?? false
+        }
+      />
+
       <Section
         title="Inspect"
         icon={<SearchCode className="w-4 h-4" />}
@@ -200,3 +221,79 @@ const VolumePageInner = ({
     </div>
   );
 };
+
+const VolumeBackups = ({
+  server,
+  volume,
+  canExecute,
+  running,
+}: {
+  server: string;
+  volume: string;
+  canExecute: boolean;
+  running: boolean;
+}) => {
+  const { data: backups, refetch } = useRead("ListDockerVolumeBackups", {
+    server,
+    volume,
+  });
+  const { mutate: backup, isPending: backupPending } = useExecute(
+    "BackupVolume",
+    { onSuccess: () => refetch() }
+  );
+  const { mutate: restore, isPending: restorePending } =
+    useExecute("RestoreVolume");
+  return (
+    <Section
+      title="Backups"
+      icon={<History className="w-4 h-4" />}
+      actions={
+        canExecute && (
+          <ConfirmButton
+            title="Backup Volume"
+            icon={<Archive className="w-4 h-4" />}
+            loading={backupPending}
+            onClick={() => backup({ server, volume })}
+          />
+        )
+      }
+    >
+      <DataTable
+        tableKey="volume-backups"
+        data={backups ?? []}
+        columns={[
+          {
+            accessorKey: "name",
+            header: "Name",
+          },
+          {
+            header: "Created At",
+            cell: ({ row }) =>
+              fmt_date_with_minutes(new Date(row.original.created_at)),
+          },
+          {
+            header: "Size",
+            cell: ({ row }) =>
+              `${(row.original.size / 1024 / 1024).toFixed(2)} MB`,
+          },
+          {
+            header: "Restore",
+            cell: ({ row }) => (
+              <ConfirmButton
+                variant="destructive"
+                title="Restore"
+                icon={<History className="w-4 h-4" />}
+                loading={restorePending}
+                // Containers using the volume must be stopped first
+                disabled={!canExecute || running}
+                onClick={() =>
+                  restore({ server, volume, backup: row.original.name })
+                }
+              />
+            ),
+          },
+        ]}
+      />
+    </Section>
+  );
+};
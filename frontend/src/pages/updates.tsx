@@ -299,9 +299,17 @@ const OPERATIONS_BY_RESOURCE: { [key: string]: Types.Operation[] } = {
     Types.Operation.PruneImages,
     Types.Operation.DeleteVolume,
     Types.Operation.PruneVolumes,
+    Types.Operation.BackupVolume,
+    Types.Operation.RestoreVolume,
     Types.Operation.PruneDockerBuilders,
     Types.Operation.PruneBuildx,
     Types.Operation.PruneSystem,
+    Types.Operation.WriteServerFile,
+    Types.Operation.DeleteServerFile,
+    Types.Operation.RotateServerCertificate,
+    Types.Operation.ResetServerCertificate,
+    Types.Operation.RotateServerPasskey,
+    Types.Operation.ResetServerPasskey,
   ],
   Stack: [
     Types.Operation.CreateStack,
@@ -322,6 +330,9 @@ const OPERATIONS_BY_RESOURCE: { [key: string]: Types.Operation[] } = {
     Types.Operation.PauseStackService,
     Types.Operation.UnpauseStackService,
     Types.Operation.StopStackService,
+    Types.Operation.ExecStackService,
+    Types.Operation.DownloadFromStackService,
+    Types.Operation.UploadToStackService,
   ],
   Deployment: [
     Types.Operation.CreateDeployment,
@@ -334,6 +345,9 @@ const OPERATIONS_BY_RESOURCE: { [key: string]: Types.Operation[] } = {
     Types.Operation.UnpauseDeployment,
     Types.Operation.StopDeployment,
     Types.Operation.DestroyDeployment,
+    Types.Operation.ExecDeployment,
+    Types.Operation.DownloadFromDeployment,
+    Types.Operation.UploadToDeployment,
     Types.Operation.RenameDeployment,
   ],
   Build: [